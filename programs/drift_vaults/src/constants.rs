@@ -3,3 +3,16 @@ pub const TIME_FOR_LIQUIDATION: i64 = ONE_HOUR;
 // TIME
 pub const ONE_HOUR: i64 = 60 * 60;
 pub const ONE_DAY: i64 = ONE_HOUR * 24;
+
+// FEES
+pub const BPS_PRECISION: u64 = 10_000;
+/// Max entry fee and max combined exit + early exit fee
+pub const MAX_LOAD_FEE_BPS: u16 = 1_000;
+
+// PAUSE
+/// Max time withdraws can be paused for, and the cooldown before they can be paused again
+pub const MAX_WITHDRAW_PAUSE: i64 = ONE_DAY * 7;
+
+// SHARES
+/// Shares of the first deposit into an empty vault locked as user shares no depositor owns
+pub const VAULT_DEAD_SHARES: u128 = 1_000;
@@ -1,4 +1,5 @@
 use anchor_lang::prelude::*;
+use drift::state::user::User;
 
 pub trait InitializeUserCPI {
     fn drift_initialize_user(&self, name: [u8; 32], bump: u8) -> Result<()>;
@@ -14,6 +15,10 @@ pub trait WithdrawCPI {
     fn drift_withdraw(&self, amount: u64) -> Result<()>;
 }
 
+pub trait DeleteUserCPI {
+    fn drift_delete_user(&self) -> Result<()>;
+}
+
 pub trait UpdateUserDelegateCPI {
     fn drift_update_user_delegate(&self, delegate: Pubkey) -> Result<()>;
 }
@@ -22,6 +27,24 @@ pub trait UpdateUserReduceOnlyCPI {
     fn drift_update_user_reduce_only(&self, reduce_only: bool) -> Result<()>;
 }
 
+/// Vault sub-accounts aren't part of the instruction accounts, so these take the sub-account found in the remaining
+/// accounts
+pub trait UpdateSubAccountDelegateCPI<'a> {
+    fn drift_update_sub_account_delegate(
+        &self,
+        sub_account: &AccountLoader<'a, User>,
+        delegate: Pubkey,
+    ) -> Result<()>;
+}
+
+pub trait UpdateSubAccountReduceOnlyCPI<'a> {
+    fn drift_update_sub_account_reduce_only(
+        &self,
+        sub_account: &AccountLoader<'a, User>,
+        reduce_only: bool,
+    ) -> Result<()>;
+}
+
 pub trait UpdateUserMarginTradingEnabledCPI {
     fn drift_update_user_margin_trading_enabled(&self, enabled: bool) -> Result<()>;
 }
@@ -48,3 +71,7 @@ pub trait CancelRequestRemoveInsuranceFundStakeCPI {
 pub trait RemoveInsuranceFundStakeCPI {
     fn drift_remove_insurance_fund_stake(&self, market_index: u16) -> Result<()>;
 }
+
+pub trait TransferDepositCPI {
+    fn drift_transfer_deposit(&self, market_index: u16, amount: u64) -> Result<()>;
+}
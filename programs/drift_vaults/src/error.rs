@@ -55,6 +55,54 @@ pub enum ErrorCode {
     VaultProtocolMissing,
     #[msg("InvalidTokenization")]
     InvalidTokenization,
+    #[msg("ManagerPositionMissing")]
+    ManagerPositionMissing,
+    #[msg("InvalidFeeRecipients")]
+    InvalidFeeRecipients,
+    #[msg("VaultReferrerMissing")]
+    VaultReferrerMissing,
+    #[msg("InvalidShareClass")]
+    InvalidShareClass,
+    #[msg("InvalidFeeOverride")]
+    InvalidFeeOverride,
+    #[msg("VaultShutdown")]
+    VaultShutdown,
+    #[msg("VaultPositionsNotFlat")]
+    VaultPositionsNotFlat,
+    #[msg("CannotCloseAccount")]
+    CannotCloseAccount,
+    #[msg("VaultPaused")]
+    VaultPaused,
+    #[msg("InvalidWithdrawGate")]
+    InvalidWithdrawGate,
+    #[msg("WithdrawGated")]
+    WithdrawGated,
+    #[msg("SlippageExceeded")]
+    SlippageExceeded,
+    #[msg("ManagerBelowMinShare")]
+    ManagerBelowMinShare,
+    #[msg("InvalidDrawdownGuard")]
+    InvalidDrawdownGuard,
+    #[msg("DrawdownGuardCooldown")]
+    DrawdownGuardCooldown,
+    #[msg("InvalidRiskLimits")]
+    InvalidRiskLimits,
+    #[msg("InvalidDelegateTimelock")]
+    InvalidDelegateTimelock,
+    #[msg("DelegateTimelocked")]
+    DelegateTimelocked,
+    #[msg("SubAccountsMissing")]
+    SubAccountsMissing,
+    #[msg("InvalidSubAccount")]
+    InvalidSubAccount,
+    #[msg("InvalidVaultAllocation")]
+    InvalidVaultAllocation,
+    #[msg("VaultAllocationsMissing")]
+    VaultAllocationsMissing,
+    #[msg("InvalidVaultDepositorTransfer")]
+    InvalidVaultDepositorTransfer,
+    #[msg("InvalidVaultRole")]
+    InvalidVaultRole,
 }
 
 impl From<DriftErrorCode> for ErrorCode {
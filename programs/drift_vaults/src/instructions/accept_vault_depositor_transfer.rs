@@ -0,0 +1,114 @@
+use anchor_lang::prelude::*;
+use drift::math::safe_math::SafeMath;
+
+use crate::constraints::is_vault_for_vault_depositor;
+use crate::error::ErrorCode;
+use crate::events::VaultDepositorTransferRecord;
+use crate::state::{Vault, VaultDepositor, VaultDepositorBase, VaultDepositorTransfer};
+use crate::{validate, Size};
+
+/// Moves a depositor's position, including any pending withdraw request, to a new [`VaultDepositor`] owned by the new
+/// authority and closes the old one, along with its [`DepositorOperator`](crate::DepositorOperator) if it has one. The
+/// new authority can't already have a depositor in the vault.
+pub fn accept_vault_depositor_transfer(ctx: Context<AcceptVaultDepositorTransfer>) -> Result<()> {
+    let clock = &Clock::get()?;
+    let vault = ctx.accounts.vault.load()?;
+    let old_vault_depositor = ctx.accounts.vault_depositor.load()?;
+
+    // the liquidation delegate is recorded by authority, so the depositor must keep it until the liquidation ends
+    validate!(
+        !vault.in_liquidation() || vault.liquidation_delegate != old_vault_depositor.authority,
+        ErrorCode::OngoingLiquidation,
+        "vault depositor is liquidating the vault"
+    )?;
+
+    // in a permissioned vault the manager decides who can hold a depositor
+    if vault.permissioned {
+        validate!(
+            ctx.accounts
+                .manager
+                .as_ref()
+                .map_or(false, |manager| manager.key() == vault.manager),
+            ErrorCode::PermissionedVault,
+            "Vault depositor can only be transferred with the vault manager's signature"
+        )?;
+    }
+
+    let mut new_vault_depositor = ctx.accounts.new_vault_depositor.load_init()?;
+    *new_vault_depositor = old_vault_depositor.with_authority(
+        ctx.accounts.new_vault_depositor.key(),
+        ctx.accounts.new_authority.key(),
+        clock.unix_timestamp,
+    );
+
+    emit!(VaultDepositorTransferRecord {
+        ts: clock.unix_timestamp,
+        vault: vault.pubkey,
+        vault_depositor_before: old_vault_depositor.pubkey,
+        vault_depositor_after: new_vault_depositor.pubkey,
+        authority_before: old_vault_depositor.authority,
+        authority_after: new_vault_depositor.authority,
+        vault_shares: new_vault_depositor.get_vault_shares(),
+    });
+
+    // the operator was named by the old authority, so it doesn't act for the new one
+    let depositor_operator = &ctx.accounts.depositor_operator;
+    if depositor_operator.owner == ctx.program_id {
+        let authority = &ctx.accounts.authority;
+        let lamports = authority
+            .lamports()
+            .safe_add(depositor_operator.lamports())?;
+        **authority.try_borrow_mut_lamports()? = lamports;
+        **depositor_operator.try_borrow_mut_lamports()? = 0;
+        depositor_operator.assign(&System::id());
+        depositor_operator.realloc(0, false)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AcceptVaultDepositorTransfer<'info> {
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        close = authority,
+        constraint = is_vault_for_vault_depositor(&vault_depositor, &vault)?,
+    )]
+    pub vault_depositor: AccountLoader<'info, VaultDepositor>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"vault_depositor_transfer", vault_depositor.key().as_ref()],
+        bump,
+        constraint = vault_depositor_transfer.load()?.new_authority.eq(&new_authority.key()),
+    )]
+    pub vault_depositor_transfer: AccountLoader<'info, VaultDepositorTransfer>,
+    #[account(
+        init,
+        seeds = [b"vault_depositor", vault.key().as_ref(), new_authority.key().as_ref()],
+        space = VaultDepositor::SIZE,
+        bump,
+        payer = new_authority
+    )]
+    pub new_vault_depositor: AccountLoader<'info, VaultDepositor>,
+    #[account(
+        mut,
+        seeds = [b"depositor_operator", vault_depositor.key().as_ref()],
+        bump,
+    )]
+    /// CHECK: the old depositor's [`DepositorOperator`](crate::DepositorOperator) pda, closed if it was initialized
+    pub depositor_operator: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = vault_depositor.load()?.authority.eq(&authority.key()),
+    )]
+    /// CHECK: the old depositor's authority, refunded the closed accounts' rent
+    pub authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub new_authority: Signer<'info>,
+    /// Pass the vault program id unless the vault is permissioned
+    pub manager: Option<Signer<'info>>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::{
+    is_manager_for_vault, is_vault_for_vault_allocations, is_vault_for_vault_depositor,
+};
+use crate::error::ErrorCode;
+use crate::state::{Vault, VaultAllocations, VaultCompanion, VaultDepositor};
+use crate::validate;
+
+/// Registers the vault's [`VaultDepositor`] in a child vault so the manager can allocate to it. The depositor is
+/// initialized beforehand with the vault as authority, by the child's manager if the child is permissioned.
+pub fn add_vault_allocation(ctx: Context<AddVaultAllocation>) -> Result<()> {
+    let vault = ctx.accounts.vault.load()?;
+    let mut child_vault = ctx.accounts.child_vault.load_mut()?;
+    let mut vault_allocations = ctx.accounts.vault_allocations.load_mut()?;
+
+    validate!(
+        child_vault.pubkey != vault.pubkey,
+        ErrorCode::InvalidVaultAllocation,
+        "vault can't allocate to itself"
+    )?;
+
+    validate!(
+        child_vault.spot_market_index == vault.spot_market_index,
+        ErrorCode::InvalidVaultAllocation,
+        "child vault spot market {} must match the vault's {}",
+        child_vault.spot_market_index,
+        vault.spot_market_index
+    )?;
+
+    // the child's equity is valued without its own allocations
+    validate!(
+        !child_vault.has_companion(VaultCompanion::VaultAllocations),
+        ErrorCode::InvalidVaultAllocation,
+        "child vault allocates to other vaults"
+    )?;
+
+    vault_allocations.add(
+        ctx.accounts.child_vault.key(),
+        ctx.accounts.child_vault_depositor.key(),
+    )?;
+
+    // so the child can't initialize allocations of its own later
+    child_vault.add_companion(VaultCompanion::ParentVault);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct AddVaultAllocation<'info> {
+    #[account(
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_vault_for_vault_allocations(&vault_allocations, &vault)?,
+    )]
+    pub vault_allocations: AccountLoader<'info, VaultAllocations>,
+    pub manager: Signer<'info>,
+    #[account(mut)]
+    pub child_vault: AccountLoader<'info, Vault>,
+    #[account(
+        seeds = [b"vault_depositor", child_vault.key().as_ref(), vault.key().as_ref()],
+        bump,
+        constraint = is_vault_for_vault_depositor(&child_vault_depositor, &child_vault)?,
+    )]
+    pub child_vault_depositor: AccountLoader<'info, VaultDepositor>,
+}
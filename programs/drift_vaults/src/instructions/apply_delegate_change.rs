@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+use drift::cpi::accounts::UpdateUser;
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{
+    is_sub_account_for_vault, is_user_for_vault, is_vault_for_delegate_timelock,
+};
+use crate::drift_cpi::UpdateUserDelegateCPI;
+use crate::error::ErrorCode;
+use crate::state::{DelegateTimelock, Vault};
+use crate::{declare_vault_seeds, implement_update_user_delegate_cpi, validate};
+
+/// Permissionless crank that applies a queued delegate change once its delay has passed. `drift_user` must be the
+/// vault drift user the change is queued for
+pub fn apply_delegate_change<'info>(
+    ctx: Context<'_, '_, '_, 'info, ApplyDelegateChange<'info>>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let mut vault = ctx.accounts.vault.load_mut()?;
+
+    let (delegate, sub_account_id) = ctx
+        .accounts
+        .delegate_timelock
+        .load_mut()?
+        .apply(&vault, now)?;
+
+    validate!(
+        ctx.accounts.drift_user.load()?.sub_account_id == sub_account_id,
+        ErrorCode::InvalidSubAccount,
+        "delegate change is queued for sub-account {}",
+        sub_account_id
+    )?;
+
+    // the liquidation delegate keeps the primary drift user until the liquidation ends and reset_delegate restores
+    // this one
+    let mut update_drift_delegate = true;
+    if sub_account_id == 0 {
+        vault.delegate = delegate;
+        update_drift_delegate = !vault.in_liquidation();
+    }
+
+    drop(vault);
+
+    if update_drift_delegate {
+        ctx.drift_update_user_delegate(delegate)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ApplyDelegateChange<'info> {
+    #[account(mut)]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_vault_for_delegate_timelock(&delegate_timelock, &vault)?,
+    )]
+    pub delegate_timelock: AccountLoader<'info, DelegateTimelock>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+            || is_sub_account_for_vault(&vault, &drift_user)?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    pub drift_program: Program<'info, Drift>,
+}
+
+impl<'info> UpdateUserDelegateCPI for Context<'_, '_, '_, 'info, ApplyDelegateChange<'info>> {
+    fn drift_update_user_delegate(&self, delegate: Pubkey) -> Result<()> {
+        let sub_account_id = self.accounts.drift_user.load()?.sub_account_id;
+        implement_update_user_delegate_cpi!(self, delegate, sub_account_id);
+        Ok(())
+    }
+}
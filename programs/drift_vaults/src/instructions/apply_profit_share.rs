@@ -4,18 +4,32 @@ use drift::program::Drift;
 use drift::state::user::User;
 
 use crate::constraints::{
-    is_delegate_for_vault, is_manager_for_vault, is_user_for_vault, is_user_stats_for_vault,
-    is_vault_for_vault_depositor,
+    is_delegate_for_vault, is_keeper_for_vault, is_manager_for_vault, is_user_for_vault,
+    is_user_stats_for_vault, is_vault_for_vault_depositor,
+};
+use crate::error::ErrorCode;
+use crate::state::{
+    ShareClassProvider, SubAccountProvider, Vault, VaultAllocationsProvider, VaultDepositorBase,
+    VaultProtocolProvider, VaultReferrerProvider, VaultRolesProvider,
 };
-use crate::state::{Vault, VaultProtocolProvider};
-use crate::AccountMapProvider;
 use crate::VaultDepositor;
+use crate::{validate, AccountMapProvider};
 
 pub fn apply_profit_share<'c: 'info, 'info>(
     ctx: Context<'_, '_, 'c, 'info, ApplyProfitShare<'info>>,
 ) -> Result<()> {
     let clock = &Clock::get()?;
 
+    // if the vault has [`VaultRoles`], they can be anywhere in the rem accts before any [`VaultProtocol`].
+    let vault_roles = ctx.vault_roles(&*ctx.accounts.vault.load()?);
+    validate!(
+        is_manager_for_vault(&ctx.accounts.vault, &ctx.accounts.manager)?
+            || is_delegate_for_vault(&ctx.accounts.vault, &ctx.accounts.manager)?
+            || is_keeper_for_vault(&ctx.accounts.vault, &vault_roles, &ctx.accounts.manager)?,
+        ErrorCode::InvalidVaultRole,
+        "only the vault manager, delegate or keeper can apply profit share"
+    )?;
+
     let mut vault = ctx.accounts.vault.load_mut()?;
     let mut vault_depositor = ctx.accounts.vault_depositor.load_mut()?;
 
@@ -24,6 +38,16 @@ pub fn apply_profit_share<'c: 'info, 'info>(
     vault.validate_vault_protocol(&vp)?;
     let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
 
+    // if the depositor was referred, its [`VaultReferrer`] can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut vr = ctx.vault_referrer(&vault_depositor);
+    vault_depositor.validate_vault_referrer(&vr)?;
+    let mut vr = vr.as_mut().map(|vr| vr.load_mut()).transpose()?;
+
+    // if the depositor is in a [`ShareClass`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut sc = ctx.share_class(&vault_depositor);
+    vault_depositor.validate_share_class(&sc)?;
+    let mut sc = sc.as_mut().map(|sc| sc.load_mut()).transpose()?;
+
     let user = ctx.accounts.drift_user.load()?;
     let spot_market_index = vault.spot_market_index;
 
@@ -33,26 +57,47 @@ pub fn apply_profit_share<'c: 'info, 'info>(
         mut oracle_map,
     } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
 
-    let vault_equity =
-        vault.calculate_equity(&user, &perp_market_map, &spot_market_map, &mut oracle_map)?;
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
+
+    vault_depositor.settle_fees(
+        sc.as_deref_mut(),
+        vr.as_deref_mut(),
+        vault_equity,
+        &mut vault,
+        &mut vp,
+        clock.unix_timestamp,
+    )?;
 
+    let class_shares_before = vault_depositor.get_vault_shares();
     vault_depositor.apply_profit_share(vault_equity, &mut vault, &mut vp)?;
 
+    if let Some(sc) = sc.as_mut() {
+        sc.update_user_shares(class_shares_before, vault_depositor.get_vault_shares())?;
+    }
+
     Ok(())
 }
 
 #[derive(Accounts)]
 pub struct ApplyProfitShare<'info> {
-    #[account(
-        mut,
-        constraint = is_manager_for_vault(&vault, &manager)? || is_delegate_for_vault(&vault, &manager)?
-    )]
+    #[account(mut)]
     pub vault: AccountLoader<'info, Vault>,
     #[account(
         mut,
         constraint = is_vault_for_vault_depositor(&vault_depositor, &vault)?
     )]
     pub vault_depositor: AccountLoader<'info, VaultDepositor>,
+    /// The vault manager, delegate or keeper, checked in the handler
     pub manager: Signer<'info>,
     #[account(
         mut,
@@ -3,6 +3,7 @@ use drift::instructions::optional_accounts::AccountMaps;
 use drift::state::user::User;
 
 use crate::constraints::{is_user_for_vault, is_vault_for_vault_depositor};
+use crate::state::{SubAccountProvider, VaultAllocationsProvider};
 use crate::{AccountMapProvider, Vault, VaultDepositor, VaultProtocolProvider};
 
 pub fn apply_rebase<'c: 'info, 'info>(
@@ -27,8 +28,17 @@ pub fn apply_rebase<'c: 'info, 'info>(
         mut oracle_map,
     } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
 
-    let vault_equity =
-        vault.calculate_equity(&user, &perp_market_map, &spot_market_map, &mut oracle_map)?;
+    let sub_accounts = ctx.sub_accounts_in_remaining_accounts(&vault)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
 
     vault_depositor.apply_rebase(&mut vault, &mut vp, vault_equity)?;
 
@@ -4,6 +4,7 @@ use drift::state::user::User;
 
 use crate::constraints::{is_tokenized_depositor_for_vault, is_user_for_vault};
 use crate::state::traits::VaultDepositorBase;
+use crate::state::{SubAccountProvider, VaultAllocationsProvider};
 use crate::{AccountMapProvider, TokenizedVaultDepositor, Vault, VaultProtocolProvider};
 
 pub fn apply_rebase_tokenized_depositor<'c: 'info, 'info>(
@@ -27,8 +28,17 @@ pub fn apply_rebase_tokenized_depositor<'c: 'info, 'info>(
         mut oracle_map,
     } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
 
-    let vault_equity =
-        vault.calculate_equity(&user, &perp_market_map, &spot_market_map, &mut oracle_map)?;
+    let sub_accounts = ctx.sub_accounts_in_remaining_accounts(&vault)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
 
     ctx.accounts
         .tokenized_vault_depositor
@@ -0,0 +1,30 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::{is_manager_for_vault, is_vault_for_delegate_timelock};
+use crate::state::{DelegateTimelock, Vault};
+
+pub fn cancel_delegate_change(ctx: Context<CancelDelegateChange>) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let vault = ctx.accounts.vault.load()?;
+
+    ctx.accounts
+        .delegate_timelock
+        .load_mut()?
+        .cancel(&vault, now)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelDelegateChange<'info> {
+    #[account(
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_vault_for_delegate_timelock(&delegate_timelock, &vault)?,
+    )]
+    pub delegate_timelock: AccountLoader<'info, DelegateTimelock>,
+    pub manager: Signer<'info>,
+}
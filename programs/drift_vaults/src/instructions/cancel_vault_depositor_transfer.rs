@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::is_authority_for_vault_depositor;
+use crate::state::{VaultDepositor, VaultDepositorTransfer};
+
+pub fn cancel_vault_depositor_transfer(_ctx: Context<CancelVaultDepositorTransfer>) -> Result<()> {
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CancelVaultDepositorTransfer<'info> {
+    #[account(
+        constraint = is_authority_for_vault_depositor(&vault_depositor, &authority)?,
+    )]
+    pub vault_depositor: AccountLoader<'info, VaultDepositor>,
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"vault_depositor_transfer", vault_depositor.key().as_ref()],
+        bump,
+    )]
+    pub vault_depositor_transfer: AccountLoader<'info, VaultDepositorTransfer>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
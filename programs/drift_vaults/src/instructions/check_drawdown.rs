@@ -0,0 +1,107 @@
+use anchor_lang::prelude::*;
+use drift::cpi::accounts::UpdateUser;
+use drift::instructions::optional_accounts::AccountMaps;
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{is_user_for_vault, is_user_stats_for_vault, is_vault_for_drawdown_guard};
+use crate::drift_cpi::{UpdateSubAccountReduceOnlyCPI, UpdateUserReduceOnlyCPI};
+use crate::state::{DrawdownGuard, SubAccountProvider, Vault, VaultAllocationsProvider};
+use crate::AccountMapProvider;
+use crate::{declare_vault_seeds, implement_update_user_reduce_only_cpi};
+
+/// Permissionless crank that trips the [`DrawdownGuard`] if the vault share price has drawn down too far
+pub fn check_drawdown<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, CheckDrawdown<'info>>,
+) -> Result<()> {
+    let clock = &Clock::get()?;
+    let now = clock.unix_timestamp;
+
+    let mut vault = ctx.accounts.vault.load_mut()?;
+    let mut drawdown_guard = ctx.accounts.drawdown_guard.load_mut()?;
+
+    let user = ctx.accounts.drift_user.load()?;
+
+    let AccountMaps {
+        perp_market_map,
+        spot_market_map,
+        mut oracle_map,
+    } = ctx.load_maps(clock.slot, None, false)?;
+
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
+
+    let tripped = drawdown_guard.update(&vault, vault_equity, now)?;
+
+    if !tripped {
+        return Ok(());
+    }
+
+    msg!("vault drawdown guard tripped");
+
+    vault.set_breaker_paused(drawdown_guard.pubkey, now)?;
+
+    drop(vault);
+    drop(drawdown_guard);
+    drop(user);
+
+    ctx.drift_update_user_reduce_only(true)?;
+    for sub_account in sub_accounts.iter() {
+        ctx.drift_update_sub_account_reduce_only(sub_account, true)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CheckDrawdown<'info> {
+    #[account(mut)]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_vault_for_drawdown_guard(&drawdown_guard, &vault)?,
+    )]
+    pub drawdown_guard: AccountLoader<'info, DrawdownGuard>,
+    #[account(
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked by constraint
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    pub drift_program: Program<'info, Drift>,
+}
+
+impl<'info> UpdateUserReduceOnlyCPI for Context<'_, '_, '_, 'info, CheckDrawdown<'info>> {
+    fn drift_update_user_reduce_only(&self, reduce_only: bool) -> Result<()> {
+        implement_update_user_reduce_only_cpi!(self, reduce_only);
+        Ok(())
+    }
+}
+
+impl<'c: 'info, 'info> UpdateSubAccountReduceOnlyCPI<'c>
+    for Context<'_, '_, 'c, 'info, CheckDrawdown<'info>>
+{
+    fn drift_update_sub_account_reduce_only(
+        &self,
+        sub_account: &AccountLoader<'c, User>,
+        reduce_only: bool,
+    ) -> Result<()> {
+        let sub_account_id = sub_account.load()?.sub_account_id;
+        implement_update_user_reduce_only_cpi!(self, reduce_only, sub_account_id, sub_account);
+        Ok(())
+    }
+}
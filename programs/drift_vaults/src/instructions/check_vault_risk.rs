@@ -0,0 +1,172 @@
+use anchor_lang::prelude::*;
+use drift::cpi::accounts::UpdateUser;
+use drift::instructions::optional_accounts::AccountMaps;
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{is_user_for_vault, is_user_stats_for_vault, is_vault_for_risk_limits};
+use crate::drift_cpi::{
+    UpdateSubAccountDelegateCPI, UpdateSubAccountReduceOnlyCPI, UpdateUserDelegateCPI,
+    UpdateUserReduceOnlyCPI,
+};
+use crate::events::RiskLimitRecord;
+use crate::state::{RiskLimits, SubAccountProvider, Vault};
+use crate::AccountMapProvider;
+use crate::{
+    declare_vault_seeds, implement_update_user_delegate_cpi, implement_update_user_reduce_only_cpi,
+};
+
+/// Permissionless crank that restricts the vault's drift user and sub-accounts if any of them breaks the vault's
+/// [`RiskLimits`]
+pub fn check_vault_risk<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, CheckVaultRisk<'info>>,
+) -> Result<()> {
+    let clock = &Clock::get()?;
+    let now = clock.unix_timestamp;
+
+    let mut vault = ctx.accounts.vault.load_mut()?;
+    let mut risk_limits = ctx.accounts.risk_limits.load_mut()?;
+
+    // a shut down or liquidating vault is already reduce-only and out of the delegate's hands
+    if risk_limits.is_breached() || vault.shutdown || vault.in_liquidation() {
+        return Ok(());
+    }
+
+    let user = ctx.accounts.drift_user.load()?;
+
+    let AccountMaps {
+        perp_market_map,
+        spot_market_map,
+        mut oracle_map,
+    } = ctx.load_maps(clock.slot, None, false)?;
+
+    // every sub-account is checked and restricted along with the primary user, so they must all be in the rem accts
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+
+    let mut violation = risk_limits.find_violation(
+        &vault,
+        &user,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+    )?;
+    for sub_account in sub_accounts.iter() {
+        if violation.is_some() {
+            break;
+        }
+
+        violation = risk_limits.find_violation(
+            &vault,
+            &sub_account.load()?,
+            &perp_market_map,
+            &spot_market_map,
+            &mut oracle_map,
+        )?;
+    }
+
+    let (violation, market_index, value) = match violation {
+        Some(violation) => violation,
+        None => return Ok(()),
+    };
+
+    msg!("vault risk limit breached: {:?}", violation);
+
+    risk_limits.breached_ts = now;
+    let revoke_delegate = risk_limits.revoke_delegate;
+
+    if revoke_delegate {
+        vault.delegate = Pubkey::default();
+    }
+
+    emit!(RiskLimitRecord {
+        ts: now,
+        vault: vault.pubkey,
+        violation,
+        market_index,
+        value,
+        revoked_delegate: revoke_delegate,
+    });
+
+    drop(vault);
+    drop(risk_limits);
+    drop(user);
+
+    if revoke_delegate {
+        ctx.drift_update_user_delegate(Pubkey::default())?;
+        for sub_account in sub_accounts.iter() {
+            ctx.drift_update_sub_account_delegate(sub_account, Pubkey::default())?;
+        }
+    } else {
+        ctx.drift_update_user_reduce_only(true)?;
+        for sub_account in sub_accounts.iter() {
+            ctx.drift_update_sub_account_reduce_only(sub_account, true)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CheckVaultRisk<'info> {
+    #[account(mut)]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_vault_for_risk_limits(&risk_limits, &vault)?,
+    )]
+    pub risk_limits: AccountLoader<'info, RiskLimits>,
+    #[account(
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked by constraint
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    pub drift_program: Program<'info, Drift>,
+}
+
+impl<'info> UpdateUserDelegateCPI for Context<'_, '_, '_, 'info, CheckVaultRisk<'info>> {
+    fn drift_update_user_delegate(&self, delegate: Pubkey) -> Result<()> {
+        implement_update_user_delegate_cpi!(self, delegate);
+        Ok(())
+    }
+}
+
+impl<'info> UpdateUserReduceOnlyCPI for Context<'_, '_, '_, 'info, CheckVaultRisk<'info>> {
+    fn drift_update_user_reduce_only(&self, reduce_only: bool) -> Result<()> {
+        implement_update_user_reduce_only_cpi!(self, reduce_only);
+        Ok(())
+    }
+}
+
+impl<'c: 'info, 'info> UpdateSubAccountDelegateCPI<'c>
+    for Context<'_, '_, 'c, 'info, CheckVaultRisk<'info>>
+{
+    fn drift_update_sub_account_delegate(
+        &self,
+        sub_account: &AccountLoader<'c, User>,
+        delegate: Pubkey,
+    ) -> Result<()> {
+        let sub_account_id = sub_account.load()?.sub_account_id;
+        implement_update_user_delegate_cpi!(self, delegate, sub_account_id, sub_account);
+        Ok(())
+    }
+}
+
+impl<'c: 'info, 'info> UpdateSubAccountReduceOnlyCPI<'c>
+    for Context<'_, '_, 'c, 'info, CheckVaultRisk<'info>>
+{
+    fn drift_update_sub_account_reduce_only(
+        &self,
+        sub_account: &AccountLoader<'c, User>,
+        reduce_only: bool,
+    ) -> Result<()> {
+        let sub_account_id = sub_account.load()?.sub_account_id;
+        implement_update_user_reduce_only_cpi!(self, reduce_only, sub_account_id, sub_account);
+        Ok(())
+    }
+}
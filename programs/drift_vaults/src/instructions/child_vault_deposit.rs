@@ -0,0 +1,190 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use drift::cpi::accounts::Withdraw as DriftWithdraw;
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{
+    is_manager_for_vault, is_user_for_vault, is_user_stats_for_vault,
+    is_vault_for_vault_allocations,
+};
+use crate::drift_cpi::WithdrawCPI;
+use crate::error::ErrorCode;
+use crate::program::DriftVaults;
+use crate::state::{Vault, VaultAllocations};
+use crate::vault_cpi::{invoke_vault_instruction, VaultDepositCPI};
+use crate::{declare_vault_seeds, validate};
+
+/// Moves `amount` out of the vault's drift user and deposits it into a child vault. Remaining accounts are passed to
+/// both the drift withdraw and the child's deposit, so they must be the child's deposit remaining accounts plus any
+/// market the vault's drift user needs for its margin check.
+pub fn child_vault_deposit<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ChildVaultDeposit<'info>>,
+    amount: u64,
+    min_shares_out: Option<u128>,
+) -> Result<()> {
+    let vault = ctx.accounts.vault.load()?;
+
+    validate!(
+        !vault.shutdown,
+        ErrorCode::VaultShutdown,
+        "vault is shut down"
+    )?;
+
+    validate!(
+        !vault.in_liquidation(),
+        ErrorCode::OngoingLiquidation,
+        "vault is in liquidation"
+    )?;
+
+    ctx.accounts.vault_allocations.load()?.validate_allocation(
+        &ctx.accounts.child_vault.key(),
+        &ctx.accounts.child_vault_depositor.key(),
+    )?;
+
+    drop(vault);
+
+    ctx.drift_withdraw(amount)?;
+
+    ctx.vault_deposit(amount, min_shares_out)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ChildVaultDeposit<'info> {
+    #[account(
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    pub manager: Signer<'info>,
+    #[account(
+        constraint = is_vault_for_vault_allocations(&vault_allocations, &vault)?,
+    )]
+    pub vault_allocations: AccountLoader<'info, VaultAllocations>,
+    #[account(
+        mut,
+        seeds = [b"vault_token_account".as_ref(), vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    /// CHECK: checked in drift cpi
+    pub drift_state: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::mint = vault_token_account.mint
+    )]
+    pub drift_spot_market_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: checked in drift cpi
+    pub drift_signer: AccountInfo<'info>,
+    #[account(mut)]
+    pub child_vault: AccountLoader<'info, Vault>,
+    #[account(mut)]
+    /// CHECK: checked against vault_allocations
+    pub child_vault_depositor: AccountInfo<'info>,
+    #[account(mut)]
+    /// CHECK: checked in child vault cpi
+    pub child_vault_token_account: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_stats_for_vault(&child_vault, &child_drift_user_stats)?
+    )]
+    /// CHECK: checked in child vault cpi
+    pub child_drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&child_vault, &child_drift_user.key())?
+    )]
+    /// CHECK: checked in child vault cpi
+    pub child_drift_user: AccountInfo<'info>,
+    pub drift_program: Program<'info, Drift>,
+    pub drift_vaults_program: Program<'info, DriftVaults>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> WithdrawCPI for Context<'_, '_, '_, 'info, ChildVaultDeposit<'info>> {
+    fn drift_withdraw(&self, amount: u64) -> Result<()> {
+        declare_vault_seeds!(self.accounts.vault, seeds);
+        let spot_market_index = self.accounts.vault.load()?.spot_market_index;
+
+        let cpi_accounts = DriftWithdraw {
+            state: self.accounts.drift_state.to_account_info().clone(),
+            user: self.accounts.drift_user.to_account_info().clone(),
+            user_stats: self.accounts.drift_user_stats.to_account_info().clone(),
+            authority: self.accounts.vault.to_account_info().clone(),
+            spot_market_vault: self
+                .accounts
+                .drift_spot_market_vault
+                .to_account_info()
+                .clone(),
+            drift_signer: self.accounts.drift_signer.to_account_info().clone(),
+            user_token_account: self.accounts.vault_token_account.to_account_info().clone(),
+            token_program: self.accounts.token_program.to_account_info().clone(),
+        };
+
+        let drift_program = self.accounts.drift_program.to_account_info().clone();
+        let cpi_context = CpiContext::new_with_signer(drift_program, cpi_accounts, seeds)
+            .with_remaining_accounts(self.remaining_accounts.into());
+        drift::cpi::withdraw(cpi_context, spot_market_index, amount, false)?;
+
+        Ok(())
+    }
+}
+
+impl<'info> VaultDepositCPI for Context<'_, '_, '_, 'info, ChildVaultDeposit<'info>> {
+    fn vault_deposit(&self, amount: u64, min_shares_out: Option<u128>) -> Result<()> {
+        declare_vault_seeds!(self.accounts.vault, seeds);
+
+        let accounts = crate::accounts::Deposit {
+            vault: self.accounts.child_vault.key(),
+            vault_depositor: self.accounts.child_vault_depositor.key(),
+            authority: self.accounts.vault.key(),
+            vault_token_account: self.accounts.child_vault_token_account.key(),
+            drift_user_stats: self.accounts.child_drift_user_stats.key(),
+            drift_user: self.accounts.child_drift_user.key(),
+            drift_state: self.accounts.drift_state.key(),
+            drift_spot_market_vault: self.accounts.drift_spot_market_vault.key(),
+            user_token_account: self.accounts.vault_token_account.key(),
+            drift_program: self.accounts.drift_program.key(),
+            token_program: self.accounts.token_program.key(),
+        };
+
+        let account_infos = vec![
+            self.accounts.child_vault.to_account_info(),
+            self.accounts.child_vault_depositor.clone(),
+            self.accounts.vault.to_account_info(),
+            self.accounts.child_vault_token_account.clone(),
+            self.accounts.child_drift_user_stats.clone(),
+            self.accounts.child_drift_user.clone(),
+            self.accounts.drift_state.clone(),
+            self.accounts.drift_spot_market_vault.to_account_info(),
+            self.accounts.vault_token_account.to_account_info(),
+            self.accounts.drift_program.to_account_info(),
+            self.accounts.token_program.to_account_info(),
+            self.accounts.drift_vaults_program.to_account_info(),
+        ];
+
+        invoke_vault_instruction(
+            &accounts,
+            &crate::instruction::DepositWithMinShares {
+                amount,
+                min_shares_out: min_shares_out.unwrap_or(0),
+            },
+            account_infos,
+            self.remaining_accounts,
+            seeds,
+        )
+    }
+}
@@ -0,0 +1,101 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::{
+    is_manager_for_vault, is_user_for_vault, is_user_stats_for_vault,
+    is_vault_for_vault_allocations,
+};
+use crate::declare_vault_seeds;
+use crate::program::DriftVaults;
+use crate::state::{Vault, VaultAllocations};
+use crate::vault_cpi::{invoke_vault_instruction, VaultRequestWithdrawCPI};
+use crate::WithdrawUnit;
+
+/// Requests a withdraw of the vault's position in a child vault. Remaining accounts are the child's request withdraw
+/// remaining accounts.
+pub fn child_vault_request_withdraw<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ChildVaultRequestWithdraw<'info>>,
+    withdraw_amount: u64,
+    withdraw_unit: WithdrawUnit,
+) -> Result<()> {
+    ctx.accounts.vault_allocations.load()?.validate_allocation(
+        &ctx.accounts.child_vault.key(),
+        &ctx.accounts.child_vault_depositor.key(),
+    )?;
+
+    ctx.vault_request_withdraw(withdraw_amount, withdraw_unit)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ChildVaultRequestWithdraw<'info> {
+    #[account(
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    pub manager: Signer<'info>,
+    #[account(
+        constraint = is_vault_for_vault_allocations(&vault_allocations, &vault)?,
+    )]
+    pub vault_allocations: AccountLoader<'info, VaultAllocations>,
+    #[account(mut)]
+    pub child_vault: AccountLoader<'info, Vault>,
+    #[account(mut)]
+    /// CHECK: checked against vault_allocations
+    pub child_vault_depositor: AccountInfo<'info>,
+    #[account(
+        constraint = is_user_stats_for_vault(&child_vault, &child_drift_user_stats)?
+    )]
+    /// CHECK: checked in child vault cpi
+    pub child_drift_user_stats: AccountInfo<'info>,
+    #[account(
+        constraint = is_user_for_vault(&child_vault, &child_drift_user.key())?
+    )]
+    /// CHECK: checked in child vault cpi
+    pub child_drift_user: AccountInfo<'info>,
+    /// CHECK: checked in drift cpi
+    pub drift_state: AccountInfo<'info>,
+    pub drift_vaults_program: Program<'info, DriftVaults>,
+}
+
+impl<'info> VaultRequestWithdrawCPI
+    for Context<'_, '_, '_, 'info, ChildVaultRequestWithdraw<'info>>
+{
+    fn vault_request_withdraw(
+        &self,
+        withdraw_amount: u64,
+        withdraw_unit: WithdrawUnit,
+    ) -> Result<()> {
+        declare_vault_seeds!(self.accounts.vault, seeds);
+
+        let accounts = crate::accounts::RequestWithdraw {
+            vault: self.accounts.child_vault.key(),
+            vault_depositor: self.accounts.child_vault_depositor.key(),
+            authority: self.accounts.vault.key(),
+            drift_user_stats: self.accounts.child_drift_user_stats.key(),
+            drift_user: self.accounts.child_drift_user.key(),
+            drift_state: self.accounts.drift_state.key(),
+        };
+
+        let account_infos = vec![
+            self.accounts.child_vault.to_account_info(),
+            self.accounts.child_vault_depositor.clone(),
+            self.accounts.vault.to_account_info(),
+            self.accounts.child_drift_user_stats.clone(),
+            self.accounts.child_drift_user.clone(),
+            self.accounts.drift_state.clone(),
+            self.accounts.drift_vaults_program.to_account_info(),
+        ];
+
+        invoke_vault_instruction(
+            &accounts,
+            &crate::instruction::RequestWithdraw {
+                withdraw_amount,
+                withdraw_unit,
+            },
+            account_infos,
+            self.remaining_accounts,
+            seeds,
+        )
+    }
+}
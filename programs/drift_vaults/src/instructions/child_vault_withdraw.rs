@@ -0,0 +1,180 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use drift::cpi::accounts::Deposit as DriftDeposit;
+use drift::math::safe_math::SafeMath;
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{
+    is_manager_for_vault, is_user_for_vault, is_user_stats_for_vault,
+    is_vault_for_vault_allocations,
+};
+use crate::declare_vault_seeds;
+use crate::drift_cpi::DepositCPI;
+use crate::program::DriftVaults;
+use crate::state::{Vault, VaultAllocations};
+use crate::vault_cpi::{invoke_vault_instruction, VaultWithdrawCPI};
+
+/// Withdraws the vault's requested position from a child vault and deposits the proceeds into the vault's drift
+/// user. Remaining accounts are passed to both the child's withdraw and the drift deposit, so they must be the child's
+/// withdraw remaining accounts plus any market the vault's drift user needs.
+pub fn child_vault_withdraw<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ChildVaultWithdraw<'info>>,
+    min_amount_out: Option<u64>,
+) -> Result<()> {
+    ctx.accounts.vault_allocations.load()?.validate_allocation(
+        &ctx.accounts.child_vault.key(),
+        &ctx.accounts.child_vault_depositor.key(),
+    )?;
+
+    let token_balance_before = ctx.accounts.vault_token_account.amount;
+    ctx.vault_withdraw(min_amount_out)?;
+    ctx.accounts.vault_token_account.reload()?;
+    let token_balance_after = ctx.accounts.vault_token_account.amount;
+    msg!(
+        "token_balance_before: {} token_balance_after: {}",
+        token_balance_before,
+        token_balance_after
+    );
+
+    ctx.drift_deposit(token_balance_after.safe_sub(token_balance_before)?)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ChildVaultWithdraw<'info> {
+    #[account(
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    pub manager: Signer<'info>,
+    #[account(
+        constraint = is_vault_for_vault_allocations(&vault_allocations, &vault)?,
+    )]
+    pub vault_allocations: AccountLoader<'info, VaultAllocations>,
+    #[account(
+        mut,
+        seeds = [b"vault_token_account".as_ref(), vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    /// CHECK: checked in drift cpi
+    pub drift_state: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::mint = vault_token_account.mint
+    )]
+    pub drift_spot_market_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: checked in drift cpi
+    pub drift_signer: AccountInfo<'info>,
+    #[account(mut)]
+    pub child_vault: AccountLoader<'info, Vault>,
+    #[account(mut)]
+    /// CHECK: checked against vault_allocations
+    pub child_vault_depositor: AccountInfo<'info>,
+    #[account(mut)]
+    /// CHECK: checked in child vault cpi
+    pub child_vault_token_account: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_stats_for_vault(&child_vault, &child_drift_user_stats)?
+    )]
+    /// CHECK: checked in child vault cpi
+    pub child_drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&child_vault, &child_drift_user.key())?
+    )]
+    /// CHECK: checked in child vault cpi
+    pub child_drift_user: AccountInfo<'info>,
+    pub drift_program: Program<'info, Drift>,
+    pub drift_vaults_program: Program<'info, DriftVaults>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> VaultWithdrawCPI for Context<'_, '_, '_, 'info, ChildVaultWithdraw<'info>> {
+    fn vault_withdraw(&self, min_amount_out: Option<u64>) -> Result<()> {
+        declare_vault_seeds!(self.accounts.vault, seeds);
+
+        let accounts = crate::accounts::Withdraw {
+            vault: self.accounts.child_vault.key(),
+            vault_depositor: self.accounts.child_vault_depositor.key(),
+            authority: self.accounts.vault.key(),
+            vault_token_account: self.accounts.child_vault_token_account.key(),
+            drift_user_stats: self.accounts.child_drift_user_stats.key(),
+            drift_user: self.accounts.child_drift_user.key(),
+            drift_state: self.accounts.drift_state.key(),
+            drift_spot_market_vault: self.accounts.drift_spot_market_vault.key(),
+            drift_signer: self.accounts.drift_signer.key(),
+            user_token_account: self.accounts.vault_token_account.key(),
+            drift_program: self.accounts.drift_program.key(),
+            token_program: self.accounts.token_program.key(),
+        };
+
+        let account_infos = vec![
+            self.accounts.child_vault.to_account_info(),
+            self.accounts.child_vault_depositor.clone(),
+            self.accounts.vault.to_account_info(),
+            self.accounts.child_vault_token_account.clone(),
+            self.accounts.child_drift_user_stats.clone(),
+            self.accounts.child_drift_user.clone(),
+            self.accounts.drift_state.clone(),
+            self.accounts.drift_spot_market_vault.to_account_info(),
+            self.accounts.drift_signer.clone(),
+            self.accounts.vault_token_account.to_account_info(),
+            self.accounts.drift_program.to_account_info(),
+            self.accounts.token_program.to_account_info(),
+            self.accounts.drift_vaults_program.to_account_info(),
+        ];
+
+        invoke_vault_instruction(
+            &accounts,
+            &crate::instruction::WithdrawWithMinAmount {
+                min_amount_out: min_amount_out.unwrap_or(0),
+            },
+            account_infos,
+            self.remaining_accounts,
+            seeds,
+        )
+    }
+}
+
+impl<'info> DepositCPI for Context<'_, '_, '_, 'info, ChildVaultWithdraw<'info>> {
+    fn drift_deposit(&self, amount: u64) -> Result<()> {
+        declare_vault_seeds!(self.accounts.vault, seeds);
+        let spot_market_index = self.accounts.vault.load()?.spot_market_index;
+
+        let cpi_program = self.accounts.drift_program.to_account_info().clone();
+        let cpi_accounts = DriftDeposit {
+            state: self.accounts.drift_state.clone(),
+            user: self.accounts.drift_user.to_account_info().clone(),
+            user_stats: self.accounts.drift_user_stats.clone(),
+            authority: self.accounts.vault.to_account_info().clone(),
+            spot_market_vault: self
+                .accounts
+                .drift_spot_market_vault
+                .to_account_info()
+                .clone(),
+            user_token_account: self.accounts.vault_token_account.to_account_info().clone(),
+            token_program: self.accounts.token_program.to_account_info().clone(),
+        };
+        let cpi_context = CpiContext::new_with_signer(cpi_program, cpi_accounts, seeds)
+            .with_remaining_accounts(self.remaining_accounts.into());
+        drift::cpi::deposit(cpi_context, spot_market_index, amount, false)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,171 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Transfer};
+use anchor_spl::token::{Token, TokenAccount};
+use drift::cpi::accounts::Withdraw as DriftWithdraw;
+use drift::instructions::optional_accounts::AccountMaps;
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{is_fee_recipients_for_vault, is_user_for_vault, is_user_stats_for_vault};
+use crate::drift_cpi::WithdrawCPI;
+use crate::error::ErrorCode;
+use crate::state::{
+    FeeRecipients, ManagerPositionProvider, SubAccountProvider, Vault, VaultAllocationsProvider,
+    VaultPauseFlag, VaultProtocolProvider,
+};
+use crate::token_cpi::TokenTransferCPI;
+use crate::{declare_vault_seeds, implement_withdraw, validate, AccountMapProvider};
+
+pub fn claim_fees<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ClaimFees<'info>>,
+    recipient_index: u8,
+) -> Result<()> {
+    let clock = &Clock::get()?;
+    let mut vault = ctx.accounts.vault.load_mut()?;
+    let now = clock.unix_timestamp;
+
+    validate!(!vault.in_liquidation(), ErrorCode::OngoingLiquidation)?;
+    vault.validate_not_paused(VaultPauseFlag::Fees, now)?;
+
+    let mut fee_recipients = ctx.accounts.fee_recipients.load_mut()?;
+    let recipient_index = recipient_index as usize;
+
+    validate!(
+        fee_recipients
+            .recipients
+            .get(recipient_index)
+            .map(|recipient| recipient.token_account)
+            .eq(&Some(ctx.accounts.recipient_token_account.key())),
+        ErrorCode::InvalidFeeRecipients,
+        "recipient token account does not match fee recipient {}",
+        recipient_index
+    )?;
+
+    // backwards compatible: if last rem acct does not deserialize into [`VaultProtocol`] then it's a legacy vault.
+    let mut vp = ctx.vault_protocol();
+    vault.validate_vault_protocol(&vp)?;
+    let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
+
+    // if the vault has a [`ManagerPosition`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut mp = ctx.manager_position(&vault);
+    vault.validate_manager_position(&mp)?;
+    let mut mp = mp.as_mut().map(|mp| mp.load_mut()).transpose()?;
+
+    let user = ctx.accounts.drift_user.load()?;
+    let spot_market_index = vault.spot_market_index;
+
+    let AccountMaps {
+        perp_market_map,
+        spot_market_map,
+        mut oracle_map,
+    } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
+
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
+
+    let claim_amount = fee_recipients.claim(
+        recipient_index,
+        vault_equity,
+        &mut vault,
+        &mut vp,
+        &mut mp,
+        now,
+    )?;
+
+    msg!("claim_amount: {}", claim_amount);
+
+    drop(vault);
+    drop(user);
+    drop(vp);
+    drop(mp);
+    drop(fee_recipients);
+
+    ctx.drift_withdraw(claim_amount)?;
+
+    ctx.token_transfer(claim_amount)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ClaimFees<'info> {
+    #[account(mut)]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_fee_recipients_for_vault(&fee_recipients, &vault)?
+    )]
+    pub fee_recipients: AccountLoader<'info, FeeRecipients>,
+    #[account(
+        mut,
+        seeds = [b"vault_token_account".as_ref(), vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    /// CHECK: checked in drift cpi
+    pub drift_state: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::mint = vault_token_account.mint
+    )]
+    pub drift_spot_market_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: checked in drift cpi
+    pub drift_signer: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::mint = vault_token_account.mint
+    )]
+    pub recipient_token_account: Box<Account<'info, TokenAccount>>,
+    pub drift_program: Program<'info, Drift>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> WithdrawCPI for Context<'_, '_, '_, 'info, ClaimFees<'info>> {
+    fn drift_withdraw(&self, amount: u64) -> Result<()> {
+        implement_withdraw!(self, amount);
+        Ok(())
+    }
+}
+
+impl<'info> TokenTransferCPI for Context<'_, '_, '_, 'info, ClaimFees<'info>> {
+    fn token_transfer(&self, amount: u64) -> Result<()> {
+        declare_vault_seeds!(self.accounts.vault, seeds);
+
+        let cpi_accounts = Transfer {
+            from: self.accounts.vault_token_account.to_account_info().clone(),
+            to: self
+                .accounts
+                .recipient_token_account
+                .to_account_info()
+                .clone(),
+            authority: self.accounts.vault.to_account_info().clone(),
+        };
+        let token_program = self.accounts.token_program.to_account_info().clone();
+        let cpi_context = CpiContext::new_with_signer(token_program, cpi_accounts, seeds);
+
+        token::transfer(cpi_context, amount)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::Mint;
+
+use crate::constraints::{
+    is_manager_for_vault, is_mint_for_tokenized_depositor, is_tokenized_depositor_for_vault,
+};
+use crate::state::{TokenizedVaultDepositor, Vault};
+
+pub fn close_tokenized_vault_depositor(ctx: Context<CloseTokenizedVaultDepositor>) -> Result<()> {
+    let tokenized_vault_depositor = ctx.accounts.tokenized_vault_depositor.load()?;
+
+    tokenized_vault_depositor.check_can_close(ctx.accounts.mint.supply)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseTokenizedVaultDepositor<'info> {
+    #[account(
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        close = manager,
+        constraint = is_tokenized_depositor_for_vault(&tokenized_vault_depositor, &vault)?,
+    )]
+    pub tokenized_vault_depositor: AccountLoader<'info, TokenizedVaultDepositor>,
+    #[account(
+        constraint = is_mint_for_tokenized_depositor(&mint.key(), &tokenized_vault_depositor)?,
+    )]
+    pub mint: Account<'info, Mint>,
+    #[account(mut)]
+    pub manager: Signer<'info>,
+}
@@ -0,0 +1,207 @@
+use anchor_lang::prelude::*;
+use anchor_lang::AccountsClose;
+use anchor_spl::token::{self, CloseAccount, Token, TokenAccount, Transfer};
+use drift::cpi::accounts::{DeleteUser, Withdraw as DriftWithdraw};
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{is_manager_for_vault, is_user_for_vault, is_user_stats_for_vault};
+use crate::declare_vault_seeds;
+use crate::drift_cpi::{DeleteUserCPI, WithdrawCPI};
+use crate::state::{ManagerPositionProvider, SubAccountProvider, Vault, VaultProtocolProvider};
+use crate::token_cpi::{CloseTokenAccountCPI, TokenTransferCPI};
+
+pub fn close_vault<'info>(ctx: Context<'_, '_, 'info, 'info, CloseVault<'info>>) -> Result<()> {
+    let vault = ctx.accounts.vault.load()?;
+    let user = ctx.accounts.drift_user.load()?;
+
+    vault.check_can_close()?;
+    vault.check_positions_flat(&user)?;
+
+    // the vault's sub-accounts can't be used once it's closed, so they must all be in the rem accts and empty
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    for sub_account in sub_accounts.iter() {
+        vault.check_sub_account_flat(&*sub_account.load()?)?;
+    }
+
+    // the vault's [`VaultProtocol`] and [`ManagerPosition`] hold no shares once the vault can close, and are closed
+    // with it, so they must be passed if they exist
+    let vp = ctx.vault_protocol();
+    vault.validate_vault_protocol(&vp)?;
+
+    // if the vault has a [`ManagerPosition`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mp = ctx.manager_position(&vault);
+    vault.validate_manager_position(&mp)?;
+
+    let spot_market_index = vault.spot_market_index;
+    let has_balance = user
+        .spot_positions
+        .iter()
+        .any(|position| position.market_index == spot_market_index && position.scaled_balance != 0);
+
+    drop(vault);
+    drop(user);
+
+    // only the dead shares are left and no depositor owns them, so any remaining balance (rounding dust or a
+    // donation) is swept to the manager
+    if has_balance {
+        ctx.drift_withdraw(u64::MAX)?;
+    }
+
+    ctx.accounts.vault_token_account.reload()?;
+    let dust = ctx.accounts.vault_token_account.amount;
+    if dust > 0 {
+        msg!("sweeping {} to manager", dust);
+        ctx.token_transfer(dust)?;
+    }
+
+    ctx.close_token_account()?;
+    ctx.drift_delete_user()?;
+
+    if let Some(vp) = vp {
+        vp.close(ctx.accounts.manager.to_account_info())?;
+    }
+    if let Some(mp) = mp {
+        mp.close(ctx.accounts.manager.to_account_info())?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseVault<'info> {
+    #[account(
+        mut,
+        close = manager,
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(mut)]
+    pub manager: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"vault_token_account".as_ref(), vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::authority = manager,
+        token::mint = vault_token_account.mint
+    )]
+    pub manager_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    #[account(mut)]
+    /// CHECK: checked in drift cpi
+    pub drift_state: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::mint = vault_token_account.mint
+    )]
+    pub drift_spot_market_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: checked in drift cpi
+    pub drift_signer: AccountInfo<'info>,
+    pub drift_program: Program<'info, Drift>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> WithdrawCPI for Context<'_, '_, '_, 'info, CloseVault<'info>> {
+    fn drift_withdraw(&self, amount: u64) -> Result<()> {
+        declare_vault_seeds!(self.accounts.vault, seeds);
+        let spot_market_index = self.accounts.vault.load()?.spot_market_index;
+
+        let cpi_accounts = DriftWithdraw {
+            state: self.accounts.drift_state.to_account_info().clone(),
+            user: self.accounts.drift_user.to_account_info().clone(),
+            user_stats: self.accounts.drift_user_stats.to_account_info().clone(),
+            authority: self.accounts.vault.to_account_info().clone(),
+            spot_market_vault: self
+                .accounts
+                .drift_spot_market_vault
+                .to_account_info()
+                .clone(),
+            drift_signer: self.accounts.drift_signer.to_account_info().clone(),
+            user_token_account: self.accounts.vault_token_account.to_account_info().clone(),
+            token_program: self.accounts.token_program.to_account_info().clone(),
+        };
+
+        let drift_program = self.accounts.drift_program.to_account_info().clone();
+        let cpi_context = CpiContext::new_with_signer(drift_program, cpi_accounts, seeds)
+            .with_remaining_accounts(self.remaining_accounts.into());
+        // reduce only caps the withdraw at the remaining deposit
+        drift::cpi::withdraw(cpi_context, spot_market_index, amount, true)?;
+
+        Ok(())
+    }
+}
+
+impl<'info> TokenTransferCPI for Context<'_, '_, '_, 'info, CloseVault<'info>> {
+    fn token_transfer(&self, amount: u64) -> Result<()> {
+        declare_vault_seeds!(self.accounts.vault, seeds);
+
+        let cpi_accounts = Transfer {
+            from: self.accounts.vault_token_account.to_account_info().clone(),
+            to: self
+                .accounts
+                .manager_token_account
+                .to_account_info()
+                .clone(),
+            authority: self.accounts.vault.to_account_info().clone(),
+        };
+        let token_program = self.accounts.token_program.to_account_info().clone();
+        let cpi_context = CpiContext::new_with_signer(token_program, cpi_accounts, seeds);
+
+        token::transfer(cpi_context, amount)?;
+
+        Ok(())
+    }
+}
+
+impl<'info> CloseTokenAccountCPI for Context<'_, '_, '_, 'info, CloseVault<'info>> {
+    fn close_token_account(&self) -> Result<()> {
+        declare_vault_seeds!(self.accounts.vault, seeds);
+
+        let cpi_accounts = CloseAccount {
+            account: self.accounts.vault_token_account.to_account_info().clone(),
+            destination: self.accounts.manager.to_account_info().clone(),
+            authority: self.accounts.vault.to_account_info().clone(),
+        };
+        let token_program = self.accounts.token_program.to_account_info().clone();
+        let cpi_context = CpiContext::new_with_signer(token_program, cpi_accounts, seeds);
+
+        token::close_account(cpi_context)?;
+
+        Ok(())
+    }
+}
+
+impl<'info> DeleteUserCPI for Context<'_, '_, '_, 'info, CloseVault<'info>> {
+    fn drift_delete_user(&self) -> Result<()> {
+        declare_vault_seeds!(self.accounts.vault, seeds);
+
+        let cpi_accounts = DeleteUser {
+            user: self.accounts.drift_user.to_account_info().clone(),
+            user_stats: self.accounts.drift_user_stats.clone(),
+            state: self.accounts.drift_state.clone(),
+            authority: self.accounts.vault.to_account_info().clone(),
+        };
+        let drift_program = self.accounts.drift_program.to_account_info().clone();
+        let cpi_context = CpiContext::new_with_signer(drift_program, cpi_accounts, seeds);
+
+        // the drift user's rent is returned to the vault, which is closed to the manager
+        drift::cpi::delete_user(cpi_context)?;
+
+        Ok(())
+    }
+}
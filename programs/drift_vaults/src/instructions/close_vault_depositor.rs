@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::is_authority_for_vault_depositor;
+use crate::state::{ShareClassProvider, VaultDepositor};
+
+pub fn close_vault_depositor<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, CloseVaultDepositor<'info>>,
+) -> Result<()> {
+    let vault_depositor = ctx.accounts.vault_depositor.load()?;
+
+    vault_depositor.check_can_close()?;
+
+    // if the depositor is in a [`ShareClass`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let sc = ctx.share_class(&vault_depositor);
+    vault_depositor.validate_share_class(&sc)?;
+    if let Some(sc) = sc {
+        let mut sc = sc.load_mut()?;
+        sc.num_depositors = sc.num_depositors.saturating_sub(1);
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CloseVaultDepositor<'info> {
+    #[account(
+        mut,
+        close = authority,
+        constraint = is_authority_for_vault_depositor(&vault_depositor, &authority)?,
+    )]
+    pub vault_depositor: AccountLoader<'info, VaultDepositor>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+}
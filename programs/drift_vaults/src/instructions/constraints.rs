@@ -1,9 +1,15 @@
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::token::TokenAccount;
 use drift::state::insurance_fund_stake::InsuranceFundStake;
+use drift::state::user::User;
 
-use crate::state::VaultProtocol;
-use crate::{TokenizedVaultDepositor, Vault, VaultDepositor};
+use crate::state::{ManagerPosition, VaultProtocol};
+use crate::{
+    DelegateTimelock, DepositorOperator, DrawdownGuard, FeeRecipients, RiskLimits, ShareClass,
+    TokenizedVaultDepositor, TokenizedWithdrawRequest, Vault, VaultAllocations, VaultDepositor,
+    VaultReferrer, VaultRoles, WithdrawGate,
+};
 
 pub fn is_vault_for_vault_depositor(
     vault_depositor: &AccountLoader<VaultDepositor>,
@@ -19,6 +25,25 @@ pub fn is_authority_for_vault_depositor(
     Ok(vault_depositor.load()?.authority.eq(signer.key))
 }
 
+pub fn is_operator_for_vault_depositor(
+    depositor_operator: &AccountLoader<DepositorOperator>,
+    signer: &Signer,
+) -> Result<bool> {
+    Ok(depositor_operator.load()?.is_operator(signer.key))
+}
+
+pub fn is_withdraw_destination_for_vault_depositor(
+    depositor_operator: &AccountLoader<DepositorOperator>,
+    vault_depositor: &AccountLoader<VaultDepositor>,
+    token_account: &Account<TokenAccount>,
+) -> Result<bool> {
+    Ok(depositor_operator.load()?.is_withdraw_destination(
+        &token_account.key(),
+        &token_account.owner,
+        &vault_depositor.load()?.authority,
+    ))
+}
+
 pub fn is_manager_for_vault(vault: &AccountLoader<Vault>, signer: &Signer) -> Result<bool> {
     Ok(vault.load()?.manager.eq(signer.key))
 }
@@ -47,10 +72,80 @@ pub fn is_delegate_for_vault(vault: &AccountLoader<Vault>, signer: &Signer) -> R
     Ok(vault.load()?.delegate.eq(signer.key))
 }
 
+pub fn is_risk_officer_for_vault(
+    vault: &AccountLoader<Vault>,
+    vault_roles: &Option<AccountLoader<VaultRoles>>,
+    signer: &Signer,
+) -> Result<bool> {
+    is_role_for_vault(vault, vault_roles, |roles| roles.risk_officer, signer)
+}
+
+pub fn is_keeper_for_vault(
+    vault: &AccountLoader<Vault>,
+    vault_roles: &Option<AccountLoader<VaultRoles>>,
+    signer: &Signer,
+) -> Result<bool> {
+    is_role_for_vault(vault, vault_roles, |roles| roles.keeper, signer)
+}
+
+pub fn is_operator_for_vault(
+    vault: &AccountLoader<Vault>,
+    vault_roles: &Option<AccountLoader<VaultRoles>>,
+    signer: &Signer,
+) -> Result<bool> {
+    is_role_for_vault(vault, vault_roles, |roles| roles.operator, signer)
+}
+
+fn is_role_for_vault(
+    vault: &AccountLoader<Vault>,
+    vault_roles: &Option<AccountLoader<VaultRoles>>,
+    role: impl Fn(&VaultRoles) -> Pubkey,
+    signer: &Signer,
+) -> Result<bool> {
+    match vault_roles {
+        Some(vault_roles) => {
+            let vault_roles = vault_roles.load()?;
+            Ok(vault_roles.vault.eq(&vault.key()) && role(&vault_roles).eq(signer.key))
+        }
+        None => Ok(false),
+    }
+}
+
+pub fn is_vault_for_vault_roles(
+    vault_roles: &AccountLoader<VaultRoles>,
+    vault: &AccountLoader<Vault>,
+) -> Result<bool> {
+    Ok(vault_roles.load()?.vault.eq(&vault.key()))
+}
+
+pub fn is_vault_for_vault_allocations(
+    vault_allocations: &AccountLoader<VaultAllocations>,
+    vault: &AccountLoader<Vault>,
+) -> Result<bool> {
+    Ok(vault_allocations.load()?.vault.eq(&vault.key()))
+}
+
 pub fn is_user_for_vault(vault: &AccountLoader<Vault>, user_key: &Pubkey) -> Result<bool> {
     Ok(vault.load()?.user.eq(user_key))
 }
 
+/// A drift user owned by the vault other than its primary `Vault.user`
+pub fn is_sub_account_for_vault(
+    vault: &AccountLoader<Vault>,
+    drift_user: &AccountLoader<User>,
+) -> Result<bool> {
+    let vault = vault.load()?;
+    Ok(drift_user.key() != vault.user && drift_user.load()?.authority.eq(&vault.pubkey))
+}
+
+/// A drift user the signer owns, which only its authority (not a delegate) can move collateral out of
+pub fn is_authority_for_drift_user(
+    drift_user: &AccountLoader<User>,
+    signer: &Signer,
+) -> Result<bool> {
+    Ok(drift_user.load()?.authority.eq(signer.key))
+}
+
 pub fn is_user_stats_for_vault(
     vault: &AccountLoader<Vault>,
     user_stats: &AccountInfo,
@@ -101,6 +196,76 @@ pub fn is_vault_shares_base_for_tokenized_depositor(
         .eq(vault_shares_base))
 }
 
+pub fn is_authority_for_tokenized_withdraw_request(
+    tokenized_withdraw_request: &AccountLoader<TokenizedWithdrawRequest>,
+    signer: &Signer,
+) -> anchor_lang::Result<bool> {
+    Ok(tokenized_withdraw_request.load()?.authority.eq(signer.key))
+}
+
+pub fn is_fee_recipients_for_vault(
+    fee_recipients: &AccountLoader<FeeRecipients>,
+    vault: &AccountLoader<Vault>,
+) -> Result<bool> {
+    Ok(fee_recipients.load()?.vault.eq(&vault.key()))
+}
+
+pub fn is_vault_for_vault_referrer(
+    vault_referrer: &AccountLoader<VaultReferrer>,
+    vault: &AccountLoader<Vault>,
+) -> Result<bool> {
+    Ok(vault_referrer.load()?.vault.eq(&vault.key()))
+}
+
+pub fn is_authority_for_vault_referrer(
+    vault_referrer: &AccountLoader<VaultReferrer>,
+    signer: &Signer,
+) -> Result<bool> {
+    Ok(vault_referrer.load()?.authority.eq(signer.key))
+}
+
+pub fn is_vault_for_share_class(
+    share_class: &AccountLoader<ShareClass>,
+    vault: &AccountLoader<Vault>,
+) -> Result<bool> {
+    Ok(share_class.load()?.vault.eq(&vault.key()))
+}
+
+pub fn is_vault_for_withdraw_gate(
+    withdraw_gate: &AccountLoader<WithdrawGate>,
+    vault: &AccountLoader<Vault>,
+) -> Result<bool> {
+    Ok(withdraw_gate.load()?.vault.eq(&vault.key()))
+}
+
+pub fn is_vault_for_delegate_timelock(
+    delegate_timelock: &AccountLoader<DelegateTimelock>,
+    vault: &AccountLoader<Vault>,
+) -> Result<bool> {
+    Ok(delegate_timelock.load()?.vault.eq(&vault.key()))
+}
+
+pub fn is_vault_for_drawdown_guard(
+    drawdown_guard: &AccountLoader<DrawdownGuard>,
+    vault: &AccountLoader<Vault>,
+) -> Result<bool> {
+    Ok(drawdown_guard.load()?.vault.eq(&vault.key()))
+}
+
+pub fn is_vault_for_risk_limits(
+    risk_limits: &AccountLoader<RiskLimits>,
+    vault: &AccountLoader<Vault>,
+) -> Result<bool> {
+    Ok(risk_limits.load()?.vault.eq(&vault.key()))
+}
+
+pub fn is_vault_for_manager_position(
+    manager_position: &AccountLoader<ManagerPosition>,
+    vault: &AccountLoader<Vault>,
+) -> Result<bool> {
+    Ok(manager_position.load()?.vault.eq(&vault.key()))
+}
+
 pub fn is_ata(token_account: &Pubkey, owner: &Pubkey, mint: &Pubkey) -> anchor_lang::Result<bool> {
     Ok(get_associated_token_address(owner, mint).eq(token_account))
 }
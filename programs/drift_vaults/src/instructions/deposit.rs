@@ -2,6 +2,7 @@ use anchor_lang::prelude::*;
 use anchor_spl::token::{self, Token, TokenAccount, Transfer};
 use drift::cpi::accounts::Deposit as DriftDeposit;
 use drift::instructions::optional_accounts::AccountMaps;
+use drift::math::safe_math::SafeMath;
 use drift::program::Drift;
 use drift::state::user::User;
 
@@ -10,27 +11,74 @@ use crate::constraints::{
 };
 use crate::drift_cpi::DepositCPI;
 use crate::error::ErrorCode;
-use crate::state::{Vault, VaultDepositor, VaultProtocolProvider};
+use crate::state::{
+    ManagerPositionProvider, ShareClassProvider, SubAccountProvider, Vault,
+    VaultAllocationsProvider, VaultDepositor, VaultDepositorBase, VaultPauseFlag,
+    VaultProtocolProvider, VaultReferrerProvider,
+};
 use crate::token_cpi::TokenTransferCPI;
 use crate::{declare_vault_seeds, implement_deposit, validate, AccountMapProvider};
 
 pub fn deposit<'c: 'info, 'info>(
     ctx: Context<'_, '_, 'c, 'info, Deposit<'info>>,
     amount: u64,
+    min_shares_out: Option<u128>,
+) -> Result<()> {
+    deposit_to_vault_depositor(
+        &ctx,
+        &ctx.accounts.vault,
+        &mut ctx.accounts.vault_depositor.load_mut()?,
+        &ctx.accounts.drift_user,
+        &ctx.accounts.drift_user_stats,
+        amount,
+        min_shares_out,
+    )?;
+
+    ctx.token_transfer(amount)?;
+
+    ctx.drift_deposit(amount)?;
+
+    Ok(())
+}
+
+/// Settles the depositor's fees and mints its shares for `amount`. Every deposit into a [`VaultDepositor`] goes
+/// through this; the caller then moves `amount` into the vault token account and deposits it into drift.
+pub fn deposit_to_vault_depositor<'c: 'info, 'info, T: anchor_lang::Bumps>(
+    ctx: &Context<'_, '_, 'c, 'info, T>,
+    vault: &AccountLoader<'info, Vault>,
+    vault_depositor: &mut VaultDepositor,
+    drift_user: &AccountLoader<'info, User>,
+    drift_user_stats: &AccountInfo<'info>,
+    amount: u64,
+    min_shares_out: Option<u128>,
 ) -> Result<()> {
     let clock = &Clock::get()?;
 
-    let mut vault = ctx.accounts.vault.load_mut()?;
+    let mut vault = vault.load_mut()?;
     validate!(!vault.in_liquidation(), ErrorCode::OngoingLiquidation)?;
-
-    let mut vault_depositor = ctx.accounts.vault_depositor.load_mut()?;
+    vault.validate_not_paused(VaultPauseFlag::Deposit, clock.unix_timestamp)?;
 
     // backwards compatible: if last rem acct does not deserialize into [`VaultProtocol`] then it's a legacy vault.
     let mut vp = ctx.vault_protocol();
     vault.validate_vault_protocol(&vp)?;
     let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
 
-    let user = ctx.accounts.drift_user.load()?;
+    // if the depositor was referred, its [`VaultReferrer`] can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut vr = ctx.vault_referrer(&vault_depositor);
+    vault_depositor.validate_vault_referrer(&vr)?;
+    let mut vr = vr.as_mut().map(|vr| vr.load_mut()).transpose()?;
+
+    // if the depositor is in a [`ShareClass`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut sc = ctx.share_class(&vault_depositor);
+    vault_depositor.validate_share_class(&sc)?;
+    let mut sc = sc.as_mut().map(|sc| sc.load_mut()).transpose()?;
+
+    // if the vault has a [`ManagerPosition`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut mp = ctx.manager_position(&vault);
+    vault.validate_manager_position(&mp)?;
+    let mut mp = mp.as_mut().map(|mp| mp.load_mut()).transpose()?;
+
+    let user = drift_user.load()?;
     let spot_market_index = vault.spot_market_index;
 
     let AccountMaps {
@@ -39,9 +87,33 @@ pub fn deposit<'c: 'info, 'info>(
         mut oracle_map,
     } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
 
-    let vault_equity =
-        vault.calculate_equity(&user, &perp_market_map, &spot_market_map, &mut oracle_map)?;
+    let sub_accounts = ctx.sub_accounts(&vault, drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
 
+    vault_depositor.settle_fees(
+        sc.as_deref_mut(),
+        vr.as_deref_mut(),
+        vault_equity,
+        &mut vault,
+        &mut vp,
+        clock.unix_timestamp,
+    )?;
+
+    if let Some(mp) = mp.as_mut() {
+        mp.settle(&mut vault, &mut vp, vault_equity, clock.unix_timestamp)?;
+    }
+
+    let first_deposit = vault.is_first_deposit();
+    let class_shares_before = vault_depositor.get_vault_shares();
     vault_depositor.deposit(
         amount,
         vault_equity,
@@ -50,13 +122,32 @@ pub fn deposit<'c: 'info, 'info>(
         clock.unix_timestamp,
     )?;
 
-    drop(vault);
-    drop(user);
-    drop(vp);
+    if first_deposit {
+        vault_depositor.lock_dead_shares(&vault)?;
+    }
 
-    ctx.token_transfer(amount)?;
+    // depositors are only let in while the manager keeps its minimum share of the vault, including this deposit
+    if let Some(mp) = mp.as_mut() {
+        mp.sync(&vault, &mut vp)?;
+        mp.validate_min_manager_share(&vault, 0)?;
+    }
 
-    ctx.drift_deposit(amount)?;
+    if let Some(min_shares_out) = min_shares_out {
+        let shares_out = vault_depositor
+            .get_vault_shares()
+            .safe_sub(class_shares_before)?;
+        validate!(
+            shares_out >= min_shares_out,
+            ErrorCode::SlippageExceeded,
+            "deposit minted {} shares, less than min_shares_out {}",
+            shares_out,
+            min_shares_out
+        )?;
+    }
+
+    if let Some(sc) = sc.as_mut() {
+        sc.update_user_shares(class_shares_before, vault_depositor.get_vault_shares())?;
+    }
 
     Ok(())
 }
@@ -0,0 +1,137 @@
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use anchor_spl::token::{self, Token, TokenAccount, Transfer};
+use drift::cpi::accounts::Deposit as DriftDeposit;
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{is_user_for_vault, is_user_stats_for_vault};
+use crate::drift_cpi::DepositCPI;
+use crate::instructions::deposit_to_vault_depositor;
+use crate::state::{Vault, VaultDepositor};
+use crate::token_cpi::TokenTransferCPI;
+use crate::{declare_vault_seeds, implement_deposit, Size};
+
+/// Deposits the payer's tokens into `authority`'s [`VaultDepositor`], creating it if it doesn't exist. The shares,
+/// net deposits and profit share basis are credited to `authority`. In a permissioned vault only the manager can
+/// create the depositor, so anyone else can only fund depositors the manager already let in.
+pub fn deposit_for<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, DepositFor<'info>>,
+    amount: u64,
+    min_shares_out: Option<u128>,
+) -> Result<()> {
+    let initialized = ctx
+        .accounts
+        .vault_depositor
+        .to_account_info()
+        .try_borrow_data()?[..8]
+        == VaultDepositor::discriminator();
+
+    let mut vault_depositor = if initialized {
+        ctx.accounts.vault_depositor.load_mut()?
+    } else {
+        let mut vault_depositor = ctx.accounts.vault_depositor.load_init()?;
+        *vault_depositor = VaultDepositor::new_for(
+            &*ctx.accounts.vault.load()?,
+            ctx.accounts.vault_depositor.key(),
+            ctx.accounts.authority.key(),
+            ctx.accounts.payer.key,
+            Clock::get()?.unix_timestamp,
+        )?;
+        vault_depositor
+    };
+
+    deposit_to_vault_depositor(
+        &ctx,
+        &ctx.accounts.vault,
+        &mut vault_depositor,
+        &ctx.accounts.drift_user,
+        &ctx.accounts.drift_user_stats,
+        amount,
+        min_shares_out,
+    )?;
+
+    drop(vault_depositor);
+
+    ctx.token_transfer(amount)?;
+
+    ctx.drift_deposit(amount)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DepositFor<'info> {
+    #[account(mut)]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        init_if_needed,
+        seeds = [b"vault_depositor", vault.key().as_ref(), authority.key().as_ref()],
+        space = VaultDepositor::SIZE,
+        bump,
+        payer = payer
+    )]
+    pub vault_depositor: AccountLoader<'info, VaultDepositor>,
+    /// CHECK: the beneficiary, doesn't need to sign
+    pub authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"vault_token_account".as_ref(), vault.key().as_ref()],
+        bump
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    /// CHECK: checked in drift cpi
+    pub drift_state: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::mint = vault_token_account.mint
+    )]
+    pub drift_spot_market_vault: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        token::authority = payer,
+        token::mint = vault_token_account.mint
+    )]
+    pub payer_token_account: Box<Account<'info, TokenAccount>>,
+    pub drift_program: Program<'info, Drift>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TokenTransferCPI for Context<'_, '_, '_, 'info, DepositFor<'info>> {
+    fn token_transfer(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: self.accounts.payer_token_account.to_account_info().clone(),
+            to: self.accounts.vault_token_account.to_account_info().clone(),
+            authority: self.accounts.payer.to_account_info().clone(),
+        };
+        let token_program = self.accounts.token_program.to_account_info().clone();
+        let cpi_context = CpiContext::new(token_program, cpi_accounts);
+
+        token::transfer(cpi_context, amount)?;
+
+        Ok(())
+    }
+}
+
+impl<'info> DepositCPI for Context<'_, '_, '_, 'info, DepositFor<'info>> {
+    fn drift_deposit(&self, amount: u64) -> Result<()> {
+        implement_deposit!(self, amount);
+        Ok(())
+    }
+}
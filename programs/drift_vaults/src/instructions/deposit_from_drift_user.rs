@@ -0,0 +1,128 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{Token, TokenAccount};
+use drift::cpi::accounts::{Deposit as DriftDeposit, Withdraw as DriftWithdraw};
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{
+    is_authority_for_drift_user, is_authority_for_vault_depositor, is_user_for_vault,
+    is_user_stats_for_vault,
+};
+use crate::drift_cpi::{DepositCPI, WithdrawCPI};
+use crate::instructions::deposit_to_vault_depositor;
+use crate::state::{Vault, VaultDepositor};
+use crate::{declare_vault_seeds, implement_deposit};
+
+/// Deposits collateral the depositor holds in its own drift user. The depositor withdraws it from drift straight into
+/// the vault token account, and the vault deposits it into its drift user as in `deposit`. Drift's transfer_deposit
+/// only moves collateral between users of the same authority, so it can't be used here.
+pub fn deposit_from_drift_user<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, DepositFromDriftUser<'info>>,
+    amount: u64,
+    min_shares_out: Option<u128>,
+) -> Result<()> {
+    deposit_to_vault_depositor(
+        &ctx,
+        &ctx.accounts.vault,
+        &mut ctx.accounts.vault_depositor.load_mut()?,
+        &ctx.accounts.drift_user,
+        &ctx.accounts.drift_user_stats,
+        amount,
+        min_shares_out,
+    )?;
+
+    ctx.drift_withdraw(amount)?;
+
+    ctx.drift_deposit(amount)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct DepositFromDriftUser<'info> {
+    #[account(mut)]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        seeds = [b"vault_depositor", vault.key().as_ref(), authority.key().as_ref()],
+        bump,
+        constraint = is_authority_for_vault_depositor(&vault_depositor, &authority)?
+    )]
+    pub vault_depositor: AccountLoader<'info, VaultDepositor>,
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"vault_token_account".as_ref(), vault.key().as_ref()],
+        bump
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    #[account(mut)]
+    /// CHECK: checked in drift cpi
+    pub depositor_drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_authority_for_drift_user(&depositor_drift_user, &authority)?
+    )]
+    pub depositor_drift_user: AccountLoader<'info, User>,
+    /// CHECK: checked in drift cpi
+    pub drift_state: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::mint = vault_token_account.mint
+    )]
+    pub drift_spot_market_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: checked in drift cpi
+    pub drift_signer: AccountInfo<'info>,
+    pub drift_program: Program<'info, Drift>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> WithdrawCPI for Context<'_, '_, '_, 'info, DepositFromDriftUser<'info>> {
+    fn drift_withdraw(&self, amount: u64) -> Result<()> {
+        let spot_market_index = self.accounts.vault.load()?.spot_market_index;
+
+        // signed by the depositor, the collateral is paid into the vault token account
+        let cpi_accounts = DriftWithdraw {
+            state: self.accounts.drift_state.to_account_info().clone(),
+            user: self.accounts.depositor_drift_user.to_account_info().clone(),
+            user_stats: self.accounts.depositor_drift_user_stats.clone(),
+            authority: self.accounts.authority.to_account_info().clone(),
+            spot_market_vault: self
+                .accounts
+                .drift_spot_market_vault
+                .to_account_info()
+                .clone(),
+            drift_signer: self.accounts.drift_signer.to_account_info().clone(),
+            user_token_account: self.accounts.vault_token_account.to_account_info().clone(),
+            token_program: self.accounts.token_program.to_account_info().clone(),
+        };
+
+        let drift_program = self.accounts.drift_program.to_account_info().clone();
+        let cpi_context = CpiContext::new(drift_program, cpi_accounts)
+            .with_remaining_accounts(self.remaining_accounts.into());
+        // not reduce only, which would cap the withdraw at the depositor's balance while the shares are minted for
+        // the full amount
+        drift::cpi::withdraw(cpi_context, spot_market_index, amount, false)?;
+
+        Ok(())
+    }
+}
+
+impl<'info> DepositCPI for Context<'_, '_, '_, 'info, DepositFromDriftUser<'info>> {
+    fn drift_deposit(&self, amount: u64) -> Result<()> {
+        implement_deposit!(self, amount);
+        Ok(())
+    }
+}
@@ -8,15 +8,31 @@ use drift::state::user::User;
 
 use crate::constraints::*;
 use crate::drift_cpi::WithdrawCPI;
-use crate::state::{Vault, VaultDepositor, VaultProtocolProvider};
+use crate::error::ErrorCode;
+use crate::state::{
+    ShareClassProvider, SubAccountProvider, Vault, VaultAllocationsProvider, VaultDepositor,
+    VaultDepositorBase, VaultPauseFlag, VaultProtocolProvider, VaultReferrerProvider,
+    VaultRolesProvider, WithdrawGateProvider,
+};
 use crate::token_cpi::TokenTransferCPI;
-use crate::{declare_vault_seeds, AccountMapProvider};
+use crate::{declare_vault_seeds, validate, AccountMapProvider};
 
 pub fn force_withdraw<'c: 'info, 'info>(
     ctx: Context<'_, '_, 'c, 'info, ForceWithdraw<'info>>,
 ) -> Result<()> {
     let clock = &Clock::get()?;
+
+    // if the vault has [`VaultRoles`], they can be anywhere in the rem accts before any [`VaultProtocol`].
+    let vault_roles = ctx.vault_roles(&*ctx.accounts.vault.load()?);
+    validate!(
+        is_manager_for_vault(&ctx.accounts.vault, &ctx.accounts.manager)?
+            || is_operator_for_vault(&ctx.accounts.vault, &vault_roles, &ctx.accounts.manager)?,
+        ErrorCode::InvalidVaultRole,
+        "only the vault manager or operator can force withdraw"
+    )?;
+
     let mut vault = ctx.accounts.vault.load_mut()?;
+    vault.validate_not_paused(VaultPauseFlag::Withdraw, clock.unix_timestamp)?;
     let mut vault_depositor = ctx.accounts.vault_depositor.load_mut()?;
 
     // backwards compatible: if last rem acct does not deserialize into [`VaultProtocol`] then it's a legacy vault.
@@ -24,6 +40,21 @@ pub fn force_withdraw<'c: 'info, 'info>(
     vault.validate_vault_protocol(&vp)?;
     let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
 
+    // if the depositor was referred, its [`VaultReferrer`] can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut vr = ctx.vault_referrer(&vault_depositor);
+    vault_depositor.validate_vault_referrer(&vr)?;
+    let mut vr = vr.as_mut().map(|vr| vr.load_mut()).transpose()?;
+
+    // if the depositor is in a [`ShareClass`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut sc = ctx.share_class(&vault_depositor);
+    vault_depositor.validate_share_class(&sc)?;
+    let mut sc = sc.as_mut().map(|sc| sc.load_mut()).transpose()?;
+
+    // if the vault has a [`WithdrawGate`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut gate = ctx.withdraw_gate(&vault);
+    vault.validate_withdraw_gate(&gate)?;
+    let mut gate = gate.as_mut().map(|gate| gate.load_mut()).transpose()?;
+
     let user = ctx.accounts.drift_user.load()?;
     let spot_market_index = vault.spot_market_index;
 
@@ -33,17 +64,65 @@ pub fn force_withdraw<'c: 'info, 'info>(
         mut oracle_map,
     } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
 
-    let vault_equity =
-        vault.calculate_equity(&user, &perp_market_map, &spot_market_map, &mut oracle_map)?;
-
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
+
+    vault_depositor.settle_fees(
+        sc.as_deref_mut(),
+        vr.as_deref_mut(),
+        vault_equity,
+        &mut vault,
+        &mut vp,
+        clock.unix_timestamp,
+    )?;
+
+    // a gated vault only fills the part of the request that fits in the epoch's withdraw capacity
+    let remaining_request = match gate.as_mut() {
+        Some(gate) => gate.split_request(
+            &mut vault_depositor.last_withdraw_request,
+            &vault,
+            vault_equity,
+            clock.unix_timestamp,
+        )?,
+        None => None,
+    };
+
+    let class_shares_before = vault_depositor.get_vault_shares();
     let (withdraw_amount, _) =
         vault_depositor.withdraw(vault_equity, &mut vault, &mut vp, clock.unix_timestamp)?;
 
+    if let Some(gate) = gate.as_mut() {
+        let authority = vault_depositor.authority;
+        gate.record_withdraw(
+            authority,
+            withdraw_amount,
+            remaining_request,
+            &mut vault_depositor.last_withdraw_request,
+            clock.unix_timestamp,
+        )?;
+    }
+
+    if let Some(sc) = sc.as_mut() {
+        sc.update_user_shares(class_shares_before, vault_depositor.get_vault_shares())?;
+    }
+
     msg!("force_withdraw_amount: {}", withdraw_amount);
 
     drop(vault);
     drop(user);
     drop(vp);
+    drop(vr);
+    drop(sc);
+    drop(gate);
 
     ctx.drift_withdraw(withdraw_amount)?;
 
@@ -54,11 +133,9 @@ pub fn force_withdraw<'c: 'info, 'info>(
 
 #[derive(Accounts)]
 pub struct ForceWithdraw<'info> {
-    #[account(
-        mut,
-        constraint = is_manager_for_vault(&vault, &manager)? || is_delegate_for_vault(&vault, &manager)?
-    )]
+    #[account(mut)]
     pub vault: AccountLoader<'info, Vault>,
+    /// The vault manager or its operator, checked in the handler
     pub manager: Signer<'info>,
     #[account(
         mut,
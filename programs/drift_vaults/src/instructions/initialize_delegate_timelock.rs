@@ -0,0 +1,47 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::is_manager_for_vault;
+use crate::state::{DelegateTimelock, Vault, VaultCompanion};
+use crate::Size;
+
+pub fn initialize_delegate_timelock(
+    ctx: Context<InitializeDelegateTimelock>,
+    delay: i64,
+) -> Result<()> {
+    let mut vault = ctx.accounts.vault.load_mut()?;
+
+    DelegateTimelock::validate_delay(delay, &vault)?;
+
+    let mut delegate_timelock = ctx.accounts.delegate_timelock.load_init()?;
+    *delegate_timelock = DelegateTimelock::new(
+        ctx.accounts.vault.key(),
+        ctx.accounts.delegate_timelock.key(),
+        delay,
+        ctx.bumps.delegate_timelock,
+    );
+    vault.add_companion(VaultCompanion::DelegateTimelock);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeDelegateTimelock<'info> {
+    #[account(
+        mut,
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        init,
+        seeds = [b"delegate_timelock", vault.key().as_ref()],
+        space = DelegateTimelock::SIZE,
+        bump,
+        payer = payer
+    )]
+    pub delegate_timelock: AccountLoader<'info, DelegateTimelock>,
+    pub manager: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
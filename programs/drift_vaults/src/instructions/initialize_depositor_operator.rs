@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::{is_authority_for_vault_depositor, is_vault_for_vault_depositor};
+use crate::state::{DepositorOperator, Vault, VaultDepositor};
+use crate::Size;
+
+pub fn initialize_depositor_operator(
+    ctx: Context<InitializeDepositorOperator>,
+    params: DepositorOperatorParams,
+) -> Result<()> {
+    let mut depositor_operator = ctx.accounts.depositor_operator.load_init()?;
+    *depositor_operator = DepositorOperator::new(
+        ctx.accounts.vault_depositor.key(),
+        ctx.accounts.depositor_operator.key(),
+        params.operator,
+        params.withdraw_destination,
+        ctx.bumps.depositor_operator,
+    );
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct DepositorOperatorParams {
+    pub operator: Pubkey,
+    pub withdraw_destination: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct InitializeDepositorOperator<'info> {
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        constraint = is_vault_for_vault_depositor(&vault_depositor, &vault)?,
+        constraint = is_authority_for_vault_depositor(&vault_depositor, &authority)?,
+    )]
+    pub vault_depositor: AccountLoader<'info, VaultDepositor>,
+    #[account(
+        init,
+        seeds = [b"depositor_operator", vault_depositor.key().as_ref()],
+        space = DepositorOperator::SIZE,
+        bump,
+        payer = payer
+    )]
+    pub depositor_operator: AccountLoader<'info, DepositorOperator>,
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
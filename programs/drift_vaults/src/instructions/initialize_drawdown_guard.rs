@@ -0,0 +1,56 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::is_manager_for_vault;
+use crate::state::{DrawdownGuard, Vault, VaultCompanion};
+use crate::Size;
+
+pub fn initialize_drawdown_guard(
+    ctx: Context<InitializeDrawdownGuard>,
+    params: DrawdownGuardParams,
+) -> Result<()> {
+    DrawdownGuard::validate_params(params.max_drawdown_bps, params.reset_cooldown)?;
+
+    let mut vault = ctx.accounts.vault.load_mut()?;
+
+    let mut drawdown_guard = ctx.accounts.drawdown_guard.load_init()?;
+    *drawdown_guard = DrawdownGuard::new(
+        ctx.accounts.vault.key(),
+        ctx.accounts.drawdown_guard.key(),
+        params.max_drawdown_bps,
+        params.reset_cooldown,
+        ctx.bumps.drawdown_guard,
+    );
+    drawdown_guard.high_water_shares_base = vault.shares_base;
+
+    vault.add_companion(VaultCompanion::DrawdownGuard);
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct DrawdownGuardParams {
+    pub max_drawdown_bps: u16,
+    pub reset_cooldown: i64,
+}
+
+#[derive(Accounts)]
+pub struct InitializeDrawdownGuard<'info> {
+    #[account(
+        mut,
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        init,
+        seeds = [b"drawdown_guard", vault.key().as_ref()],
+        space = DrawdownGuard::SIZE,
+        bump,
+        payer = payer
+    )]
+    pub drawdown_guard: AccountLoader<'info, DrawdownGuard>,
+    pub manager: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,95 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{FeeRecipients, ManagerPositionProvider, Vault, VaultProtocolProvider};
+use crate::{error::ErrorCode, validate, Size};
+
+pub fn initialize_fee_recipients<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, InitializeFeeRecipients<'info>>,
+    params: FeeRecipientsParams,
+) -> Result<()> {
+    let vault = ctx.accounts.vault.load()?;
+
+    validate!(!vault.in_liquidation(), ErrorCode::OngoingLiquidation)?;
+
+    // backwards compatible: if last rem acct does not deserialize into [`VaultProtocol`] then it's a legacy vault.
+    let mut vp = ctx.vault_protocol();
+    vault.validate_vault_protocol(&vp)?;
+    let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
+
+    // if the vault has a [`ManagerPosition`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut mp = ctx.manager_position(&vault);
+    vault.validate_manager_position(&mp)?;
+    let mut mp = mp.as_mut().map(|mp| mp.load_mut()).transpose()?;
+
+    FeeRecipients::validate_authority(params.is_protocol, &vault, &vp, ctx.accounts.authority.key)?;
+
+    if params.is_protocol {
+        let vp = vp.as_mut().ok_or(ErrorCode::VaultProtocolMissing)?;
+        validate!(
+            !vp.last_protocol_withdraw_request.pending(),
+            ErrorCode::WithdrawInProgress,
+            "Protocol has a pending withdraw request"
+        )?;
+        vp.fee_recipients = true;
+    } else {
+        // fee shares are only tracked separately from manager capital by the [`ManagerPosition`]
+        let mp = mp.as_mut().ok_or(ErrorCode::ManagerPositionMissing)?;
+        validate!(
+            !mp.last_withdraw_request.pending(),
+            ErrorCode::WithdrawInProgress,
+            "Manager has a pending withdraw request"
+        )?;
+        mp.fee_recipients = true;
+    }
+
+    let mut fee_recipients = ctx.accounts.fee_recipients.load_init()?;
+    *fee_recipients = FeeRecipients::new(
+        ctx.accounts.vault.key(),
+        ctx.accounts.fee_recipients.key(),
+        params.is_protocol,
+        vault.shares_base,
+        ctx.bumps.fee_recipients,
+    );
+    fee_recipients.set_recipients(&params.recipients())?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct FeeRecipientParams {
+    pub token_account: Pubkey,
+    pub weight_bps: u16,
+}
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct FeeRecipientsParams {
+    pub is_protocol: bool,
+    pub recipients: Vec<FeeRecipientParams>,
+}
+
+impl FeeRecipientsParams {
+    pub fn recipients(&self) -> Vec<(Pubkey, u16)> {
+        self.recipients
+            .iter()
+            .map(|recipient| (recipient.token_account, recipient.weight_bps))
+            .collect()
+    }
+}
+
+#[derive(Accounts)]
+#[instruction(params: FeeRecipientsParams)]
+pub struct InitializeFeeRecipients<'info> {
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        init,
+        seeds = [b"fee_recipients", vault.key().as_ref(), &[params.is_protocol as u8]],
+        space = FeeRecipients::SIZE,
+        bump,
+        payer = authority
+    )]
+    pub fee_recipients: AccountLoader<'info, FeeRecipients>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,86 @@
+use anchor_lang::prelude::*;
+use drift::instructions::optional_accounts::AccountMaps;
+use drift::state::user::User;
+
+use crate::constraints::{is_manager_for_vault, is_user_for_vault, is_user_stats_for_vault};
+use crate::state::{
+    ManagerPosition, SubAccountProvider, Vault, VaultAllocationsProvider, VaultProtocolProvider,
+};
+use crate::{error::ErrorCode, validate, AccountMapProvider, Size};
+
+pub fn initialize_manager_position<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, InitializeManagerPosition<'info>>,
+) -> Result<()> {
+    let clock = &Clock::get()?;
+    let mut vault = ctx.accounts.vault.load_mut()?;
+
+    validate!(!vault.in_liquidation(), ErrorCode::OngoingLiquidation)?;
+
+    // backwards compatible: if last rem acct does not deserialize into [`VaultProtocol`] then it's a legacy vault.
+    let mut vp = ctx.vault_protocol();
+    vault.validate_vault_protocol(&vp)?;
+    let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
+
+    let user = ctx.accounts.drift_user.load()?;
+
+    let AccountMaps {
+        perp_market_map,
+        spot_market_map,
+        mut oracle_map,
+    } = ctx.load_maps(clock.slot, None, vp.is_some())?;
+
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
+
+    let mut manager_position = ctx.accounts.manager_position.load_init()?;
+    *manager_position = ManagerPosition::new(
+        ctx.accounts.vault.key(),
+        ctx.accounts.manager_position.key(),
+        *ctx.accounts.manager.key,
+        ctx.bumps.manager_position,
+        clock.unix_timestamp,
+    );
+    manager_position.initialize(&mut vault, &mut vp, vault_equity, clock.unix_timestamp)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeManagerPosition<'info> {
+    #[account(
+        mut,
+        constraint = is_manager_for_vault(&vault, &manager)?
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        init,
+        seeds = [b"manager_position", vault.key().as_ref()],
+        space = ManagerPosition::SIZE,
+        bump,
+        payer = manager
+    )]
+    pub manager_position: AccountLoader<'info, ManagerPosition>,
+    #[account(mut)]
+    pub manager: Signer<'info>,
+    #[account(
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked by constraint
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
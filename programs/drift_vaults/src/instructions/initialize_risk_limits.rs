@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::is_manager_for_vault;
+use crate::state::{RiskLimits, Vault, VaultCompanion};
+use crate::Size;
+
+pub fn initialize_risk_limits(
+    ctx: Context<InitializeRiskLimits>,
+    params: RiskLimitsParams,
+) -> Result<()> {
+    let mut risk_limits = ctx.accounts.risk_limits.load_init()?;
+    *risk_limits = RiskLimits::new(
+        ctx.accounts.vault.key(),
+        ctx.accounts.risk_limits.key(),
+        ctx.bumps.risk_limits,
+    );
+
+    risk_limits.update_limits(
+        &params.allowed_perp_markets,
+        &params.allowed_spot_markets,
+        params.max_leverage,
+        params.max_position_notional,
+        params.revoke_delegate,
+    )?;
+
+    let mut vault = ctx.accounts.vault.load_mut()?;
+    vault.add_companion(VaultCompanion::RiskLimits);
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct RiskLimitsParams {
+    pub allowed_perp_markets: Vec<u16>,
+    pub allowed_spot_markets: Vec<u16>,
+    pub max_leverage: u32,
+    pub max_position_notional: u64,
+    pub revoke_delegate: bool,
+}
+
+#[derive(Accounts)]
+pub struct InitializeRiskLimits<'info> {
+    #[account(
+        mut,
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        init,
+        seeds = [b"risk_limits", vault.key().as_ref()],
+        space = RiskLimits::SIZE,
+        bump,
+        payer = payer
+    )]
+    pub risk_limits: AccountLoader<'info, RiskLimits>,
+    pub manager: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,83 @@
+use anchor_lang::prelude::*;
+use drift::math::casting::Cast;
+use drift::math::constants::PERCENTAGE_PRECISION_U64;
+
+use crate::constraints::is_manager_for_vault;
+use crate::state::{ShareClass, Vault};
+use crate::{error::ErrorCode, validate, Size};
+
+pub fn initialize_share_class(
+    ctx: Context<InitializeShareClass>,
+    params: ShareClassParams,
+) -> Result<()> {
+    let vault = ctx.accounts.vault.load()?;
+
+    validate!(
+        params.class_id != 0,
+        ErrorCode::InvalidShareClass,
+        "share class id 0 is reserved for the vault's default terms"
+    )?;
+
+    // the vault management fee is charged by minting shares, which would dilute every class
+    validate!(
+        vault.management_fee == 0,
+        ErrorCode::InvalidShareClass,
+        "vault management fee must be 0 to use share classes"
+    )?;
+
+    validate!(
+        params.management_fee < PERCENTAGE_PRECISION_U64.cast()?,
+        ErrorCode::InvalidShareClass,
+        "management fee must be < 100%"
+    )?;
+
+    validate!(
+        params.profit_share < PERCENTAGE_PRECISION_U64.cast()?,
+        ErrorCode::InvalidShareClass,
+        "profit share must be < 100%"
+    )?;
+
+    let mut share_class = ctx.accounts.share_class.load_init()?;
+    *share_class = ShareClass::new(
+        ctx.accounts.vault.key(),
+        ctx.accounts.share_class.key(),
+        params.class_id,
+        params.name,
+        params.management_fee,
+        params.profit_share,
+        vault.shares_base,
+        ctx.bumps.share_class,
+    );
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct ShareClassParams {
+    pub class_id: u8,
+    pub name: [u8; 32],
+    pub management_fee: u32,
+    pub profit_share: u32,
+}
+
+#[derive(Accounts)]
+#[instruction(params: ShareClassParams)]
+pub struct InitializeShareClass<'info> {
+    #[account(
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        init,
+        seeds = [b"share_class", vault.key().as_ref(), &[params.class_id]],
+        space = ShareClass::SIZE,
+        bump,
+        payer = payer
+    )]
+    pub share_class: AccountLoader<'info, ShareClass>,
+    pub manager: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
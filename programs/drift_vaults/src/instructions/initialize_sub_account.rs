@@ -0,0 +1,78 @@
+use anchor_lang::prelude::*;
+use drift::cpi::accounts::InitializeUser;
+use drift::program::Drift;
+
+use crate::constraints::{is_manager_for_vault, is_user_stats_for_vault};
+use crate::declare_vault_seeds;
+use crate::error::ErrorCode;
+use crate::state::{Vault, VaultCompanion, MAX_VAULT_SUB_ACCOUNTS};
+use crate::validate;
+
+pub fn initialize_sub_account<'info>(
+    ctx: Context<'_, '_, '_, 'info, InitializeSubAccount<'info>>,
+    sub_account_id: u16,
+) -> Result<()> {
+    let mut vault = ctx.accounts.vault.load_mut()?;
+
+    validate!(
+        sub_account_id > 0 && sub_account_id < MAX_VAULT_SUB_ACCOUNTS,
+        ErrorCode::InvalidSubAccount,
+        "sub-account id must be between 1 and {}",
+        MAX_VAULT_SUB_ACCOUNTS - 1
+    )?;
+    validate!(
+        !vault.shutdown,
+        ErrorCode::VaultShutdown,
+        "vault is shut down"
+    )?;
+
+    vault.add_companion(VaultCompanion::SubAccounts);
+
+    let name = vault.name;
+
+    drop(vault);
+
+    declare_vault_seeds!(ctx.accounts.vault, seeds);
+
+    let cpi_accounts = InitializeUser {
+        user_stats: ctx.accounts.drift_user_stats.clone(),
+        user: ctx.accounts.drift_user.clone(),
+        state: ctx.accounts.drift_state.clone(),
+        authority: ctx.accounts.vault.to_account_info().clone(),
+        payer: ctx.accounts.payer.to_account_info().clone(),
+        rent: ctx.accounts.rent.to_account_info().clone(),
+        system_program: ctx.accounts.system_program.to_account_info().clone(),
+    };
+    let drift_program = ctx.accounts.drift_program.to_account_info().clone();
+    let cpi_context = CpiContext::new_with_signer(drift_program, cpi_accounts, seeds);
+    drift::cpi::initialize_user(cpi_context, sub_account_id, name)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeSubAccount<'info> {
+    #[account(
+        mut,
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    pub manager: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    #[account(
+        mut,
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(mut)]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountInfo<'info>,
+    #[account(mut)]
+    /// CHECK: checked in drift cpi
+    pub drift_state: AccountInfo<'info>,
+    pub drift_program: Program<'info, Drift>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::is_manager_for_vault;
+use crate::error::ErrorCode;
+use crate::state::{Vault, VaultAllocations, VaultCompanion};
+use crate::{validate, Size};
+
+pub fn initialize_vault_allocations(ctx: Context<InitializeVaultAllocations>) -> Result<()> {
+    let mut vault = ctx.accounts.vault.load_mut()?;
+
+    // parents value their position in the vault without allocations of its own
+    validate!(
+        !vault.has_companion(VaultCompanion::ParentVault),
+        ErrorCode::InvalidVaultAllocation,
+        "vault is held by another vault's allocations"
+    )?;
+
+    let mut vault_allocations = ctx.accounts.vault_allocations.load_init()?;
+    *vault_allocations = VaultAllocations::new(
+        ctx.accounts.vault.key(),
+        ctx.accounts.vault_allocations.key(),
+        ctx.bumps.vault_allocations,
+    );
+
+    vault.add_companion(VaultCompanion::VaultAllocations);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeVaultAllocations<'info> {
+    #[account(
+        mut,
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        init,
+        seeds = [b"vault_allocations", vault.key().as_ref()],
+        space = VaultAllocations::SIZE,
+        bump,
+        payer = payer
+    )]
+    pub vault_allocations: AccountLoader<'info, VaultAllocations>,
+    pub manager: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
@@ -9,6 +9,7 @@ pub fn initialize_vault_depositor(ctx: Context<InitializeVaultDepositor>) -> Res
     vault_depositor.vault = ctx.accounts.vault.key();
     vault_depositor.pubkey = ctx.accounts.vault_depositor.key();
     vault_depositor.authority = *ctx.accounts.authority.key;
+    vault_depositor.deposit_ts = Clock::get()?.unix_timestamp;
 
     let vault = ctx.accounts.vault.load()?;
     if vault.permissioned {
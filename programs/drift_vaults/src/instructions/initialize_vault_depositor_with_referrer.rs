@@ -0,0 +1,66 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::is_vault_for_vault_referrer;
+use crate::error::ErrorCode;
+use crate::state::{Vault, VaultReferrer};
+use crate::{validate, Size, VaultDepositor};
+
+pub fn initialize_vault_depositor_with_referrer(
+    ctx: Context<InitializeVaultDepositorWithReferrer>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    let mut vault_depositor = ctx.accounts.vault_depositor.load_init()?;
+    vault_depositor.vault = ctx.accounts.vault.key();
+    vault_depositor.pubkey = ctx.accounts.vault_depositor.key();
+    vault_depositor.authority = *ctx.accounts.authority.key;
+    vault_depositor.referrer = ctx.accounts.vault_referrer.key();
+    vault_depositor.last_fee_ts = now;
+    vault_depositor.deposit_ts = now;
+
+    validate!(
+        vault_depositor.authority != ctx.accounts.vault_referrer.load()?.authority,
+        ErrorCode::InvalidVaultDepositorInitialization,
+        "Vault depositor can not refer itself"
+    )?;
+
+    let vault = ctx.accounts.vault.load()?;
+    if vault.permissioned {
+        validate!(
+            vault.manager == *ctx.accounts.payer.key,
+            ErrorCode::PermissionedVault,
+            "Vault depositor can only be created by vault manager"
+        )?;
+    } else {
+        validate!(
+            vault_depositor.authority == *ctx.accounts.payer.key,
+            ErrorCode::Default,
+            "Vault depositor authority must pay to create account"
+        )?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeVaultDepositorWithReferrer<'info> {
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+      init,
+      seeds = [b"vault_depositor", vault.key().as_ref(), authority.key().as_ref()],
+      space = VaultDepositor::SIZE,
+      bump,
+      payer = payer
+    )]
+    pub vault_depositor: AccountLoader<'info, VaultDepositor>,
+    #[account(
+        constraint = is_vault_for_vault_referrer(&vault_referrer, &vault)?
+    )]
+    pub vault_referrer: AccountLoader<'info, VaultReferrer>,
+    /// CHECK: dont need to sign if vault is permissioned
+    pub authority: AccountInfo<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,36 @@
+use anchor_lang::prelude::*;
+
+use crate::state::{Vault, VaultReferrer};
+use crate::Size;
+
+pub fn initialize_vault_referrer(ctx: Context<InitializeVaultReferrer>) -> Result<()> {
+    let vault = ctx.accounts.vault.load()?;
+
+    let mut vault_referrer = ctx.accounts.vault_referrer.load_init()?;
+    *vault_referrer = VaultReferrer::new(
+        ctx.accounts.vault.key(),
+        ctx.accounts.vault_referrer.key(),
+        *ctx.accounts.authority.key,
+        vault.shares_base,
+        ctx.bumps.vault_referrer,
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct InitializeVaultReferrer<'info> {
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        init,
+        seeds = [b"vault_referrer", vault.key().as_ref(), authority.key().as_ref()],
+        space = VaultReferrer::SIZE,
+        bump,
+        payer = authority
+    )]
+    pub vault_referrer: AccountLoader<'info, VaultReferrer>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::is_manager_for_vault;
+use crate::state::{Vault, VaultRoles};
+use crate::Size;
+
+pub fn initialize_vault_roles(
+    ctx: Context<InitializeVaultRoles>,
+    params: VaultRolesParams,
+) -> Result<()> {
+    let mut vault_roles = ctx.accounts.vault_roles.load_init()?;
+    *vault_roles = VaultRoles::new(
+        ctx.accounts.vault.key(),
+        ctx.accounts.vault_roles.key(),
+        params.risk_officer,
+        params.keeper,
+        params.operator,
+        ctx.bumps.vault_roles,
+    );
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct VaultRolesParams {
+    pub risk_officer: Pubkey,
+    pub keeper: Pubkey,
+    pub operator: Pubkey,
+}
+
+#[derive(Accounts)]
+pub struct InitializeVaultRoles<'info> {
+    #[account(
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        init,
+        seeds = [b"vault_roles", vault.key().as_ref()],
+        space = VaultRoles::SIZE,
+        bump,
+        payer = payer
+    )]
+    pub vault_roles: AccountLoader<'info, VaultRoles>,
+    pub manager: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
@@ -76,7 +76,7 @@ pub fn initialize_vault_with_protocol<'c: 'info, 'info>(
         ErrorCode::InvalidVaultInitialization,
         "hurdle rate not implemented"
     )?;
-    vault.hurdle_rate = params.hurdle_rate;
+    vault.hurdle_rate = params.hurdle_rate.cast()?;
     vault.bump = bump;
     vault.permissioned = params.permissioned;
 
@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::is_manager_for_vault;
+use crate::state::{Vault, VaultCompanion, WithdrawGate};
+use crate::Size;
+
+pub fn initialize_withdraw_gate(
+    ctx: Context<InitializeWithdrawGate>,
+    params: WithdrawGateParams,
+) -> Result<()> {
+    let mut vault = ctx.accounts.vault.load_mut()?;
+
+    WithdrawGate::validate_params(params.gate_bps, params.epoch_duration, &vault)?;
+
+    let mut withdraw_gate = ctx.accounts.withdraw_gate.load_init()?;
+    *withdraw_gate = WithdrawGate::new(
+        ctx.accounts.vault.key(),
+        ctx.accounts.withdraw_gate.key(),
+        params.gate_bps,
+        params.epoch_duration,
+        ctx.bumps.withdraw_gate,
+    );
+
+    vault.add_companion(VaultCompanion::WithdrawGate);
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct WithdrawGateParams {
+    pub gate_bps: u16,
+    pub epoch_duration: i64,
+}
+
+#[derive(Accounts)]
+pub struct InitializeWithdrawGate<'info> {
+    #[account(
+        mut,
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        init,
+        seeds = [b"withdraw_gate", vault.key().as_ref()],
+        space = WithdrawGate::SIZE,
+        bump,
+        payer = payer
+    )]
+    pub withdraw_gate: AccountLoader<'info, WithdrawGate>,
+    pub manager: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
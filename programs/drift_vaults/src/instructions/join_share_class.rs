@@ -0,0 +1,59 @@
+use anchor_lang::prelude::*;
+use drift::math::safe_math::SafeMath;
+
+use crate::constraints::{
+    is_authority_for_vault_depositor, is_manager_for_vault, is_vault_for_share_class,
+};
+use crate::state::{ShareClass, Vault, VaultDepositor, VaultDepositorBase};
+use crate::{error::ErrorCode, validate};
+
+pub fn join_share_class(ctx: Context<JoinShareClass>) -> Result<()> {
+    let mut vault_depositor = ctx.accounts.vault_depositor.load_mut()?;
+    let mut share_class = ctx.accounts.share_class.load_mut()?;
+
+    // fees already paid on existing shares were charged under other terms
+    validate!(
+        vault_depositor.get_vault_shares() == 0,
+        ErrorCode::InvalidShareClass,
+        "vault depositor must have no shares to join a share class"
+    )?;
+
+    validate!(
+        vault_depositor.share_class == 0,
+        ErrorCode::InvalidShareClass,
+        "vault depositor is already in share class {}",
+        vault_depositor.share_class
+    )?;
+
+    vault_depositor.share_class = share_class.class_id;
+    vault_depositor.class_management_fee = share_class.management_fee;
+    vault_depositor.class_profit_share = share_class.profit_share;
+    vault_depositor.last_fee_ts = Clock::get()?.unix_timestamp;
+
+    share_class.num_depositors = share_class.num_depositors.safe_add(1)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct JoinShareClass<'info> {
+    #[account(
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        seeds = [b"vault_depositor", vault.key().as_ref(), authority.key().as_ref()],
+        bump,
+        constraint = is_authority_for_vault_depositor(&vault_depositor, &authority)?,
+    )]
+    pub vault_depositor: AccountLoader<'info, VaultDepositor>,
+    #[account(
+        mut,
+        constraint = is_vault_for_share_class(&share_class, &vault)?,
+    )]
+    pub share_class: AccountLoader<'info, ShareClass>,
+    /// the depositor agrees to the class terms and the manager admits them into the class
+    pub authority: Signer<'info>,
+    pub manager: Signer<'info>,
+}
@@ -7,9 +7,15 @@ use drift::state::user::User;
 use crate::constraints::{
     is_authority_for_vault_depositor, is_user_for_vault, is_user_stats_for_vault,
 };
-use crate::drift_cpi::{UpdateUserDelegateCPI, UpdateUserReduceOnlyCPI};
-use crate::state::{Vault, VaultDepositor, VaultProtocolProvider};
-use crate::{declare_vault_seeds, implement_update_user_delegate_cpi};
+use crate::drift_cpi::{
+    UpdateSubAccountDelegateCPI, UpdateSubAccountReduceOnlyCPI, UpdateUserDelegateCPI,
+    UpdateUserReduceOnlyCPI,
+};
+use crate::error::ErrorCode;
+use crate::state::{
+    SubAccountProvider, Vault, VaultAllocationsProvider, VaultDepositor, VaultProtocolProvider,
+};
+use crate::{declare_vault_seeds, implement_update_user_delegate_cpi, validate};
 use crate::{implement_update_user_reduce_only_cpi, AccountMapProvider};
 
 pub fn liquidate<'c: 'info, 'info>(
@@ -33,13 +39,24 @@ pub fn liquidate<'c: 'info, 'info>(
         mut oracle_map,
     } = ctx.load_maps(clock.slot, Some(vault.spot_market_index), vp.is_some())?;
 
+    // 0. A shut down vault is wound down through shutdown_withdraw instead
+    validate!(!vault.shutdown, ErrorCode::VaultShutdown)?;
     // 1. Check the vault depositor has waited the redeem period
     vault_depositor
         .last_withdraw_request
         .check_redeem_period_finished(&vault, now)?;
     // 2. Check that the depositor is unable to withdraw
-    let vault_equity =
-        vault.calculate_equity(&user, &perp_market_map, &spot_market_map, &mut oracle_map)?;
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
     vault_depositor.check_cant_withdraw(
         &vault,
         vault_equity,
@@ -59,6 +76,11 @@ pub fn liquidate<'c: 'info, 'info>(
 
     ctx.drift_update_user_delegate(vault_depositor.authority)?;
     ctx.drift_update_user_reduce_only(true)?;
+    // the liquidator unwinds the sub-accounts' positions too
+    for sub_account in sub_accounts.iter() {
+        ctx.drift_update_sub_account_delegate(sub_account, vault_depositor.authority)?;
+        ctx.drift_update_sub_account_reduce_only(sub_account, true)?;
+    }
 
     Ok(())
 }
@@ -105,3 +127,31 @@ impl<'info> UpdateUserReduceOnlyCPI for Context<'_, '_, '_, 'info, Liquidate<'in
         Ok(())
     }
 }
+
+impl<'c: 'info, 'info> UpdateSubAccountDelegateCPI<'c>
+    for Context<'_, '_, 'c, 'info, Liquidate<'info>>
+{
+    fn drift_update_sub_account_delegate(
+        &self,
+        sub_account: &AccountLoader<'c, User>,
+        delegate: Pubkey,
+    ) -> Result<()> {
+        let sub_account_id = sub_account.load()?.sub_account_id;
+        implement_update_user_delegate_cpi!(self, delegate, sub_account_id, sub_account);
+        Ok(())
+    }
+}
+
+impl<'c: 'info, 'info> UpdateSubAccountReduceOnlyCPI<'c>
+    for Context<'_, '_, 'c, 'info, Liquidate<'info>>
+{
+    fn drift_update_sub_account_reduce_only(
+        &self,
+        sub_account: &AccountLoader<'c, User>,
+        reduce_only: bool,
+    ) -> Result<()> {
+        let sub_account_id = sub_account.load()?.sub_account_id;
+        implement_update_user_reduce_only_cpi!(self, reduce_only, sub_account_id, sub_account);
+        Ok(())
+    }
+}
@@ -4,7 +4,10 @@ use drift::math::casting::Cast;
 use drift::state::user::User;
 
 use crate::constraints::{is_manager_for_vault, is_user_for_vault, is_user_stats_for_vault};
-use crate::state::{Vault, VaultProtocolProvider};
+use crate::state::{
+    ManagerPositionProvider, SubAccountProvider, Vault, VaultAllocationsProvider,
+    VaultProtocolProvider,
+};
 use crate::AccountMapProvider;
 
 pub fn manager_cancel_withdraw_request<'c: 'info, 'info>(
@@ -18,6 +21,11 @@ pub fn manager_cancel_withdraw_request<'c: 'info, 'info>(
     vault.validate_vault_protocol(&vp)?;
     let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
 
+    // if the vault has a [`ManagerPosition`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut mp = ctx.manager_position(&vault);
+    vault.validate_manager_position(&mp)?;
+    let mut mp = mp.as_mut().map(|mp| mp.load_mut()).transpose()?;
+
     let user = ctx.accounts.drift_user.load()?;
 
     let AccountMaps {
@@ -26,10 +34,28 @@ pub fn manager_cancel_withdraw_request<'c: 'info, 'info>(
         mut oracle_map,
     } = ctx.load_maps(clock.slot, None, vp.is_some())?;
 
-    let vault_equity =
-        vault.calculate_equity(&user, &perp_market_map, &spot_market_map, &mut oracle_map)?;
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
 
-    vault.manager_cancel_withdraw_request(&mut vp, vault_equity.cast()?, clock.unix_timestamp)?;
+    match mp.as_mut() {
+        Some(mp) => {
+            mp.cancel_withdraw_request(vault_equity.cast()?, vault, &mut vp, clock.unix_timestamp)?
+        }
+        None => vault.manager_cancel_withdraw_request(
+            &mut vp,
+            vault_equity.cast()?,
+            clock.unix_timestamp,
+        )?,
+    }
 
     Ok(())
 }
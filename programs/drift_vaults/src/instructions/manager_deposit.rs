@@ -7,7 +7,10 @@ use drift::state::user::User;
 
 use crate::constraints::{is_manager_for_vault, is_user_for_vault, is_user_stats_for_vault};
 use crate::drift_cpi::DepositCPI;
-use crate::state::{Vault, VaultProtocolProvider};
+use crate::state::{
+    ManagerPositionProvider, SubAccountProvider, Vault, VaultAllocationsProvider, VaultPauseFlag,
+    VaultProtocolProvider,
+};
 use crate::token_cpi::TokenTransferCPI;
 use crate::{declare_vault_seeds, AccountMapProvider};
 
@@ -18,12 +21,18 @@ pub fn manager_deposit<'c: 'info, 'info>(
     let clock = &Clock::get()?;
 
     let mut vault = ctx.accounts.vault.load_mut()?;
+    vault.validate_not_paused(VaultPauseFlag::Deposit, clock.unix_timestamp)?;
 
     // backwards compatible: if last rem acct does not deserialize into [`VaultProtocol`] then it's a legacy vault.
     let mut vp = ctx.vault_protocol();
     vault.validate_vault_protocol(&vp)?;
     let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
 
+    // if the vault has a [`ManagerPosition`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut mp = ctx.manager_position(&vault);
+    vault.validate_manager_position(&mp)?;
+    let mut mp = mp.as_mut().map(|mp| mp.load_mut()).transpose()?;
+
     let user = ctx.accounts.drift_user.load()?;
     let spot_market_index = vault.spot_market_index;
 
@@ -33,14 +42,41 @@ pub fn manager_deposit<'c: 'info, 'info>(
         mut oracle_map,
     } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
 
-    let vault_equity =
-        vault.calculate_equity(&user, &perp_market_map, &spot_market_map, &mut oracle_map)?;
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
+
+    let first_deposit = vault.is_first_deposit();
+    match mp.as_mut() {
+        Some(mp) => mp.deposit(
+            amount,
+            vault_equity,
+            &mut vault,
+            &mut vp,
+            clock.unix_timestamp,
+        )?,
+        None => vault.manager_deposit(&mut vp, amount, vault_equity, clock.unix_timestamp)?,
+    }
 
-    vault.manager_deposit(&mut vp, amount, vault_equity, clock.unix_timestamp)?;
+    if first_deposit {
+        vault.lock_manager_dead_shares(&mut vp)?;
+        if let Some(mp) = mp.as_mut() {
+            mp.sync(&vault, &mut vp)?;
+        }
+    }
 
     drop(vault);
     drop(user);
     drop(vp);
+    drop(mp);
 
     ctx.token_transfer(amount)?;
 
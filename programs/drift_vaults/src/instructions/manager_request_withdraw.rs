@@ -3,7 +3,10 @@ use drift::instructions::optional_accounts::AccountMaps;
 use drift::state::user::User;
 
 use crate::constraints::{is_manager_for_vault, is_user_for_vault, is_user_stats_for_vault};
-use crate::state::VaultProtocolProvider;
+use crate::state::{
+    ManagerPositionProvider, SubAccountProvider, VaultAllocationsProvider, VaultPauseFlag,
+    VaultProtocolProvider,
+};
 use crate::AccountMapProvider;
 use crate::{Vault, WithdrawUnit};
 
@@ -15,12 +18,18 @@ pub fn manager_request_withdraw<'c: 'info, 'info>(
     let clock = &Clock::get()?;
     let mut vault = ctx.accounts.vault.load_mut()?;
     let now = clock.unix_timestamp;
+    vault.validate_not_paused(VaultPauseFlag::RequestWithdraw, now)?;
 
     // backwards compatible: if last rem acct does not deserialize into [`VaultProtocol`] then it's a legacy vault.
     let mut vp = ctx.vault_protocol();
     vault.validate_vault_protocol(&vp)?;
     let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
 
+    // if the vault has a [`ManagerPosition`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut mp = ctx.manager_position(&vault);
+    vault.validate_manager_position(&mp)?;
+    let mut mp = mp.as_mut().map(|mp| mp.load_mut()).transpose()?;
+
     let user = ctx.accounts.drift_user.load()?;
     let spot_market_index = vault.spot_market_index;
 
@@ -30,10 +39,35 @@ pub fn manager_request_withdraw<'c: 'info, 'info>(
         mut oracle_map,
     } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
 
-    let vault_equity =
-        vault.calculate_equity(&user, &perp_market_map, &spot_market_map, &mut oracle_map)?;
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
 
-    vault.manager_request_withdraw(&mut vp, withdraw_amount, withdraw_unit, vault_equity, now)?;
+    match mp.as_mut() {
+        Some(mp) => mp.request_withdraw(
+            withdraw_amount,
+            withdraw_unit,
+            vault_equity,
+            &mut vault,
+            &mut vp,
+            now,
+        )?,
+        None => vault.manager_request_withdraw(
+            &mut vp,
+            withdraw_amount,
+            withdraw_unit,
+            vault_equity,
+            now,
+        )?,
+    }
 
     Ok(())
 }
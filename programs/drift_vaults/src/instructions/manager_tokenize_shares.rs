@@ -0,0 +1,220 @@
+use crate::constraints::{
+    is_manager_for_vault, is_mint_for_tokenized_depositor, is_tokenized_depositor_for_vault,
+    is_user_for_vault, is_user_stats_for_vault, is_vault_shares_base_for_tokenized_depositor,
+};
+use crate::error::ErrorCode;
+use crate::state::traits::VaultDepositorBase;
+use crate::state::{SubAccountProvider, VaultAllocationsProvider};
+use crate::token_cpi::MintTokensCPI;
+use crate::{validate, AccountMapProvider};
+use crate::{
+    ManagerPosition, TokenizedVaultDepositor, Vault, VaultPauseFlag, VaultProtocolProvider,
+    WithdrawUnit,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{mint_to, Mint, MintTo, Token, TokenAccount};
+use drift::instructions::optional_accounts::AccountMaps;
+use drift::math::safe_math::SafeMath;
+use drift::state::user::User;
+
+pub fn manager_tokenize_shares<'info>(
+    ctx: Context<'_, '_, 'info, 'info, ManagerTokenizeShares<'info>>,
+    amount: u64,
+    unit: WithdrawUnit,
+) -> Result<()> {
+    let clock = &Clock::get()?;
+    let now = clock.unix_timestamp;
+
+    let mut vault = ctx.accounts.vault.load_mut()?;
+
+    validate!(!vault.in_liquidation(), ErrorCode::OngoingLiquidation)?;
+    vault.validate_not_paused(VaultPauseFlag::Tokenize, now)?;
+
+    let mut manager_position = ctx.accounts.manager_position.load_mut()?;
+    let mut tokenized_vault_depositor = ctx.accounts.tokenized_vault_depositor.load_mut()?;
+
+    // backwards compatible: if last rem acct does not deserialize into [`VaultProtocol`] then it's a legacy vault.
+    let mut vp = ctx.vault_protocol();
+    vault.validate_vault_protocol(&vp)?;
+    let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
+
+    validate!(
+        vault.shares_base == tokenized_vault_depositor.vault_shares_base,
+        ErrorCode::InvalidVaultRebase,
+        "Vault has rebased, can no longer tokenize shares. Only redeem_tokens() is allowed. (shares base: {:?} vs. {:?})",
+        vault.shares_base,
+        tokenized_vault_depositor.vault_shares_base
+    )?;
+
+    let user = ctx.accounts.drift_user.load()?;
+    let spot_market_index = vault.spot_market_index;
+    let AccountMaps {
+        perp_market_map,
+        spot_market_map,
+        mut oracle_map,
+    } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
+
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
+
+    manager_position.settle(&mut vault, &mut vp, vault_equity, now)?;
+
+    validate!(
+        !manager_position.last_withdraw_request.pending(),
+        ErrorCode::InvalidVaultDeposit,
+        "Cannot tokenize shares with a pending withdraw request"
+    )?;
+
+    let manager_shares_before = manager_position.get_vault_shares();
+    let manager_net_deposits_before = manager_position.net_deposits;
+    let total_shares_before =
+        manager_shares_before.safe_add(tokenized_vault_depositor.get_vault_shares())?;
+
+    let total_supply_before = ctx.accounts.mint.supply;
+
+    let (shares_transferred, mut vp) = manager_position.transfer_shares(
+        &mut *tokenized_vault_depositor,
+        &mut vault,
+        &mut vp,
+        amount,
+        unit,
+        vault_equity,
+        now,
+    )?;
+    manager_position.on_shares_transferred(
+        shares_transferred,
+        manager_shares_before,
+        manager_net_deposits_before,
+        &mut vault,
+        &mut vp,
+    )?;
+    let tokens_to_mint = tokenized_vault_depositor.tokenize_shares(
+        &mut vault,
+        &mut vp,
+        total_supply_before,
+        vault_equity,
+        shares_transferred,
+        now,
+    )?;
+
+    // tokenized shares are no longer the manager's capital, so they can't take it below its minimum share
+    manager_position.validate_min_manager_share(&vault, 0)?;
+
+    let total_shares_after = manager_position
+        .get_vault_shares()
+        .safe_add(tokenized_vault_depositor.get_vault_shares())?;
+
+    validate!(
+        total_shares_after.eq(&total_shares_before),
+        ErrorCode::InvalidVaultSharesDetected,
+        "Total manager and tokenized depositor shares before != after"
+    )?;
+
+    let vault_name = vault.name;
+    let vault_bump = vault.bump;
+
+    drop(vault);
+    drop(manager_position);
+    drop(tokenized_vault_depositor);
+
+    ctx.mint(vault_name, vault_bump, tokens_to_mint)?;
+
+    msg!(
+        "Minted {} tokens to {}",
+        tokens_to_mint,
+        ctx.accounts.manager_token_account.key()
+    );
+
+    ctx.accounts.mint.reload()?;
+    let total_supply_after = ctx.accounts.mint.supply;
+
+    let supply_delta = total_supply_after.safe_sub(total_supply_before)?;
+    validate!(
+        supply_delta.eq(&tokens_to_mint),
+        ErrorCode::InvalidTokenization,
+        "Tokens minted ({}) != supply delta ({})",
+        tokens_to_mint,
+        supply_delta
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ManagerTokenizeShares<'info> {
+    #[account(
+        mut,
+        constraint = is_manager_for_vault(&vault, &manager)?
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    pub manager: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"manager_position", vault.key().as_ref()],
+        bump,
+    )]
+    pub manager_position: AccountLoader<'info, ManagerPosition>,
+    #[account(
+		mut,
+		constraint = is_tokenized_depositor_for_vault(&tokenized_vault_depositor, &vault)?,
+		constraint = is_vault_shares_base_for_tokenized_depositor(&vault.load()?.shares_base, &tokenized_vault_depositor)?,
+	)]
+    pub tokenized_vault_depositor: AccountLoader<'info, TokenizedVaultDepositor>,
+    #[account(
+        mut,
+        seeds = [b"mint", vault.key().as_ref(), vault.load()?.shares_base.to_string().as_bytes()],
+        bump,
+        mint::authority = vault.key(),
+		constraint = is_mint_for_tokenized_depositor(&mint.key(), &tokenized_vault_depositor)?,
+    )]
+    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        token::authority = manager,
+        token::mint = tokenized_vault_depositor.load()?.mint
+    )]
+    pub manager_token_account: Account<'info, TokenAccount>,
+    #[account(
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked by constraint
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> MintTokensCPI for Context<'_, '_, '_, 'info, ManagerTokenizeShares<'info>> {
+    fn mint(&self, vault_name: [u8; 32], vault_bump: u8, amount: u64) -> Result<()> {
+        let signature_seeds = Vault::get_vault_signer_seeds(&vault_name, &vault_bump);
+        let signers = &[&signature_seeds[..]];
+
+        let cpi_accounts = MintTo {
+            mint: self.accounts.mint.to_account_info(),
+            to: self.accounts.manager_token_account.to_account_info(),
+            authority: self.accounts.vault.to_account_info(),
+        };
+
+        let cpi_context = CpiContext::new_with_signer(
+            self.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signers,
+        );
+
+        mint_to(cpi_context, amount)?;
+
+        Ok(())
+    }
+}
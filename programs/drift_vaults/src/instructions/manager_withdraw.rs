@@ -8,7 +8,10 @@ use drift::state::user::User;
 
 use crate::constraints::{is_manager_for_vault, is_user_for_vault, is_user_stats_for_vault};
 use crate::drift_cpi::WithdrawCPI;
-use crate::state::{Vault, VaultProtocolProvider};
+use crate::state::{
+    ManagerPositionProvider, SubAccountProvider, Vault, VaultAllocationsProvider, VaultPauseFlag,
+    VaultProtocolProvider,
+};
 use crate::token_cpi::TokenTransferCPI;
 use crate::{declare_vault_seeds, AccountMapProvider};
 
@@ -18,6 +21,7 @@ pub fn manager_withdraw<'c: 'info, 'info>(
     let clock = &Clock::get()?;
     let mut vault = ctx.accounts.vault.load_mut()?;
     let now = clock.unix_timestamp;
+    vault.validate_not_paused(VaultPauseFlag::Withdraw, now)?;
 
     let user = ctx.accounts.drift_user.load()?;
     let spot_market_index = vault.spot_market_index;
@@ -27,20 +31,38 @@ pub fn manager_withdraw<'c: 'info, 'info>(
     vault.validate_vault_protocol(&vp)?;
     let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
 
+    // if the vault has a [`ManagerPosition`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut mp = ctx.manager_position(&vault);
+    vault.validate_manager_position(&mp)?;
+    let mut mp = mp.as_mut().map(|mp| mp.load_mut()).transpose()?;
+
     let AccountMaps {
         perp_market_map,
         spot_market_map,
         mut oracle_map,
     } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
 
-    let vault_equity =
-        vault.calculate_equity(&user, &perp_market_map, &spot_market_map, &mut oracle_map)?;
-
-    let manager_withdraw_amount = vault.manager_withdraw(&mut vp, vault_equity, now)?;
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
+
+    let manager_withdraw_amount = match mp.as_mut() {
+        Some(mp) => mp.withdraw(vault_equity, &mut vault, &mut vp, now)?,
+        None => vault.manager_withdraw(&mut vp, vault_equity, now)?,
+    };
 
     drop(vault);
     drop(user);
     drop(vp);
+    drop(mp);
 
     ctx.drift_withdraw(manager_withdraw_amount)?;
 
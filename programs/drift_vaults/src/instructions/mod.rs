@@ -1,66 +1,188 @@
+pub use accept_vault_depositor_transfer::*;
 pub use add_insurance_fund_stake::*;
+pub use add_vault_allocation::*;
+pub use apply_delegate_change::*;
 pub use apply_profit_share::*;
 pub use apply_rebase::*;
 pub use apply_rebase_tokenized_depositor::*;
+pub use cancel_delegate_change::*;
 pub use cancel_request_remove_insurance_fund_stake::*;
+pub use cancel_vault_depositor_transfer::*;
 pub use cancel_withdraw_request::*;
+pub use check_drawdown::*;
+pub use check_vault_risk::*;
+pub use child_vault_deposit::*;
+pub use child_vault_request_withdraw::*;
+pub use child_vault_withdraw::*;
+pub use claim_fees::*;
+pub use close_tokenized_vault_depositor::*;
+pub use close_vault::*;
+pub use close_vault_depositor::*;
 pub use deposit::*;
+pub use deposit_for::*;
+pub use deposit_from_drift_user::*;
 pub use force_withdraw::*;
+pub use initialize_delegate_timelock::*;
+pub use initialize_depositor_operator::*;
+pub use initialize_drawdown_guard::*;
+pub use initialize_fee_recipients::*;
 pub use initialize_insurance_fund_stake::*;
+pub use initialize_manager_position::*;
+pub use initialize_risk_limits::*;
+pub use initialize_share_class::*;
+pub use initialize_sub_account::*;
 pub use initialize_tokenized_vault_depositor::*;
 pub use initialize_vault::*;
+pub use initialize_vault_allocations::*;
 pub use initialize_vault_depositor::*;
+pub use initialize_vault_depositor_with_referrer::*;
+pub use initialize_vault_referrer::*;
+pub use initialize_vault_roles::*;
 pub use initialize_vault_with_protocol::*;
+pub use initialize_withdraw_gate::*;
+pub use join_share_class::*;
 pub use liquidate::*;
 pub use manager_cancel_withdraw_request::*;
 pub use manager_deposit::*;
 pub use manager_request_withdraw::*;
+pub use manager_tokenize_shares::*;
 pub use manager_withdraw::*;
+pub use operator_cancel_withdraw_request::*;
+pub use operator_request_withdraw::*;
+pub use operator_withdraw::*;
 pub use protocol_cancel_withdraw_request::*;
 pub use protocol_request_withdraw::*;
 pub use protocol_withdraw::*;
 pub use redeem_tokens::*;
+pub use referrer_cancel_withdraw_request::*;
+pub use referrer_request_withdraw::*;
+pub use referrer_withdraw::*;
 pub use remove_insurance_fund_stake::*;
+pub use remove_vault_allocation::*;
+pub use request_claim_fees::*;
 pub use request_remove_insurance_fund_stake::*;
+pub use request_tokenized_withdraw::*;
+pub use request_vault_depositor_transfer::*;
 pub use request_withdraw::*;
 pub use reset_delegate::*;
+pub use reset_drawdown_guard::*;
+pub use reset_risk_limits::*;
+pub use shutdown_sweep_sub_account::*;
+pub use shutdown_vault::*;
+pub use shutdown_withdraw::*;
 pub use tokenize_shares::*;
+pub use tokenized_withdraw::*;
+pub use transfer_sub_account_deposit::*;
 pub use update_delegate::*;
+pub use update_depositor_operator::*;
+pub use update_fee_recipients::*;
 pub use update_margin_trading_enabled::*;
+pub use update_min_manager_share::*;
+pub use update_risk_limits::*;
+pub use update_share_class::*;
+pub use update_sub_account_delegate::*;
 pub use update_vault::*;
+pub use update_vault_depositor_fee_override::*;
+pub use update_vault_fees::*;
+pub use update_vault_paused::*;
 pub use update_vault_protocol::*;
+pub use update_vault_reduce_only::*;
+pub use update_vault_roles::*;
+pub use update_withdraw_gate::*;
 pub use withdraw::*;
+pub use withdraw_to_drift_user::*;
 
+mod accept_vault_depositor_transfer;
 mod add_insurance_fund_stake;
+mod add_vault_allocation;
+mod apply_delegate_change;
 mod apply_profit_share;
 mod apply_rebase;
 mod apply_rebase_tokenized_depositor;
+mod cancel_delegate_change;
 mod cancel_request_remove_insurance_fund_stake;
+mod cancel_vault_depositor_transfer;
 mod cancel_withdraw_request;
+mod check_drawdown;
+mod check_vault_risk;
+mod child_vault_deposit;
+mod child_vault_request_withdraw;
+mod child_vault_withdraw;
+mod claim_fees;
+mod close_tokenized_vault_depositor;
+mod close_vault;
+mod close_vault_depositor;
 pub mod constraints;
 mod deposit;
+mod deposit_for;
+mod deposit_from_drift_user;
 mod force_withdraw;
+mod initialize_delegate_timelock;
+mod initialize_depositor_operator;
+mod initialize_drawdown_guard;
+mod initialize_fee_recipients;
 mod initialize_insurance_fund_stake;
+mod initialize_manager_position;
+mod initialize_risk_limits;
+mod initialize_share_class;
+mod initialize_sub_account;
 mod initialize_tokenized_vault_depositor;
 mod initialize_vault;
+mod initialize_vault_allocations;
 mod initialize_vault_depositor;
+mod initialize_vault_depositor_with_referrer;
+mod initialize_vault_referrer;
+mod initialize_vault_roles;
 mod initialize_vault_with_protocol;
+mod initialize_withdraw_gate;
+mod join_share_class;
 mod liquidate;
 mod manager_cancel_withdraw_request;
 mod manager_deposit;
 mod manager_request_withdraw;
+mod manager_tokenize_shares;
 mod manager_withdraw;
+mod operator_cancel_withdraw_request;
+mod operator_request_withdraw;
+mod operator_withdraw;
 mod protocol_cancel_withdraw_request;
 mod protocol_request_withdraw;
 mod protocol_withdraw;
 mod redeem_tokens;
+mod referrer_cancel_withdraw_request;
+mod referrer_request_withdraw;
+mod referrer_withdraw;
 mod remove_insurance_fund_stake;
+mod remove_vault_allocation;
+mod request_claim_fees;
 mod request_remove_insurance_fund_stake;
+mod request_tokenized_withdraw;
+mod request_vault_depositor_transfer;
 mod request_withdraw;
 mod reset_delegate;
+mod reset_drawdown_guard;
+mod reset_risk_limits;
+mod shutdown_sweep_sub_account;
+mod shutdown_vault;
+mod shutdown_withdraw;
 mod tokenize_shares;
+mod tokenized_withdraw;
+mod transfer_sub_account_deposit;
 mod update_delegate;
+mod update_depositor_operator;
+mod update_fee_recipients;
 mod update_margin_trading_enabled;
+mod update_min_manager_share;
+mod update_risk_limits;
+mod update_share_class;
+mod update_sub_account_delegate;
 mod update_vault;
+mod update_vault_depositor_fee_override;
+mod update_vault_fees;
+mod update_vault_paused;
 pub mod update_vault_protocol;
+mod update_vault_reduce_only;
+mod update_vault_roles;
+mod update_withdraw_gate;
 mod withdraw;
+mod withdraw_to_drift_user;
@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+use drift::state::user::User;
+
+use crate::constraints::{
+    is_operator_for_vault_depositor, is_user_for_vault, is_user_stats_for_vault,
+    is_vault_for_vault_depositor,
+};
+use crate::instructions::cancel_withdraw_request_for_vault_depositor;
+use crate::state::Vault;
+use crate::{DepositorOperator, VaultDepositor};
+
+/// Cancels a depositor's withdraw request on behalf of a depositor that named the signer its operator
+pub fn operator_cancel_withdraw_request<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, OperatorCancelWithdrawRequest<'info>>,
+) -> Result<()> {
+    cancel_withdraw_request_for_vault_depositor(
+        &ctx,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_depositor,
+        &ctx.accounts.drift_user,
+        &ctx.accounts.drift_user_stats,
+    )
+}
+
+#[derive(Accounts)]
+pub struct OperatorCancelWithdrawRequest<'info> {
+    #[account(mut)]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_vault_for_vault_depositor(&vault_depositor, &vault)?,
+    )]
+    pub vault_depositor: AccountLoader<'info, VaultDepositor>,
+    #[account(
+        seeds = [b"depositor_operator", vault_depositor.key().as_ref()],
+        bump,
+        constraint = is_operator_for_vault_depositor(&depositor_operator, &operator)?,
+    )]
+    pub depositor_operator: AccountLoader<'info, DepositorOperator>,
+    pub operator: Signer<'info>,
+    #[account(
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    /// CHECK: checked in drift cpi
+    pub drift_state: AccountInfo<'info>,
+}
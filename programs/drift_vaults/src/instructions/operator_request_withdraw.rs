@@ -0,0 +1,57 @@
+use anchor_lang::prelude::*;
+use drift::state::user::User;
+
+use crate::constraints::{
+    is_operator_for_vault_depositor, is_user_for_vault, is_user_stats_for_vault,
+    is_vault_for_vault_depositor,
+};
+use crate::instructions::request_withdraw_for_vault_depositor;
+use crate::state::Vault;
+use crate::{DepositorOperator, VaultDepositor, WithdrawUnit};
+
+/// Requests a withdraw on behalf of a depositor that named the signer its operator
+pub fn operator_request_withdraw<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, OperatorRequestWithdraw<'info>>,
+    withdraw_amount: u64,
+    withdraw_unit: WithdrawUnit,
+) -> Result<()> {
+    request_withdraw_for_vault_depositor(
+        &ctx,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_depositor,
+        &ctx.accounts.drift_user,
+        &ctx.accounts.drift_user_stats,
+        withdraw_amount,
+        withdraw_unit,
+    )
+}
+
+#[derive(Accounts)]
+pub struct OperatorRequestWithdraw<'info> {
+    #[account(mut)]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_vault_for_vault_depositor(&vault_depositor, &vault)?,
+    )]
+    pub vault_depositor: AccountLoader<'info, VaultDepositor>,
+    #[account(
+        seeds = [b"depositor_operator", vault_depositor.key().as_ref()],
+        bump,
+        constraint = is_operator_for_vault_depositor(&depositor_operator, &operator)?,
+    )]
+    pub depositor_operator: AccountLoader<'info, DepositorOperator>,
+    pub operator: Signer<'info>,
+    #[account(
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    /// CHECK: checked in drift cpi
+    pub drift_state: AccountInfo<'info>,
+}
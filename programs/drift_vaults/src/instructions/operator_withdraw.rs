@@ -0,0 +1,172 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Transfer};
+use anchor_spl::token::{Token, TokenAccount};
+use drift::cpi::accounts::{UpdateUser, Withdraw as DriftWithdraw};
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{
+    is_operator_for_vault_depositor, is_user_for_vault, is_user_stats_for_vault,
+    is_vault_for_vault_depositor, is_withdraw_destination_for_vault_depositor,
+};
+use crate::drift_cpi::{
+    UpdateSubAccountDelegateCPI, UpdateSubAccountReduceOnlyCPI, UpdateUserDelegateCPI,
+    UpdateUserReduceOnlyCPI, WithdrawCPI,
+};
+use crate::instructions::{exit_liquidation, withdraw_from_vault_depositor};
+use crate::state::{DepositorOperator, Vault, VaultDepositor};
+use crate::token_cpi::TokenTransferCPI;
+use crate::{
+    declare_vault_seeds, implement_update_user_delegate_cpi, implement_update_user_reduce_only_cpi,
+    implement_withdraw,
+};
+
+/// Finalizes a depositor's withdraw on behalf of a depositor that named the signer its operator. The withdraw is only
+/// paid to the depositor's pinned withdraw destination, or to a token account the depositor's authority owns.
+pub fn operator_withdraw<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, OperatorWithdraw<'info>>,
+    min_amount_out: Option<u64>,
+) -> Result<()> {
+    let (user_withdraw_amount, finishing_liquidation) = withdraw_from_vault_depositor(
+        &ctx,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_depositor,
+        &ctx.accounts.drift_user,
+        &ctx.accounts.drift_user_stats,
+        min_amount_out,
+    )?;
+
+    ctx.drift_withdraw(user_withdraw_amount)?;
+
+    ctx.token_transfer(user_withdraw_amount)?;
+
+    if finishing_liquidation {
+        exit_liquidation(&ctx, &ctx.accounts.vault, &ctx.accounts.drift_user_stats)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct OperatorWithdraw<'info> {
+    #[account(mut)]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_vault_for_vault_depositor(&vault_depositor, &vault)?,
+    )]
+    pub vault_depositor: AccountLoader<'info, VaultDepositor>,
+    #[account(
+        seeds = [b"depositor_operator", vault_depositor.key().as_ref()],
+        bump,
+        constraint = is_operator_for_vault_depositor(&depositor_operator, &operator)?,
+    )]
+    pub depositor_operator: AccountLoader<'info, DepositorOperator>,
+    pub operator: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"vault_token_account".as_ref(), vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    /// CHECK: checked in drift cpi
+    pub drift_state: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::mint = vault_token_account.mint
+    )]
+    pub drift_spot_market_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: checked in drift cpi
+    pub drift_signer: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::mint = vault_token_account.mint,
+        constraint = is_withdraw_destination_for_vault_depositor(
+            &depositor_operator,
+            &vault_depositor,
+            &user_token_account
+        )?
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+    pub drift_program: Program<'info, Drift>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> WithdrawCPI for Context<'_, '_, '_, 'info, OperatorWithdraw<'info>> {
+    fn drift_withdraw(&self, amount: u64) -> Result<()> {
+        implement_withdraw!(self, amount);
+        Ok(())
+    }
+}
+
+impl<'info> TokenTransferCPI for Context<'_, '_, '_, 'info, OperatorWithdraw<'info>> {
+    fn token_transfer(&self, amount: u64) -> Result<()> {
+        declare_vault_seeds!(self.accounts.vault, seeds);
+
+        let cpi_accounts = Transfer {
+            from: self.accounts.vault_token_account.to_account_info().clone(),
+            to: self.accounts.user_token_account.to_account_info().clone(),
+            authority: self.accounts.vault.to_account_info().clone(),
+        };
+        let token_program = self.accounts.token_program.to_account_info().clone();
+        let cpi_context = CpiContext::new_with_signer(token_program, cpi_accounts, seeds);
+
+        token::transfer(cpi_context, amount)?;
+
+        Ok(())
+    }
+}
+
+impl<'info> UpdateUserDelegateCPI for Context<'_, '_, '_, 'info, OperatorWithdraw<'info>> {
+    fn drift_update_user_delegate(&self, delegate: Pubkey) -> Result<()> {
+        implement_update_user_delegate_cpi!(self, delegate);
+        Ok(())
+    }
+}
+
+impl<'info> UpdateUserReduceOnlyCPI for Context<'_, '_, '_, 'info, OperatorWithdraw<'info>> {
+    fn drift_update_user_reduce_only(&self, reduce_only: bool) -> Result<()> {
+        implement_update_user_reduce_only_cpi!(self, reduce_only);
+        Ok(())
+    }
+}
+
+impl<'c: 'info, 'info> UpdateSubAccountDelegateCPI<'c>
+    for Context<'_, '_, 'c, 'info, OperatorWithdraw<'info>>
+{
+    fn drift_update_sub_account_delegate(
+        &self,
+        sub_account: &AccountLoader<'c, User>,
+        delegate: Pubkey,
+    ) -> Result<()> {
+        let sub_account_id = sub_account.load()?.sub_account_id;
+        implement_update_user_delegate_cpi!(self, delegate, sub_account_id, sub_account);
+        Ok(())
+    }
+}
+
+impl<'c: 'info, 'info> UpdateSubAccountReduceOnlyCPI<'c>
+    for Context<'_, '_, 'c, 'info, OperatorWithdraw<'info>>
+{
+    fn drift_update_sub_account_reduce_only(
+        &self,
+        sub_account: &AccountLoader<'c, User>,
+        reduce_only: bool,
+    ) -> Result<()> {
+        let sub_account_id = sub_account.load()?.sub_account_id;
+        implement_update_user_reduce_only_cpi!(self, reduce_only, sub_account_id, sub_account);
+        Ok(())
+    }
+}
@@ -6,6 +6,7 @@ use drift::state::user::User;
 use crate::constraints::{
     is_protocol_for_vault, is_user_for_vault, is_user_stats_for_vault, is_vault_protocol_for_vault,
 };
+use crate::state::{SubAccountProvider, VaultAllocationsProvider};
 use crate::{AccountMapProvider, Vault, VaultProtocol};
 
 pub fn protocol_cancel_withdraw_request<'c: 'info, 'info>(
@@ -24,8 +25,17 @@ pub fn protocol_cancel_withdraw_request<'c: 'info, 'info>(
         mut oracle_map,
     } = ctx.load_maps(clock.slot, None, vp.is_some())?;
 
-    let vault_equity =
-        vault.calculate_equity(&user, &perp_market_map, &spot_market_map, &mut oracle_map)?;
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
 
     vault.protocol_cancel_withdraw_request(&mut vp, vault_equity.cast()?, clock.unix_timestamp)?;
 
@@ -5,7 +5,8 @@ use drift::state::user::User;
 use crate::constraints::{
     is_protocol_for_vault, is_user_for_vault, is_user_stats_for_vault, is_vault_protocol_for_vault,
 };
-use crate::{AccountMapProvider, Vault, VaultProtocol, WithdrawUnit};
+use crate::state::{SubAccountProvider, VaultAllocationsProvider};
+use crate::{AccountMapProvider, Vault, VaultPauseFlag, VaultProtocol, WithdrawUnit};
 
 pub fn protocol_request_withdraw<'c: 'info, 'info>(
     ctx: Context<'_, '_, 'c, 'info, ProtocolRequestWithdraw<'info>>,
@@ -15,6 +16,7 @@ pub fn protocol_request_withdraw<'c: 'info, 'info>(
     let clock = &Clock::get()?;
     let mut vault = ctx.accounts.vault.load_mut()?;
     let now = clock.unix_timestamp;
+    vault.validate_not_paused(VaultPauseFlag::RequestWithdraw, now)?;
 
     // backwards compatible: if last rem acct does not deserialize into [`VaultProtocol`] then it's a legacy vault.
     let mut vp = Some(ctx.accounts.vault_protocol.load_mut()?);
@@ -28,8 +30,17 @@ pub fn protocol_request_withdraw<'c: 'info, 'info>(
         mut oracle_map,
     } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
 
-    let vault_equity =
-        vault.calculate_equity(&user, &perp_market_map, &spot_market_map, &mut oracle_map)?;
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
 
     vault.protocol_request_withdraw(&mut vp, withdraw_amount, withdraw_unit, vault_equity, now)?;
 
@@ -10,7 +10,9 @@ use crate::constraints::{
     is_protocol_for_vault, is_user_for_vault, is_user_stats_for_vault, is_vault_protocol_for_vault,
 };
 use crate::drift_cpi::WithdrawCPI;
-use crate::state::{Vault, VaultProtocol};
+use crate::state::{
+    SubAccountProvider, Vault, VaultAllocationsProvider, VaultPauseFlag, VaultProtocol,
+};
 use crate::token_cpi::TokenTransferCPI;
 use crate::{declare_vault_seeds, AccountMapProvider};
 
@@ -20,6 +22,7 @@ pub fn protocol_withdraw<'c: 'info, 'info>(
     let clock = &Clock::get()?;
     let mut vault = ctx.accounts.vault.load_mut()?;
     let now = clock.unix_timestamp;
+    vault.validate_not_paused(VaultPauseFlag::Withdraw, now)?;
 
     let user = ctx.accounts.drift_user.load()?;
     let spot_market_index = vault.spot_market_index;
@@ -32,8 +35,17 @@ pub fn protocol_withdraw<'c: 'info, 'info>(
         mut oracle_map,
     } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
 
-    let vault_equity =
-        vault.calculate_equity(&user, &perp_market_map, &spot_market_map, &mut oracle_map)?;
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
 
     let protocol_withdraw_amount = vault.protocol_withdraw(&mut vp, vault_equity, now)?;
 
@@ -4,24 +4,31 @@ use crate::constraints::{
 };
 use crate::error::ErrorCode;
 use crate::state::traits::VaultDepositorBase;
+use crate::state::{SubAccountProvider, VaultAllocationsProvider};
 use crate::token_cpi::{BurnTokensCPI, TokenTransferCPI};
 use crate::{validate, AccountMapProvider};
-use crate::{TokenizedVaultDepositor, Vault, VaultDepositor, VaultProtocolProvider, WithdrawUnit};
+use crate::{
+    TokenizedVaultDepositor, Vault, VaultDepositor, VaultPauseFlag, VaultProtocolProvider,
+    WithdrawUnit,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{burn, transfer, Burn, Mint, Token, TokenAccount, Transfer};
 use drift::instructions::optional_accounts::AccountMaps;
+use drift::math::insurance::if_shares_to_vault_amount as depositor_shares_to_vault_amount;
 use drift::math::safe_math::SafeMath;
 use drift::state::user::User;
 
 pub fn redeem_tokens<'info>(
     ctx: Context<'_, '_, 'info, 'info, RedeemTokens<'info>>,
     tokens_to_burn: u64,
+    min_amount_out: Option<u64>,
 ) -> Result<()> {
     let clock = &Clock::get()?;
 
     let mut vault = ctx.accounts.vault.load_mut()?;
 
     validate!(!vault.in_liquidation(), ErrorCode::OngoingLiquidation)?;
+    vault.validate_not_paused(VaultPauseFlag::Tokenize, clock.unix_timestamp)?;
 
     let mut vault_depositor = ctx.accounts.vault_depositor.load_mut()?;
     let mut tokenized_vault_depositor = ctx.accounts.tokenized_vault_depositor.load_mut()?;
@@ -31,6 +38,12 @@ pub fn redeem_tokens<'info>(
     vault.validate_vault_protocol(&vp)?;
     let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
 
+    validate!(
+        vault_depositor.share_class == 0,
+        ErrorCode::InvalidShareClass,
+        "Cannot redeem tokens into a share class depositor"
+    )?;
+
     let manager_shares_before = vault.get_manager_shares(&mut vp)?;
     let total_shares_before = vault_depositor
         .get_vault_shares()
@@ -45,8 +58,17 @@ pub fn redeem_tokens<'info>(
         mut oracle_map,
     } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
 
-    let vault_equity =
-        vault.calculate_equity(&user, &perp_market_map, &spot_market_map, &mut oracle_map)?;
+    let sub_accounts = ctx.sub_accounts_in_remaining_accounts(&vault)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
 
     validate!(
         !vault_depositor.last_withdraw_request.pending(),
@@ -90,6 +112,18 @@ pub fn redeem_tokens<'info>(
         ErrorCode::InvalidVaultSharesDetected
     )?;
 
+    if let Some(min_amount_out) = min_amount_out {
+        let amount_out =
+            depositor_shares_to_vault_amount(shares_transferred, vault.total_shares, vault_equity)?;
+        validate!(
+            amount_out >= min_amount_out,
+            ErrorCode::SlippageExceeded,
+            "redeemed shares worth {}, less than min_amount_out {}",
+            amount_out,
+            min_amount_out
+        )?;
+    }
+
     let vault_name = vault.name;
     let vault_bump = vault.bump;
 
@@ -0,0 +1,45 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::{is_manager_for_vault, is_vault_for_vault_allocations};
+use crate::error::ErrorCode;
+use crate::state::{Vault, VaultAllocations, VaultDepositor, VaultDepositorBase};
+use crate::validate;
+
+/// Removes a child vault the vault has fully withdrawn from, so it no longer has to be passed to value the vault
+pub fn remove_vault_allocation(ctx: Context<RemoveVaultAllocation>) -> Result<()> {
+    let child_vault_depositor = ctx.accounts.child_vault_depositor.load()?;
+    let mut vault_allocations = ctx.accounts.vault_allocations.load_mut()?;
+
+    vault_allocations.validate_allocation(
+        &ctx.accounts.child_vault.key(),
+        &ctx.accounts.child_vault_depositor.key(),
+    )?;
+
+    validate!(
+        child_vault_depositor.get_vault_shares() == 0,
+        ErrorCode::InvalidVaultAllocation,
+        "vault still holds {} shares of the child vault",
+        child_vault_depositor.get_vault_shares()
+    )?;
+
+    vault_allocations.remove(&ctx.accounts.child_vault.key())?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RemoveVaultAllocation<'info> {
+    #[account(
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_vault_for_vault_allocations(&vault_allocations, &vault)?,
+    )]
+    pub vault_allocations: AccountLoader<'info, VaultAllocations>,
+    pub manager: Signer<'info>,
+    /// CHECK: checked against vault_allocations
+    pub child_vault: AccountInfo<'info>,
+    pub child_vault_depositor: AccountLoader<'info, VaultDepositor>,
+}
@@ -0,0 +1,108 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::TokenAccount;
+use drift::instructions::optional_accounts::AccountMaps;
+use drift::state::user::User;
+
+use crate::constraints::{is_fee_recipients_for_vault, is_user_for_vault, is_user_stats_for_vault};
+use crate::error::ErrorCode;
+use crate::state::{
+    FeeRecipients, ManagerPositionProvider, SubAccountProvider, Vault, VaultAllocationsProvider,
+    VaultPauseFlag, VaultProtocolProvider,
+};
+use crate::{validate, AccountMapProvider};
+
+pub fn request_claim_fees<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, RequestClaimFees<'info>>,
+    recipient_index: u8,
+) -> Result<()> {
+    let clock = &Clock::get()?;
+    let mut vault = ctx.accounts.vault.load_mut()?;
+    let now = clock.unix_timestamp;
+
+    validate!(!vault.in_liquidation(), ErrorCode::OngoingLiquidation)?;
+    vault.validate_not_paused(VaultPauseFlag::Fees, now)?;
+
+    let mut fee_recipients = ctx.accounts.fee_recipients.load_mut()?;
+    let recipient_index = recipient_index as usize;
+
+    validate!(
+        fee_recipients
+            .recipients
+            .get(recipient_index)
+            .map(|recipient| recipient.token_account)
+            .eq(&Some(ctx.accounts.recipient_token_account.key())),
+        ErrorCode::InvalidFeeRecipients,
+        "recipient token account does not match fee recipient {}",
+        recipient_index
+    )?;
+
+    // backwards compatible: if last rem acct does not deserialize into [`VaultProtocol`] then it's a legacy vault.
+    let mut vp = ctx.vault_protocol();
+    vault.validate_vault_protocol(&vp)?;
+    let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
+
+    // if the vault has a [`ManagerPosition`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut mp = ctx.manager_position(&vault);
+    vault.validate_manager_position(&mp)?;
+    let mut mp = mp.as_mut().map(|mp| mp.load_mut()).transpose()?;
+
+    let user = ctx.accounts.drift_user.load()?;
+    let spot_market_index = vault.spot_market_index;
+
+    let AccountMaps {
+        perp_market_map,
+        spot_market_map,
+        mut oracle_map,
+    } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
+
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
+
+    fee_recipients.request_claim(
+        recipient_index,
+        vault_equity,
+        &mut vault,
+        &mut vp,
+        &mut mp,
+        now,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RequestClaimFees<'info> {
+    #[account(mut)]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_fee_recipients_for_vault(&fee_recipients, &vault)?
+    )]
+    pub fee_recipients: AccountLoader<'info, FeeRecipients>,
+    #[account(
+        token::authority = authority
+    )]
+    pub recipient_token_account: Box<Account<'info, TokenAccount>>,
+    pub authority: Signer<'info>,
+    #[account(
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    /// CHECK: checked in drift cpi
+    pub drift_state: AccountInfo<'info>,
+}
@@ -0,0 +1,200 @@
+use crate::constraints::{
+    is_ata, is_mint_for_tokenized_depositor, is_tokenized_depositor_for_vault, is_user_for_vault,
+    is_user_stats_for_vault,
+};
+use crate::error::ErrorCode;
+use crate::state::{SubAccountProvider, VaultAllocationsProvider};
+use crate::token_cpi::{BurnTokensCPI, TokenTransferCPI};
+use crate::{validate, AccountMapProvider, Size};
+use crate::{
+    TokenizedVaultDepositor, TokenizedWithdrawRequest, Vault, VaultPauseFlag, VaultProtocolProvider,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token::{burn, transfer, Burn, Mint, Token, TokenAccount, Transfer};
+use drift::instructions::optional_accounts::AccountMaps;
+use drift::math::safe_math::SafeMath;
+use drift::state::user::User;
+
+pub fn request_tokenized_withdraw<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RequestTokenizedWithdraw<'info>>,
+    tokens_to_burn: u64,
+) -> Result<()> {
+    let clock = &Clock::get()?;
+
+    let mut vault = ctx.accounts.vault.load_mut()?;
+
+    validate!(!vault.in_liquidation(), ErrorCode::OngoingLiquidation)?;
+    vault.validate_not_paused(VaultPauseFlag::RequestWithdraw, clock.unix_timestamp)?;
+
+    let mut tokenized_vault_depositor = ctx.accounts.tokenized_vault_depositor.load_mut()?;
+    let mut withdraw_request = ctx.accounts.tokenized_withdraw_request.load_init()?;
+    *withdraw_request = TokenizedWithdrawRequest::new(
+        ctx.accounts.vault.key(),
+        ctx.accounts.tokenized_withdraw_request.key(),
+        ctx.accounts.tokenized_vault_depositor.key(),
+        ctx.accounts.authority.key(),
+        vault.shares_base,
+        ctx.bumps.tokenized_withdraw_request,
+    );
+
+    // backwards compatible: if last rem acct does not deserialize into [`VaultProtocol`] then it's a legacy vault.
+    let mut vp = ctx.vault_protocol();
+    vault.validate_vault_protocol(&vp)?;
+    let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
+
+    let user = ctx.accounts.drift_user.load()?;
+    let spot_market_index = vault.spot_market_index;
+    let AccountMaps {
+        perp_market_map,
+        spot_market_map,
+        mut oracle_map,
+    } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
+
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
+
+    let total_supply_before = ctx.accounts.mint.supply;
+
+    let n_shares = tokenized_vault_depositor.request_withdraw_tokens(
+        &mut withdraw_request,
+        &mut vault,
+        &mut vp,
+        total_supply_before,
+        vault_equity,
+        tokens_to_burn,
+        clock.unix_timestamp,
+    )?;
+
+    let vault_name = vault.name;
+    let vault_bump = vault.bump;
+
+    drop(vault);
+    drop(tokenized_vault_depositor);
+    drop(withdraw_request);
+
+    ctx.token_transfer(tokens_to_burn)?;
+    ctx.burn(vault_name, vault_bump, tokens_to_burn)?;
+
+    msg!(
+        "Burned {} tokens from {} into withdraw request for {} shares",
+        tokens_to_burn,
+        ctx.accounts.user_token_account.key(),
+        n_shares
+    );
+
+    ctx.accounts.mint.reload()?;
+    let total_supply_after = ctx.accounts.mint.supply;
+
+    let supply_delta = total_supply_before.safe_sub(total_supply_after)?;
+    validate!(
+        supply_delta.eq(&tokens_to_burn),
+        ErrorCode::InvalidTokenization,
+        "Tokens burned ({}) != supply delta ({})",
+        tokens_to_burn,
+        supply_delta
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RequestTokenizedWithdraw<'info> {
+    #[account(mut)]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_tokenized_depositor_for_vault(&tokenized_vault_depositor, &vault)?,
+    )]
+    pub tokenized_vault_depositor: AccountLoader<'info, TokenizedVaultDepositor>,
+    #[account(
+        init,
+        seeds = [b"tokenized_withdraw_request", tokenized_vault_depositor.key().as_ref(), authority.key().as_ref()],
+        space = TokenizedWithdrawRequest::SIZE,
+        bump,
+        payer = payer
+    )]
+    pub tokenized_withdraw_request: AccountLoader<'info, TokenizedWithdrawRequest>,
+    #[account(
+        mut,
+        mint::authority = vault.key(),
+        constraint = is_mint_for_tokenized_depositor(&mint.key(), &tokenized_vault_depositor)?,
+    )]
+    pub mint: Account<'info, Mint>,
+    #[account(
+        mut,
+        token::authority = authority,
+        token::mint = tokenized_vault_depositor.load()?.mint
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+    #[account(
+        mut,
+        token::authority = vault.key(),
+        token::mint = tokenized_vault_depositor.load()?.mint,
+        constraint = is_ata(&vault_token_account.key(), &vault.key(), &mint.key())?
+    )]
+    pub vault_token_account: Account<'info, TokenAccount>,
+    #[account(
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked by constraint
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    pub authority: Signer<'info>,
+    #[account(mut)]
+    pub payer: Signer<'info>,
+    pub token_program: Program<'info, Token>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
+
+impl<'info> TokenTransferCPI for Context<'_, '_, '_, 'info, RequestTokenizedWithdraw<'info>> {
+    fn token_transfer(&self, amount: u64) -> Result<()> {
+        let cpi_accounts = Transfer {
+            from: self.accounts.user_token_account.to_account_info(),
+            to: self.accounts.vault_token_account.to_account_info(),
+            authority: self.accounts.authority.to_account_info(),
+        };
+        let token_program = self.accounts.token_program.to_account_info();
+        let cpi_context = CpiContext::new(token_program, cpi_accounts);
+
+        transfer(cpi_context, amount)?;
+
+        Ok(())
+    }
+}
+
+impl<'info> BurnTokensCPI for Context<'_, '_, '_, 'info, RequestTokenizedWithdraw<'info>> {
+    fn burn(&self, vault_name: [u8; 32], vault_bump: u8, amount: u64) -> Result<()> {
+        let signature_seeds = Vault::get_vault_signer_seeds(&vault_name, &vault_bump);
+        let signers = &[&signature_seeds[..]];
+
+        let cpi_accounts = Burn {
+            mint: self.accounts.mint.to_account_info(),
+            from: self.accounts.vault_token_account.to_account_info(),
+            authority: self.accounts.vault.to_account_info(),
+        };
+
+        let cpi_context = CpiContext::new_with_signer(
+            self.accounts.token_program.to_account_info(),
+            cpi_accounts,
+            signers,
+        );
+
+        burn(cpi_context, amount)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::is_authority_for_vault_depositor;
+use crate::state::{VaultDepositor, VaultDepositorTransfer};
+use crate::Size;
+
+pub fn request_vault_depositor_transfer(
+    ctx: Context<RequestVaultDepositorTransfer>,
+    new_authority: Pubkey,
+) -> Result<()> {
+    let mut vault_depositor_transfer = ctx.accounts.vault_depositor_transfer.load_init()?;
+    *vault_depositor_transfer = VaultDepositorTransfer::new(
+        ctx.accounts.vault_depositor.key(),
+        ctx.accounts.vault_depositor_transfer.key(),
+        ctx.accounts.authority.key(),
+        new_authority,
+        ctx.bumps.vault_depositor_transfer,
+    )?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct RequestVaultDepositorTransfer<'info> {
+    #[account(
+        constraint = is_authority_for_vault_depositor(&vault_depositor, &authority)?,
+    )]
+    pub vault_depositor: AccountLoader<'info, VaultDepositor>,
+    #[account(
+        init,
+        seeds = [b"vault_depositor_transfer", vault_depositor.key().as_ref()],
+        space = VaultDepositorTransfer::SIZE,
+        bump,
+        payer = authority
+    )]
+    pub vault_depositor_transfer: AccountLoader<'info, VaultDepositorTransfer>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    pub rent: Sysvar<'info, Rent>,
+    pub system_program: Program<'info, System>,
+}
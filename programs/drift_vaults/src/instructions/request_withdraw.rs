@@ -7,33 +7,88 @@ use crate::constraints::{
     is_authority_for_vault_depositor, is_user_for_vault, is_user_stats_for_vault,
 };
 use crate::state::account_maps::AccountMapProvider;
-use crate::state::{Vault, VaultProtocolProvider};
+use crate::state::{
+    ShareClassProvider, SubAccountProvider, Vault, VaultAllocationsProvider, VaultDepositorBase,
+    VaultPauseFlag, VaultProtocolProvider, VaultReferrerProvider,
+};
 use crate::{VaultDepositor, WithdrawUnit};
 
 pub fn request_withdraw<'c: 'info, 'info>(
     ctx: Context<'_, '_, 'c, 'info, RequestWithdraw<'info>>,
     withdraw_amount: u64,
     withdraw_unit: WithdrawUnit,
+) -> Result<()> {
+    request_withdraw_for_vault_depositor(
+        &ctx,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_depositor,
+        &ctx.accounts.drift_user,
+        &ctx.accounts.drift_user_stats,
+        withdraw_amount,
+        withdraw_unit,
+    )
+}
+
+/// Settles the depositor's fees and requests its withdraw. Every withdraw request for a [`VaultDepositor`] goes through
+/// this, whoever signs for the depositor.
+pub fn request_withdraw_for_vault_depositor<'c: 'info, 'info, T: anchor_lang::Bumps>(
+    ctx: &Context<'_, '_, 'c, 'info, T>,
+    vault: &AccountLoader<'info, Vault>,
+    vault_depositor: &AccountLoader<'info, VaultDepositor>,
+    drift_user: &AccountLoader<'info, User>,
+    drift_user_stats: &AccountInfo<'info>,
+    withdraw_amount: u64,
+    withdraw_unit: WithdrawUnit,
 ) -> Result<()> {
     let clock = &Clock::get()?;
-    let vault = &mut ctx.accounts.vault.load_mut()?;
-    let mut vault_depositor = ctx.accounts.vault_depositor.load_mut()?;
+    let vault = &mut vault.load_mut()?;
+    vault.validate_not_paused(VaultPauseFlag::RequestWithdraw, clock.unix_timestamp)?;
+    let mut vault_depositor = vault_depositor.load_mut()?;
 
-    let user = ctx.accounts.drift_user.load()?;
+    let user = drift_user.load()?;
 
     let mut vp = ctx.vault_protocol();
     vault.validate_vault_protocol(&vp)?;
     let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
 
+    // if the depositor was referred, its [`VaultReferrer`] can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut vr = ctx.vault_referrer(&vault_depositor);
+    vault_depositor.validate_vault_referrer(&vr)?;
+    let mut vr = vr.as_mut().map(|vr| vr.load_mut()).transpose()?;
+
+    // if the depositor is in a [`ShareClass`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut sc = ctx.share_class(&vault_depositor);
+    vault_depositor.validate_share_class(&sc)?;
+    let mut sc = sc.as_mut().map(|sc| sc.load_mut()).transpose()?;
+
     let AccountMaps {
         perp_market_map,
         spot_market_map,
         mut oracle_map,
     } = ctx.load_maps(clock.slot, None, vp.is_some())?;
 
-    let vault_equity =
-        vault.calculate_equity(&user, &perp_market_map, &spot_market_map, &mut oracle_map)?;
+    let sub_accounts = ctx.sub_accounts(&vault, drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
 
+    vault_depositor.settle_fees(
+        sc.as_deref_mut(),
+        vr.as_deref_mut(),
+        vault_equity,
+        vault,
+        &mut vp,
+        clock.unix_timestamp,
+    )?;
+
+    let class_shares_before = vault_depositor.get_vault_shares();
     vault_depositor.request_withdraw(
         withdraw_amount.cast()?,
         withdraw_unit,
@@ -43,6 +98,10 @@ pub fn request_withdraw<'c: 'info, 'info>(
         clock.unix_timestamp,
     )?;
 
+    if let Some(sc) = sc.as_mut() {
+        sc.update_user_shares(class_shares_before, vault_depositor.get_vault_shares())?;
+    }
+
     Ok(())
 }
 
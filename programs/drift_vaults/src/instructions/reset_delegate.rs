@@ -4,15 +4,20 @@ use drift::program::Drift;
 use drift::state::user::User;
 
 use crate::constraints::is_user_for_vault;
-use crate::drift_cpi::{UpdateUserDelegateCPI, UpdateUserReduceOnlyCPI};
+use crate::drift_cpi::{
+    UpdateSubAccountDelegateCPI, UpdateSubAccountReduceOnlyCPI, UpdateUserDelegateCPI,
+    UpdateUserReduceOnlyCPI,
+};
 use crate::error::ErrorCode;
-use crate::state::Vault;
+use crate::state::{SubAccountProvider, Vault};
 use crate::validate;
 use crate::{
     declare_vault_seeds, implement_update_user_delegate_cpi, implement_update_user_reduce_only_cpi,
 };
 
-pub fn reset_delegate<'info>(ctx: Context<'_, '_, '_, 'info, ResetDelegate<'info>>) -> Result<()> {
+pub fn reset_delegate<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ResetDelegate<'info>>,
+) -> Result<()> {
     let mut vault = ctx.accounts.vault.load_mut()?;
 
     validate!(
@@ -27,10 +32,19 @@ pub fn reset_delegate<'info>(ctx: Context<'_, '_, '_, 'info, ResetDelegate<'info
 
     let delegate = vault.delegate;
 
+    // the liquidator was given every sub-account too, so they and the user stats must be in the rem accts
+    let sub_accounts = ctx.sub_accounts_in_remaining_accounts(&vault)?;
+
     drop(vault);
 
     ctx.drift_update_user_delegate(delegate)?;
     ctx.drift_update_user_reduce_only(false)?;
+    // sub-account delegates aren't tracked on the vault, so the manager restores them with
+    // `update_sub_account_delegate`
+    for sub_account in sub_accounts.iter() {
+        ctx.drift_update_sub_account_delegate(sub_account, Pubkey::default())?;
+        ctx.drift_update_sub_account_reduce_only(sub_account, false)?;
+    }
 
     Ok(())
 }
@@ -62,3 +76,31 @@ impl<'info> UpdateUserReduceOnlyCPI for Context<'_, '_, '_, 'info, ResetDelegate
         Ok(())
     }
 }
+
+impl<'c: 'info, 'info> UpdateSubAccountDelegateCPI<'c>
+    for Context<'_, '_, 'c, 'info, ResetDelegate<'info>>
+{
+    fn drift_update_sub_account_delegate(
+        &self,
+        sub_account: &AccountLoader<'c, User>,
+        delegate: Pubkey,
+    ) -> Result<()> {
+        let sub_account_id = sub_account.load()?.sub_account_id;
+        implement_update_user_delegate_cpi!(self, delegate, sub_account_id, sub_account);
+        Ok(())
+    }
+}
+
+impl<'c: 'info, 'info> UpdateSubAccountReduceOnlyCPI<'c>
+    for Context<'_, '_, 'c, 'info, ResetDelegate<'info>>
+{
+    fn drift_update_sub_account_reduce_only(
+        &self,
+        sub_account: &AccountLoader<'c, User>,
+        reduce_only: bool,
+    ) -> Result<()> {
+        let sub_account_id = sub_account.load()?.sub_account_id;
+        implement_update_user_reduce_only_cpi!(self, reduce_only, sub_account_id, sub_account);
+        Ok(())
+    }
+}
@@ -0,0 +1,106 @@
+use anchor_lang::prelude::*;
+use drift::cpi::accounts::UpdateUser;
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{
+    is_manager_for_vault, is_user_for_vault, is_user_stats_for_vault, is_vault_for_drawdown_guard,
+};
+use crate::drift_cpi::{UpdateSubAccountReduceOnlyCPI, UpdateUserReduceOnlyCPI};
+use crate::error::ErrorCode;
+use crate::state::{DrawdownGuard, SubAccountProvider, Vault, VaultProtocolProvider};
+use crate::{declare_vault_seeds, implement_update_user_reduce_only_cpi, validate};
+
+pub fn reset_drawdown_guard<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ResetDrawdownGuard<'info>>,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+
+    let mut vault = ctx.accounts.vault.load_mut()?;
+    let mut drawdown_guard = ctx.accounts.drawdown_guard.load_mut()?;
+
+    // a shut down or liquidating vault has to stay reduce-only
+    validate!(
+        !vault.shutdown,
+        ErrorCode::VaultShutdown,
+        "vault is shut down"
+    )?;
+    validate!(
+        !vault.in_liquidation(),
+        ErrorCode::OngoingLiquidation,
+        "vault is in liquidation"
+    )?;
+
+    drawdown_guard.reset(now)?;
+
+    // backwards compatible: if last rem acct does not deserialize into [`VaultProtocol`] then it's a legacy vault.
+    let mut vp = ctx.vault_protocol();
+    vault.validate_vault_protocol(&vp)?;
+    let vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
+
+    vault.clear_breaker_paused(&vp, ctx.accounts.manager.key(), now)?;
+
+    // the sub-accounts were made reduce-only with the primary user, so they must all be in the rem accts
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+
+    msg!("vault drawdown guard reset");
+
+    drop(vault);
+    drop(drawdown_guard);
+    drop(vp);
+
+    ctx.drift_update_user_reduce_only(false)?;
+    for sub_account in sub_accounts.iter() {
+        ctx.drift_update_sub_account_reduce_only(sub_account, false)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ResetDrawdownGuard<'info> {
+    #[account(
+        mut,
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_vault_for_drawdown_guard(&drawdown_guard, &vault)?,
+    )]
+    pub drawdown_guard: AccountLoader<'info, DrawdownGuard>,
+    pub manager: Signer<'info>,
+    #[account(
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked by constraint
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    pub drift_program: Program<'info, Drift>,
+}
+
+impl<'info> UpdateUserReduceOnlyCPI for Context<'_, '_, '_, 'info, ResetDrawdownGuard<'info>> {
+    fn drift_update_user_reduce_only(&self, reduce_only: bool) -> Result<()> {
+        implement_update_user_reduce_only_cpi!(self, reduce_only);
+        Ok(())
+    }
+}
+
+impl<'c: 'info, 'info> UpdateSubAccountReduceOnlyCPI<'c>
+    for Context<'_, '_, 'c, 'info, ResetDrawdownGuard<'info>>
+{
+    fn drift_update_sub_account_reduce_only(
+        &self,
+        sub_account: &AccountLoader<'c, User>,
+        reduce_only: bool,
+    ) -> Result<()> {
+        let sub_account_id = sub_account.load()?.sub_account_id;
+        implement_update_user_reduce_only_cpi!(self, reduce_only, sub_account_id, sub_account);
+        Ok(())
+    }
+}
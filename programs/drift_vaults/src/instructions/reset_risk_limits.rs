@@ -0,0 +1,134 @@
+use anchor_lang::prelude::*;
+use drift::cpi::accounts::UpdateUser;
+use drift::instructions::optional_accounts::AccountMaps;
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{
+    is_manager_for_vault, is_user_for_vault, is_user_stats_for_vault, is_vault_for_risk_limits,
+};
+use crate::drift_cpi::{UpdateSubAccountReduceOnlyCPI, UpdateUserReduceOnlyCPI};
+use crate::error::ErrorCode;
+use crate::state::{RiskLimits, SubAccountProvider, Vault};
+use crate::AccountMapProvider;
+use crate::{declare_vault_seeds, implement_update_user_reduce_only_cpi, validate};
+
+/// Clears a breach once the drift user is back within the vault's [`RiskLimits`]. A revoked delegate has to be
+/// restored separately with `update_delegate` and `update_sub_account_delegate`.
+pub fn reset_risk_limits<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ResetRiskLimits<'info>>,
+) -> Result<()> {
+    let clock = &Clock::get()?;
+
+    let vault = ctx.accounts.vault.load()?;
+    let mut risk_limits = ctx.accounts.risk_limits.load_mut()?;
+
+    validate!(
+        risk_limits.is_breached(),
+        ErrorCode::InvalidRiskLimits,
+        "risk limits are not breached"
+    )?;
+
+    // a shut down or liquidating vault has to stay reduce-only
+    validate!(
+        !vault.shutdown,
+        ErrorCode::VaultShutdown,
+        "vault is shut down"
+    )?;
+    validate!(
+        !vault.in_liquidation(),
+        ErrorCode::OngoingLiquidation,
+        "vault is in liquidation"
+    )?;
+
+    let user = ctx.accounts.drift_user.load()?;
+
+    let AccountMaps {
+        perp_market_map,
+        spot_market_map,
+        mut oracle_map,
+    } = ctx.load_maps(clock.slot, None, false)?;
+
+    let violation = risk_limits.find_violation(
+        &vault,
+        &user,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+    )?;
+
+    validate!(
+        violation.is_none(),
+        ErrorCode::InvalidRiskLimits,
+        "drift user still breaks risk limits: {:?}",
+        violation
+    )?;
+
+    // the sub-accounts were restricted with the primary user, so they must all be in the rem accts
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+
+    risk_limits.breached_ts = 0;
+    let lift_reduce_only = !risk_limits.revoke_delegate;
+
+    msg!("vault risk limits reset");
+
+    drop(vault);
+    drop(risk_limits);
+    drop(user);
+
+    if lift_reduce_only {
+        ctx.drift_update_user_reduce_only(false)?;
+        for sub_account in sub_accounts.iter() {
+            ctx.drift_update_sub_account_reduce_only(sub_account, false)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ResetRiskLimits<'info> {
+    #[account(
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_vault_for_risk_limits(&risk_limits, &vault)?,
+    )]
+    pub risk_limits: AccountLoader<'info, RiskLimits>,
+    pub manager: Signer<'info>,
+    #[account(
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked by constraint
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    pub drift_program: Program<'info, Drift>,
+}
+
+impl<'info> UpdateUserReduceOnlyCPI for Context<'_, '_, '_, 'info, ResetRiskLimits<'info>> {
+    fn drift_update_user_reduce_only(&self, reduce_only: bool) -> Result<()> {
+        implement_update_user_reduce_only_cpi!(self, reduce_only);
+        Ok(())
+    }
+}
+
+impl<'c: 'info, 'info> UpdateSubAccountReduceOnlyCPI<'c>
+    for Context<'_, '_, 'c, 'info, ResetRiskLimits<'info>>
+{
+    fn drift_update_sub_account_reduce_only(
+        &self,
+        sub_account: &AccountLoader<'c, User>,
+        reduce_only: bool,
+    ) -> Result<()> {
+        let sub_account_id = sub_account.load()?.sub_account_id;
+        implement_update_user_reduce_only_cpi!(self, reduce_only, sub_account_id, sub_account);
+        Ok(())
+    }
+}
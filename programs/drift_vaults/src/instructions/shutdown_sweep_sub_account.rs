@@ -0,0 +1,105 @@
+use anchor_lang::prelude::*;
+use drift::cpi::accounts::TransferDeposit;
+use drift::instructions::optional_accounts::AccountMaps;
+use drift::math::casting::Cast;
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{is_sub_account_for_vault, is_user_for_vault, is_user_stats_for_vault};
+use crate::drift_cpi::TransferDepositCPI;
+use crate::error::ErrorCode;
+use crate::state::Vault;
+use crate::{declare_vault_seeds, validate, AccountMapProvider};
+
+/// Permissionless crank that moves a flat sub-account's deposit of the vault's spot market into the primary drift
+/// user once the vault is shut down, so `shutdown_withdraw` can pay depositors out of it. Remaining accounts are the
+/// markets the sub-account needs for drift's margin check.
+pub fn shutdown_sweep_sub_account<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ShutdownSweepSubAccount<'info>>,
+) -> Result<()> {
+    let clock = &Clock::get()?;
+    let vault = ctx.accounts.vault.load()?;
+
+    validate!(vault.shutdown, ErrorCode::Default, "vault is not shut down")?;
+
+    let spot_market_index = vault.spot_market_index;
+
+    let sub_account = ctx.accounts.sub_account.load()?;
+    vault.check_positions_flat(&sub_account)?;
+
+    let AccountMaps {
+        spot_market_map, ..
+    } = ctx.load_maps(clock.slot, Some(spot_market_index), false)?;
+
+    let spot_market = spot_market_map.get_ref(&spot_market_index)?;
+    let token_amount = sub_account
+        .get_spot_position(spot_market_index)?
+        .get_signed_token_amount(&spot_market)?;
+
+    validate!(
+        token_amount > 0,
+        ErrorCode::InvalidSubAccount,
+        "sub-account {} has no deposit to sweep",
+        sub_account.sub_account_id
+    )?;
+
+    let amount = token_amount.cast::<u64>()?;
+
+    msg!("shutdown_sweep_amount: {}", amount);
+
+    drop(spot_market);
+    drop(sub_account);
+    drop(vault);
+
+    ctx.drift_transfer_deposit(spot_market_index, amount)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ShutdownSweepSubAccount<'info> {
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_sub_account_for_vault(&vault, &sub_account)?
+    )]
+    pub sub_account: AccountLoader<'info, User>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    pub drift_user: AccountLoader<'info, User>,
+    /// CHECK: checked in drift cpi
+    pub drift_state: AccountInfo<'info>,
+    /// CHECK: checked in drift cpi
+    pub drift_spot_market_vault: AccountInfo<'info>,
+    pub drift_program: Program<'info, Drift>,
+}
+
+impl<'info> TransferDepositCPI for Context<'_, '_, '_, 'info, ShutdownSweepSubAccount<'info>> {
+    fn drift_transfer_deposit(&self, market_index: u16, amount: u64) -> Result<()> {
+        declare_vault_seeds!(self.accounts.vault, seeds);
+
+        let cpi_accounts = TransferDeposit {
+            from_user: self.accounts.sub_account.to_account_info().clone(),
+            to_user: self.accounts.drift_user.to_account_info().clone(),
+            user_stats: self.accounts.drift_user_stats.clone(),
+            authority: self.accounts.vault.to_account_info().clone(),
+            state: self.accounts.drift_state.clone(),
+            spot_market_vault: self.accounts.drift_spot_market_vault.clone(),
+        };
+
+        let drift_program = self.accounts.drift_program.to_account_info().clone();
+        let cpi_context = CpiContext::new_with_signer(drift_program, cpi_accounts, seeds)
+            .with_remaining_accounts(self.remaining_accounts.into());
+        drift::cpi::transfer_deposit(cpi_context, market_index, amount)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+use drift::cpi::accounts::UpdateUser;
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{is_manager_for_vault, is_user_for_vault, is_user_stats_for_vault};
+use crate::drift_cpi::{UpdateSubAccountReduceOnlyCPI, UpdateUserReduceOnlyCPI};
+use crate::state::{SubAccountProvider, Vault};
+use crate::{declare_vault_seeds, implement_update_user_reduce_only_cpi};
+
+pub fn shutdown_vault<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ShutdownVault<'info>>,
+) -> Result<()> {
+    let mut vault = ctx.accounts.vault.load_mut()?;
+
+    vault.shutdown()?;
+
+    // every sub-account goes reduce-only with the primary user, so they must all be in the rem accts
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+
+    msg!("vault shut down");
+
+    drop(vault);
+
+    ctx.drift_update_user_reduce_only(true)?;
+    for sub_account in sub_accounts.iter() {
+        ctx.drift_update_sub_account_reduce_only(sub_account, true)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ShutdownVault<'info> {
+    #[account(
+        mut,
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    pub manager: Signer<'info>,
+    #[account(
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked by constraint
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    pub drift_program: Program<'info, Drift>,
+}
+
+impl<'info> UpdateUserReduceOnlyCPI for Context<'_, '_, '_, 'info, ShutdownVault<'info>> {
+    fn drift_update_user_reduce_only(&self, reduce_only: bool) -> Result<()> {
+        implement_update_user_reduce_only_cpi!(self, reduce_only);
+        Ok(())
+    }
+}
+
+impl<'c: 'info, 'info> UpdateSubAccountReduceOnlyCPI<'c>
+    for Context<'_, '_, 'c, 'info, ShutdownVault<'info>>
+{
+    fn drift_update_sub_account_reduce_only(
+        &self,
+        sub_account: &AccountLoader<'c, User>,
+        reduce_only: bool,
+    ) -> Result<()> {
+        let sub_account_id = sub_account.load()?.sub_account_id;
+        implement_update_user_reduce_only_cpi!(self, reduce_only, sub_account_id, sub_account);
+        Ok(())
+    }
+}
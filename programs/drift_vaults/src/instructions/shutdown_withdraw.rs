@@ -0,0 +1,219 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token::{self, Transfer};
+use anchor_spl::token::{Token, TokenAccount};
+use drift::cpi::accounts::Withdraw as DriftWithdraw;
+use drift::instructions::optional_accounts::AccountMaps;
+use drift::math::constants::PERCENTAGE_PRECISION_U64;
+use drift::math::safe_math::SafeMath;
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{
+    is_user_for_vault, is_user_stats_for_vault, is_vault_for_vault_depositor,
+};
+use crate::drift_cpi::WithdrawCPI;
+use crate::error::ErrorCode;
+use crate::state::{
+    ShareClassProvider, SubAccountProvider, Vault, VaultAllocationsProvider, VaultDepositor,
+    VaultDepositorBase, VaultPauseFlag, VaultProtocolProvider, VaultReferrerProvider, WithdrawUnit,
+};
+use crate::token_cpi::TokenTransferCPI;
+use crate::{declare_vault_seeds, validate, AccountMapProvider};
+
+pub fn shutdown_withdraw<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, ShutdownWithdraw<'info>>,
+) -> Result<()> {
+    let clock = &Clock::get()?;
+    let mut vault = ctx.accounts.vault.load_mut()?;
+    vault.validate_not_paused(VaultPauseFlag::Withdraw, clock.unix_timestamp)?;
+    let mut vault_depositor = ctx.accounts.vault_depositor.load_mut()?;
+
+    // backwards compatible: if last rem acct does not deserialize into [`VaultProtocol`] then it's a legacy vault.
+    let mut vp = ctx.vault_protocol();
+    vault.validate_vault_protocol(&vp)?;
+    let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
+
+    // if the depositor was referred, its [`VaultReferrer`] can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut vr = ctx.vault_referrer(&vault_depositor);
+    vault_depositor.validate_vault_referrer(&vr)?;
+    let mut vr = vr.as_mut().map(|vr| vr.load_mut()).transpose()?;
+
+    // if the depositor is in a [`ShareClass`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut sc = ctx.share_class(&vault_depositor);
+    vault_depositor.validate_share_class(&sc)?;
+    let mut sc = sc.as_mut().map(|sc| sc.load_mut()).transpose()?;
+
+    validate!(vault.shutdown, ErrorCode::Default, "vault is not shut down")?;
+
+    let user = ctx.accounts.drift_user.load()?;
+    vault.check_positions_flat(&user)?;
+    let spot_market_index = vault.spot_market_index;
+
+    let AccountMaps {
+        perp_market_map,
+        spot_market_map,
+        mut oracle_map,
+    } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
+
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    for sub_account in sub_accounts.iter() {
+        vault.check_sub_account_flat(&*sub_account.load()?)?;
+    }
+    let allocations = ctx.vault_allocations(&vault)?;
+    vault.check_allocations_flat(&allocations)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
+
+    vault_depositor.settle_fees(
+        sc.as_deref_mut(),
+        vr.as_deref_mut(),
+        vault_equity,
+        &mut vault,
+        &mut vp,
+        clock.unix_timestamp,
+    )?;
+
+    let class_shares_before = vault_depositor.get_vault_shares();
+    let mut vault_equity = vault_equity;
+    let mut withdraw_amount = 0_u64;
+
+    // pay out a pending request first, then the depositor's remaining shares. the redeem period is waived on shutdown.
+    if vault_depositor.last_withdraw_request.shares != 0 {
+        let (amount, _) =
+            vault_depositor.withdraw(vault_equity, &mut vault, &mut vp, clock.unix_timestamp)?;
+        withdraw_amount = amount;
+        vault_equity = vault_equity.safe_sub(amount)?;
+    }
+
+    if vault_depositor.get_vault_shares() != 0 {
+        vault_depositor.request_withdraw(
+            PERCENTAGE_PRECISION_U64,
+            WithdrawUnit::SharesPercent,
+            vault_equity,
+            &mut vault,
+            &mut vp,
+            clock.unix_timestamp,
+        )?;
+        let (amount, _) =
+            vault_depositor.withdraw(vault_equity, &mut vault, &mut vp, clock.unix_timestamp)?;
+        withdraw_amount = withdraw_amount.safe_add(amount)?;
+    }
+
+    if let Some(sc) = sc.as_mut() {
+        sc.update_user_shares(class_shares_before, vault_depositor.get_vault_shares())?;
+    }
+
+    msg!("shutdown_withdraw_amount: {}", withdraw_amount);
+
+    drop(vault);
+    drop(user);
+    drop(vp);
+    drop(vr);
+    drop(sc);
+
+    ctx.drift_withdraw(withdraw_amount)?;
+
+    ctx.token_transfer(withdraw_amount)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct ShutdownWithdraw<'info> {
+    #[account(mut)]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_vault_for_vault_depositor(&vault_depositor, &vault)?,
+    )]
+    pub vault_depositor: AccountLoader<'info, VaultDepositor>,
+    #[account(
+        mut,
+        seeds = [b"vault_token_account".as_ref(), vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    /// CHECK: checked in drift cpi
+    pub drift_state: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::mint = vault_token_account.mint
+    )]
+    pub drift_spot_market_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: checked in drift cpi
+    pub drift_signer: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::authority = vault_depositor.load()?.authority,
+        token::mint = vault_token_account.mint
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+    pub drift_program: Program<'info, Drift>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> WithdrawCPI for Context<'_, '_, '_, 'info, ShutdownWithdraw<'info>> {
+    fn drift_withdraw(&self, amount: u64) -> Result<()> {
+        declare_vault_seeds!(self.accounts.vault, seeds);
+        let spot_market_index = self.accounts.vault.load()?.spot_market_index;
+
+        let cpi_accounts = DriftWithdraw {
+            state: self.accounts.drift_state.to_account_info().clone(),
+            user: self.accounts.drift_user.to_account_info().clone(),
+            user_stats: self.accounts.drift_user_stats.to_account_info().clone(),
+            authority: self.accounts.vault.to_account_info().clone(),
+            spot_market_vault: self
+                .accounts
+                .drift_spot_market_vault
+                .to_account_info()
+                .clone(),
+            drift_signer: self.accounts.drift_signer.to_account_info().clone(),
+            user_token_account: self.accounts.vault_token_account.to_account_info().clone(),
+            token_program: self.accounts.token_program.to_account_info().clone(),
+        };
+
+        let drift_program = self.accounts.drift_program.to_account_info().clone();
+        let cpi_context = CpiContext::new_with_signer(drift_program, cpi_accounts, seeds)
+            .with_remaining_accounts(self.remaining_accounts.into());
+        drift::cpi::withdraw(cpi_context, spot_market_index, amount, false)?;
+
+        Ok(())
+    }
+}
+
+impl<'info> TokenTransferCPI for Context<'_, '_, '_, 'info, ShutdownWithdraw<'info>> {
+    fn token_transfer(&self, amount: u64) -> Result<()> {
+        declare_vault_seeds!(self.accounts.vault, seeds);
+
+        let cpi_accounts = Transfer {
+            from: self.accounts.vault_token_account.to_account_info().clone(),
+            to: self.accounts.user_token_account.to_account_info().clone(),
+            authority: self.accounts.vault.to_account_info().clone(),
+        };
+        let token_program = self.accounts.token_program.to_account_info().clone();
+        let cpi_context = CpiContext::new_with_signer(token_program, cpi_accounts, seeds);
+
+        token::transfer(cpi_context, amount)?;
+
+        Ok(())
+    }
+}
@@ -4,9 +4,13 @@ use crate::constraints::{
 };
 use crate::error::ErrorCode;
 use crate::state::traits::VaultDepositorBase;
+use crate::state::{SubAccountProvider, VaultAllocationsProvider};
 use crate::token_cpi::MintTokensCPI;
 use crate::{validate, AccountMapProvider};
-use crate::{TokenizedVaultDepositor, Vault, VaultDepositor, VaultProtocolProvider, WithdrawUnit};
+use crate::{
+    TokenizedVaultDepositor, Vault, VaultDepositor, VaultPauseFlag, VaultProtocolProvider,
+    VaultReferrerProvider, WithdrawUnit,
+};
 use anchor_lang::prelude::*;
 use anchor_spl::token::{mint_to, Mint, MintTo, Token, TokenAccount};
 use drift::instructions::optional_accounts::AccountMaps;
@@ -25,6 +29,7 @@ pub fn tokenize_shares<'info>(
     let mut vault = ctx.accounts.vault.load_mut()?;
 
     validate!(!vault.in_liquidation(), ErrorCode::OngoingLiquidation)?;
+    vault.validate_not_paused(VaultPauseFlag::Tokenize, clock.unix_timestamp)?;
 
     let mut vault_depositor = ctx.accounts.vault_depositor.load_mut()?;
     let mut tokenized_vault_depositor = ctx.accounts.tokenized_vault_depositor.load_mut()?;
@@ -34,6 +39,18 @@ pub fn tokenize_shares<'info>(
     vault.validate_vault_protocol(&vp)?;
     let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
 
+    // if the depositor was referred, its [`VaultReferrer`] can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut vr = ctx.vault_referrer(&vault_depositor);
+    vault_depositor.validate_vault_referrer(&vr)?;
+    let mut vr = vr.as_mut().map(|vr| vr.load_mut()).transpose()?;
+
+    // tokenized shares are fungible across depositors, so they can only carry the vault's default fee terms
+    validate!(
+        vault_depositor.share_class == 0,
+        ErrorCode::InvalidShareClass,
+        "Cannot tokenize shares of a share class depositor"
+    )?;
+
     validate!(
         vault.shares_base == tokenized_vault_depositor.vault_shares_base,
         ErrorCode::InvalidVaultRebase,
@@ -54,8 +71,26 @@ pub fn tokenize_shares<'info>(
         mut oracle_map,
     } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
 
-    let vault_equity =
-        vault.calculate_equity(&user, &perp_market_map, &spot_market_map, &mut oracle_map)?;
+    let sub_accounts = ctx.sub_accounts_in_remaining_accounts(&vault)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
+
+    vault_depositor.settle_fees(
+        None,
+        vr.as_deref_mut(),
+        vault_equity,
+        &mut vault,
+        &mut vp,
+        clock.unix_timestamp,
+    )?;
 
     validate!(
         !vault_depositor.last_withdraw_request.pending(),
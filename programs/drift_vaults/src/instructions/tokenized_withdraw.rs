@@ -0,0 +1,203 @@
+use anchor_lang::prelude::*;
+use anchor_lang::AccountsClose;
+use anchor_spl::token::{self, Transfer};
+use anchor_spl::token::{Token, TokenAccount};
+use drift::cpi::accounts::Withdraw as DriftWithdraw;
+use drift::instructions::optional_accounts::AccountMaps;
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{
+    is_authority_for_tokenized_withdraw_request, is_tokenized_depositor_for_vault,
+    is_user_for_vault, is_user_stats_for_vault,
+};
+use crate::drift_cpi::WithdrawCPI;
+use crate::error::ErrorCode;
+use crate::state::{
+    SubAccountProvider, TokenizedVaultDepositor, TokenizedWithdrawRequest, Vault,
+    VaultAllocationsProvider, VaultPauseFlag, VaultProtocolProvider, WithdrawGateProvider,
+};
+use crate::token_cpi::TokenTransferCPI;
+use crate::{declare_vault_seeds, implement_withdraw, validate, AccountMapProvider};
+
+pub fn tokenized_withdraw<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, TokenizedWithdraw<'info>>,
+) -> Result<()> {
+    let clock = &Clock::get()?;
+    let mut vault = ctx.accounts.vault.load_mut()?;
+
+    validate!(!vault.in_liquidation(), ErrorCode::OngoingLiquidation)?;
+    vault.validate_not_paused(VaultPauseFlag::Withdraw, clock.unix_timestamp)?;
+
+    let mut withdraw_request = ctx.accounts.tokenized_withdraw_request.load_mut()?;
+
+    // backwards compatible: if last rem acct does not deserialize into [`VaultProtocol`] then it's a legacy vault.
+    let mut vp = ctx.vault_protocol();
+    vault.validate_vault_protocol(&vp)?;
+    let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
+
+    // if the vault has a [`WithdrawGate`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut gate = ctx.withdraw_gate(&vault);
+    vault.validate_withdraw_gate(&gate)?;
+    let mut gate = gate.as_mut().map(|gate| gate.load_mut()).transpose()?;
+
+    let user = ctx.accounts.drift_user.load()?;
+    let spot_market_index = vault.spot_market_index;
+
+    let AccountMaps {
+        perp_market_map,
+        spot_market_map,
+        mut oracle_map,
+    } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
+
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
+
+    // a gated vault only fills the part of the request that fits in the epoch's withdraw capacity
+    let remaining_request = match gate.as_mut() {
+        Some(gate) => {
+            vault.apply_rebase(&mut vp, vault_equity)?;
+            withdraw_request.apply_rebase(&vault)?;
+            gate.split_request(
+                &mut withdraw_request.last_withdraw_request,
+                &vault,
+                vault_equity,
+                clock.unix_timestamp,
+            )?
+        }
+        None => None,
+    };
+
+    let user_withdraw_amount =
+        withdraw_request.withdraw(vault_equity, &mut vault, &mut vp, clock.unix_timestamp)?;
+
+    // the request stays open until it is filled in full
+    let request_filled = remaining_request.is_none();
+
+    if let Some(gate) = gate.as_mut() {
+        let authority = withdraw_request.authority;
+        gate.record_withdraw(
+            authority,
+            user_withdraw_amount,
+            remaining_request,
+            &mut withdraw_request.last_withdraw_request,
+            clock.unix_timestamp,
+        )?;
+    }
+
+    if request_filled {
+        let mut tokenized_vault_depositor = ctx.accounts.tokenized_vault_depositor.load_mut()?;
+        tokenized_vault_depositor.num_withdraw_requests = tokenized_vault_depositor
+            .num_withdraw_requests
+            .saturating_sub(1);
+    }
+
+    msg!("user_withdraw_amount: {}", user_withdraw_amount);
+
+    drop(vault);
+    drop(user);
+    drop(vp);
+    drop(withdraw_request);
+    drop(gate);
+
+    ctx.drift_withdraw(user_withdraw_amount)?;
+
+    ctx.token_transfer(user_withdraw_amount)?;
+
+    if request_filled {
+        ctx.accounts
+            .tokenized_withdraw_request
+            .close(ctx.accounts.authority.to_account_info())?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct TokenizedWithdraw<'info> {
+    #[account(mut)]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_tokenized_depositor_for_vault(&tokenized_vault_depositor, &vault)?,
+    )]
+    pub tokenized_vault_depositor: AccountLoader<'info, TokenizedVaultDepositor>,
+    #[account(
+        mut,
+        seeds = [b"tokenized_withdraw_request", tokenized_vault_depositor.key().as_ref(), authority.key().as_ref()],
+        bump,
+        constraint = is_authority_for_tokenized_withdraw_request(&tokenized_withdraw_request, &authority)?,
+    )]
+    pub tokenized_withdraw_request: AccountLoader<'info, TokenizedWithdrawRequest>,
+    #[account(mut)]
+    pub authority: Signer<'info>,
+    #[account(
+        mut,
+        seeds = [b"vault_token_account".as_ref(), vault.key().as_ref()],
+        bump,
+    )]
+    pub vault_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(
+        mut,
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    /// CHECK: checked in drift cpi
+    pub drift_state: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::mint = vault_token_account.mint
+    )]
+    pub drift_spot_market_vault: Box<Account<'info, TokenAccount>>,
+    /// CHECK: checked in drift cpi
+    pub drift_signer: AccountInfo<'info>,
+    #[account(
+        mut,
+        token::authority = authority,
+        token::mint = vault_token_account.mint
+    )]
+    pub user_token_account: Box<Account<'info, TokenAccount>>,
+    pub drift_program: Program<'info, Drift>,
+    pub token_program: Program<'info, Token>,
+}
+
+impl<'info> WithdrawCPI for Context<'_, '_, '_, 'info, TokenizedWithdraw<'info>> {
+    fn drift_withdraw(&self, amount: u64) -> Result<()> {
+        implement_withdraw!(self, amount);
+        Ok(())
+    }
+}
+
+impl<'info> TokenTransferCPI for Context<'_, '_, '_, 'info, TokenizedWithdraw<'info>> {
+    fn token_transfer(&self, amount: u64) -> Result<()> {
+        declare_vault_seeds!(self.accounts.vault, seeds);
+
+        let cpi_accounts = Transfer {
+            from: self.accounts.vault_token_account.to_account_info().clone(),
+            to: self.accounts.user_token_account.to_account_info().clone(),
+            authority: self.accounts.vault.to_account_info().clone(),
+        };
+        let token_program = self.accounts.token_program.to_account_info().clone();
+        let cpi_context = CpiContext::new_with_signer(token_program, cpi_accounts, seeds);
+
+        token::transfer(cpi_context, amount)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,103 @@
+use anchor_lang::prelude::*;
+use drift::cpi::accounts::TransferDeposit;
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{
+    is_manager_for_vault, is_sub_account_for_vault, is_user_for_vault, is_user_stats_for_vault,
+};
+use crate::declare_vault_seeds;
+use crate::drift_cpi::TransferDepositCPI;
+use crate::error::ErrorCode;
+use crate::state::Vault;
+use crate::validate;
+
+/// Moves collateral between the vault's primary drift user and its sub-accounts. Remaining accounts are passed to
+/// drift for the margin check on `from_user`.
+pub fn transfer_sub_account_deposit<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, TransferSubAccountDeposit<'info>>,
+    market_index: u16,
+    amount: u64,
+) -> Result<()> {
+    let vault = ctx.accounts.vault.load()?;
+
+    validate!(
+        ctx.accounts.from_user.key() != ctx.accounts.to_user.key(),
+        ErrorCode::InvalidSubAccount,
+        "from_user and to_user must differ"
+    )?;
+
+    // a liquidating vault pays the depositor out of the primary user, so collateral can only be moved back to it
+    validate!(
+        !vault.in_liquidation() || ctx.accounts.to_user.key() == vault.user,
+        ErrorCode::OngoingLiquidation,
+        "vault is in liquidation, collateral can only be moved to the primary user"
+    )?;
+
+    // a shut down vault pays depositors out of the primary user, so collateral can only flow back to it
+    validate!(
+        !vault.shutdown || ctx.accounts.to_user.key() == vault.user,
+        ErrorCode::VaultShutdown,
+        "vault is shut down, collateral can only be moved to the primary user"
+    )?;
+
+    drop(vault);
+
+    ctx.drift_transfer_deposit(market_index, amount)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct TransferSubAccountDeposit<'info> {
+    #[account(
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    pub manager: Signer<'info>,
+    #[account(
+        mut,
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &from_user.key())?
+            || is_sub_account_for_vault(&vault, &from_user)?
+    )]
+    pub from_user: AccountLoader<'info, User>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &to_user.key())?
+            || is_sub_account_for_vault(&vault, &to_user)?
+    )]
+    pub to_user: AccountLoader<'info, User>,
+    /// CHECK: checked in drift cpi
+    pub drift_state: AccountInfo<'info>,
+    /// CHECK: checked in drift cpi
+    pub drift_spot_market_vault: AccountInfo<'info>,
+    pub drift_program: Program<'info, Drift>,
+}
+
+impl<'info> TransferDepositCPI for Context<'_, '_, '_, 'info, TransferSubAccountDeposit<'info>> {
+    fn drift_transfer_deposit(&self, market_index: u16, amount: u64) -> Result<()> {
+        declare_vault_seeds!(self.accounts.vault, seeds);
+
+        let cpi_accounts = TransferDeposit {
+            from_user: self.accounts.from_user.to_account_info().clone(),
+            to_user: self.accounts.to_user.to_account_info().clone(),
+            user_stats: self.accounts.drift_user_stats.clone(),
+            authority: self.accounts.vault.to_account_info().clone(),
+            state: self.accounts.drift_state.clone(),
+            spot_market_vault: self.accounts.drift_spot_market_vault.clone(),
+        };
+
+        let drift_program = self.accounts.drift_program.to_account_info().clone();
+        let cpi_context = CpiContext::new_with_signer(drift_program, cpi_accounts, seeds)
+            .with_remaining_accounts(self.remaining_accounts.into());
+        drift::cpi::transfer_deposit(cpi_context, market_index, amount)?;
+
+        Ok(())
+    }
+}
@@ -5,21 +5,48 @@ use drift::state::user::User;
 
 use crate::constraints::{is_manager_for_vault, is_user_for_vault};
 use crate::drift_cpi::UpdateUserDelegateCPI;
-use crate::Vault;
-use crate::{declare_vault_seeds, implement_update_user_delegate_cpi};
+use crate::error::ErrorCode;
+use crate::state::VaultCompanion;
+use crate::{declare_vault_seeds, implement_update_user_delegate_cpi, validate};
+use crate::{DelegateTimelock, Vault};
 
 pub fn update_delegate<'info>(
     ctx: Context<'_, '_, '_, 'info, UpdateDelegate<'info>>,
     delegate: Pubkey,
 ) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
     let mut vault = ctx.accounts.vault.load_mut()?;
 
-    if vault.in_liquidation() {
-        let now = Clock::get()?.unix_timestamp;
+    let exited_liquidation = vault.in_liquidation();
+    if exited_liquidation {
         vault.check_can_exit_liquidation(now)?;
         vault.reset_liquidation_delegate();
     }
 
+    validate!(
+        ctx.accounts.delegate_timelock.is_some()
+            || !vault.has_companion(VaultCompanion::DelegateTimelock),
+        ErrorCode::InvalidDelegateTimelock,
+        "vault has a DelegateTimelock that wasn't passed"
+    )?;
+
+    // once the vault has a [`DelegateTimelock`], the change is queued and applied by apply_delegate_change
+    if let Some(delegate_timelock) = &ctx.accounts.delegate_timelock {
+        delegate_timelock
+            .load_mut()?
+            .queue(&vault, delegate, 0, now)?;
+
+        let current_delegate = vault.delegate;
+
+        drop(vault);
+
+        if exited_liquidation {
+            ctx.drift_update_user_delegate(current_delegate)?;
+        }
+
+        return Ok(());
+    }
+
     vault.delegate = delegate;
 
     drop(vault);
@@ -44,6 +71,13 @@ pub struct UpdateDelegate<'info> {
     /// CHECK: checked in drift cpi
     pub drift_user: AccountLoader<'info, User>,
     pub drift_program: Program<'info, Drift>,
+    /// Pass the vault program id unless the manager opted into delegate timelocks
+    #[account(
+        mut,
+        seeds = [b"delegate_timelock", vault.key().as_ref()],
+        bump,
+    )]
+    pub delegate_timelock: Option<AccountLoader<'info, DelegateTimelock>>,
 }
 
 impl<'info> UpdateUserDelegateCPI for Context<'_, '_, '_, 'info, UpdateDelegate<'info>> {
@@ -0,0 +1,32 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::is_authority_for_vault_depositor;
+use crate::state::{DepositorOperator, VaultDepositor};
+use crate::DepositorOperatorParams;
+
+/// Replaces the operator and withdraw destination. Set the operator to Pubkey::default() to revoke it.
+pub fn update_depositor_operator(
+    ctx: Context<UpdateDepositorOperator>,
+    params: DepositorOperatorParams,
+) -> Result<()> {
+    let mut depositor_operator = ctx.accounts.depositor_operator.load_mut()?;
+    depositor_operator.operator = params.operator;
+    depositor_operator.withdraw_destination = params.withdraw_destination;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateDepositorOperator<'info> {
+    #[account(
+        constraint = is_authority_for_vault_depositor(&vault_depositor, &authority)?,
+    )]
+    pub vault_depositor: AccountLoader<'info, VaultDepositor>,
+    #[account(
+        mut,
+        seeds = [b"depositor_operator", vault_depositor.key().as_ref()],
+        bump,
+    )]
+    pub depositor_operator: AccountLoader<'info, DepositorOperator>,
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::is_fee_recipients_for_vault;
+use crate::state::{FeeRecipients, Vault, VaultProtocolProvider};
+use crate::{error::ErrorCode, validate};
+use crate::{FeeRecipientParams, FeeRecipientsParams};
+
+pub fn update_fee_recipients<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, UpdateFeeRecipients<'info>>,
+    recipients: Vec<FeeRecipientParams>,
+) -> Result<()> {
+    let vault = ctx.accounts.vault.load()?;
+
+    validate!(!vault.in_liquidation(), ErrorCode::OngoingLiquidation)?;
+
+    // backwards compatible: if last rem acct does not deserialize into [`VaultProtocol`] then it's a legacy vault.
+    let mut vp = ctx.vault_protocol();
+    vault.validate_vault_protocol(&vp)?;
+    let vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
+
+    let mut fee_recipients = ctx.accounts.fee_recipients.load_mut()?;
+
+    FeeRecipients::validate_authority(
+        fee_recipients.is_protocol,
+        &vault,
+        &vp,
+        ctx.accounts.authority.key,
+    )?;
+
+    let params = FeeRecipientsParams {
+        is_protocol: fee_recipients.is_protocol,
+        recipients,
+    };
+    fee_recipients.set_recipients(&params.recipients())?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateFeeRecipients<'info> {
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_fee_recipients_for_vault(&fee_recipients, &vault)?
+    )]
+    pub fee_recipients: AccountLoader<'info, FeeRecipients>,
+    pub authority: Signer<'info>,
+}
@@ -0,0 +1,51 @@
+use anchor_lang::prelude::*;
+use drift::math::casting::Cast;
+
+use crate::constants::BPS_PRECISION;
+use crate::constraints::{is_manager_for_vault, is_vault_for_manager_position};
+use crate::state::{ManagerPosition, Vault};
+use crate::{error::ErrorCode, validate};
+
+pub fn update_min_manager_share(
+    ctx: Context<UpdateMinManagerShare>,
+    min_manager_share_bps: u16,
+) -> Result<()> {
+    let clock = &Clock::get()?;
+    let mut vault = ctx.accounts.vault.load_mut()?;
+    let manager_position = ctx.accounts.manager_position.load()?;
+
+    validate!(
+        min_manager_share_bps.cast::<u64>()? <= BPS_PRECISION,
+        ErrorCode::InvalidVaultUpdate,
+        "min manager share must be <= {} bps",
+        BPS_PRECISION
+    )?;
+
+    // depositors joined on the promise of the manager's stake, so it can only be lowered before there are any
+    let has_depositors = vault.user_shares > vault.dead_shares();
+    validate!(
+        !has_depositors || min_manager_share_bps >= vault.min_manager_share_bps,
+        ErrorCode::InvalidVaultUpdate,
+        "new min manager share must be greater than or equal to existing min manager share"
+    )?;
+
+    vault.min_manager_share_bps = min_manager_share_bps;
+    manager_position.emit_min_manager_share_record(&vault, clock.unix_timestamp)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateMinManagerShare<'info> {
+    #[account(
+        mut,
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    /// The minimum is checked against the manager's capital shares, so the vault needs a [`ManagerPosition`]
+    #[account(
+        constraint = is_vault_for_manager_position(&manager_position, &vault)?,
+    )]
+    pub manager_position: AccountLoader<'info, ManagerPosition>,
+    pub manager: Signer<'info>,
+}
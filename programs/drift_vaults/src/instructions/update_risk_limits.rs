@@ -0,0 +1,41 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::{is_manager_for_vault, is_vault_for_risk_limits};
+use crate::instructions::RiskLimitsParams;
+use crate::state::{RiskLimits, Vault};
+
+pub fn update_risk_limits(ctx: Context<UpdateRiskLimits>, params: RiskLimitsParams) -> Result<()> {
+    let vault = ctx.accounts.vault.load()?;
+    let mut risk_limits = ctx.accounts.risk_limits.load_mut()?;
+
+    let before = *risk_limits;
+
+    risk_limits.update_limits(
+        &params.allowed_perp_markets,
+        &params.allowed_spot_markets,
+        params.max_leverage,
+        params.max_position_notional,
+        params.revoke_delegate,
+    )?;
+
+    // depositors joined under the existing mandate, so it can only be tightened
+    if vault.user_shares > 0 {
+        risk_limits.validate_tightened(&before)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateRiskLimits<'info> {
+    #[account(
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_vault_for_risk_limits(&risk_limits, &vault)?,
+    )]
+    pub risk_limits: AccountLoader<'info, RiskLimits>,
+    pub manager: Signer<'info>,
+}
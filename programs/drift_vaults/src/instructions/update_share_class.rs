@@ -0,0 +1,52 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::{is_manager_for_vault, is_vault_for_share_class};
+use crate::state::{ShareClass, Vault};
+use crate::{error::ErrorCode, validate};
+
+pub fn update_share_class(
+    ctx: Context<UpdateShareClass>,
+    params: UpdateShareClassParams,
+) -> Result<()> {
+    let mut share_class = ctx.accounts.share_class.load_mut()?;
+
+    if let Some(management_fee) = params.management_fee {
+        validate!(
+            management_fee < share_class.management_fee,
+            ErrorCode::InvalidShareClass,
+            "new management fee must be less than existing management fee"
+        )?;
+        share_class.management_fee = management_fee;
+    }
+
+    if let Some(profit_share) = params.profit_share {
+        validate!(
+            profit_share < share_class.profit_share,
+            ErrorCode::InvalidShareClass,
+            "new profit share must be less than existing profit share"
+        )?;
+        share_class.profit_share = profit_share;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct UpdateShareClassParams {
+    pub management_fee: Option<u32>,
+    pub profit_share: Option<u32>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateShareClass<'info> {
+    #[account(
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_vault_for_share_class(&share_class, &vault)?,
+    )]
+    pub share_class: AccountLoader<'info, ShareClass>,
+    pub manager: Signer<'info>,
+}
@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+use drift::cpi::accounts::UpdateUser;
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{is_manager_for_vault, is_sub_account_for_vault};
+use crate::drift_cpi::UpdateUserDelegateCPI;
+use crate::error::ErrorCode;
+use crate::state::VaultCompanion;
+use crate::{declare_vault_seeds, implement_update_user_delegate_cpi, validate};
+use crate::{DelegateTimelock, Vault};
+
+pub fn update_sub_account_delegate<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdateSubAccountDelegate<'info>>,
+    delegate: Pubkey,
+) -> Result<()> {
+    let vault = ctx.accounts.vault.load()?;
+    validate!(
+        ctx.accounts.delegate_timelock.is_some()
+            || !vault.has_companion(VaultCompanion::DelegateTimelock),
+        ErrorCode::InvalidDelegateTimelock,
+        "vault has a DelegateTimelock that wasn't passed"
+    )?;
+
+    // sub-account delegates are queued behind the vault's [`DelegateTimelock`] like the primary delegate
+    if let Some(delegate_timelock) = &ctx.accounts.delegate_timelock {
+        let now = Clock::get()?.unix_timestamp;
+        let sub_account_id = ctx.accounts.drift_user.load()?.sub_account_id;
+
+        delegate_timelock
+            .load_mut()?
+            .queue(&vault, delegate, sub_account_id, now)?;
+
+        return Ok(());
+    }
+
+    drop(vault);
+
+    ctx.drift_update_user_delegate(delegate)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateSubAccountDelegate<'info> {
+    #[account(
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    pub manager: Signer<'info>,
+    #[account(
+        mut,
+        constraint = is_sub_account_for_vault(&vault, &drift_user)?
+    )]
+    pub drift_user: AccountLoader<'info, User>,
+    pub drift_program: Program<'info, Drift>,
+    /// Pass the vault program id unless the manager opted into delegate timelocks
+    #[account(
+        mut,
+        seeds = [b"delegate_timelock", vault.key().as_ref()],
+        bump,
+    )]
+    pub delegate_timelock: Option<AccountLoader<'info, DelegateTimelock>>,
+}
+
+impl<'info> UpdateUserDelegateCPI for Context<'_, '_, '_, 'info, UpdateSubAccountDelegate<'info>> {
+    fn drift_update_user_delegate(&self, delegate: Pubkey) -> Result<()> {
+        let sub_account_id = self.accounts.drift_user.load()?.sub_account_id;
+        implement_update_user_delegate_cpi!(self, delegate, sub_account_id);
+        Ok(())
+    }
+}
@@ -1,6 +1,7 @@
 use crate::constraints::is_manager_for_vault;
 use crate::{error::ErrorCode, validate, Vault};
 use anchor_lang::prelude::*;
+use drift::math::casting::Cast;
 
 pub fn update_vault<'info>(
     ctx: Context<'_, '_, '_, 'info, UpdateVault<'info>>,
@@ -47,11 +48,11 @@ pub fn update_vault<'info>(
 
     if let Some(hurdle_rate) = params.hurdle_rate {
         validate!(
-            hurdle_rate < vault.hurdle_rate,
+            hurdle_rate < vault.hurdle_rate.cast()?,
             ErrorCode::InvalidVaultUpdate,
             "new hurdle rate must be less than existing hurdle rate"
         )?;
-        vault.hurdle_rate = hurdle_rate;
+        vault.hurdle_rate = hurdle_rate.cast()?;
     }
 
     if let Some(permissioned) = params.permissioned {
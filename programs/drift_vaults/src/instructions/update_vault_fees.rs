@@ -0,0 +1,163 @@
+use crate::constants::{BPS_PRECISION, MAX_LOAD_FEE_BPS};
+use crate::constraints::is_manager_for_vault;
+use crate::{error::ErrorCode, validate, Vault};
+use anchor_lang::prelude::*;
+use drift::math::casting::Cast;
+use drift::math::constants::PERCENTAGE_PRECISION;
+use drift::math::safe_math::SafeMath;
+
+pub fn update_vault_fees<'info>(
+    ctx: Context<'_, '_, '_, 'info, UpdateVaultFees<'info>>,
+    params: UpdateVaultFeesParams,
+) -> Result<()> {
+    let mut vault = ctx.accounts.vault.load_mut()?;
+
+    validate!(!vault.in_liquidation(), ErrorCode::OngoingLiquidation)?;
+
+    if let Some(referral_fee_share) = params.referral_fee_share {
+        validate!(
+            referral_fee_share.cast::<u128>()? <= PERCENTAGE_PRECISION,
+            ErrorCode::InvalidVaultUpdate,
+            "referral fee share must be <= 100%"
+        )?;
+        vault.referral_fee_share = referral_fee_share;
+    }
+
+    if let Some(entry_fee_bps) = params.entry_fee_bps {
+        validate!(
+            entry_fee_bps <= MAX_LOAD_FEE_BPS,
+            ErrorCode::InvalidVaultUpdate,
+            "entry fee must be <= {} bps",
+            MAX_LOAD_FEE_BPS
+        )?;
+        vault.entry_fee_bps = entry_fee_bps;
+    }
+
+    // exit fees can only be raised (and made to pay the manager) before there are depositors to exit
+    let has_depositors = vault.user_shares > 0;
+
+    if let Some(exit_fee_bps) = params.exit_fee_bps {
+        validate!(
+            !has_depositors || exit_fee_bps <= vault.exit_fee_bps,
+            ErrorCode::InvalidVaultUpdate,
+            "new exit fee must be less than or equal to existing exit fee"
+        )?;
+        vault.exit_fee_bps = exit_fee_bps;
+    }
+
+    if let Some(early_exit_fee_bps) = params.early_exit_fee_bps {
+        validate!(
+            !has_depositors || early_exit_fee_bps <= vault.early_exit_fee_bps,
+            ErrorCode::InvalidVaultUpdate,
+            "new early exit fee must be less than or equal to existing early exit fee"
+        )?;
+        vault.early_exit_fee_bps = early_exit_fee_bps;
+    }
+
+    if let Some(early_exit_fee_period) = params.early_exit_fee_period {
+        validate!(
+            !has_depositors || early_exit_fee_period <= vault.early_exit_fee_period,
+            ErrorCode::InvalidVaultUpdate,
+            "new early exit fee period must be less than or equal to existing early exit fee period"
+        )?;
+        vault.early_exit_fee_period = early_exit_fee_period;
+    }
+
+    validate!(
+        vault.exit_fee_bps.saturating_add(vault.early_exit_fee_bps) <= MAX_LOAD_FEE_BPS,
+        ErrorCode::InvalidVaultUpdate,
+        "exit fee plus early exit fee must be <= {} bps",
+        MAX_LOAD_FEE_BPS
+    )?;
+
+    if let Some(load_fees_to_manager) = params.load_fees_to_manager {
+        validate!(
+            !has_depositors || !load_fees_to_manager || vault.load_fees_to_manager,
+            ErrorCode::InvalidVaultUpdate,
+            "entry and exit fees can only be directed to the manager before the vault has depositors"
+        )?;
+        vault.load_fees_to_manager = load_fees_to_manager;
+    }
+
+    if let Some(management_fee_tiers) = params.management_fee_tiers {
+        let mut prev_threshold = 0_u64;
+        let mut prev_rate = vault.management_fee.max(0).cast::<u128>()?;
+
+        for (i, tier) in management_fee_tiers.iter().enumerate() {
+            let old_threshold = vault.management_fee_tier_thresholds[i];
+
+            // an unused tier charges the rate below it on all equity, so an existing tier can't be removed or raised
+            validate!(
+                old_threshold == 0
+                    || (tier.threshold != 0
+                        && tier.threshold <= old_threshold
+                        && tier.fee_bps <= vault.management_fee_tier_bps[i]),
+                ErrorCode::InvalidVaultUpdate,
+                "management fee tier {} can only be lowered",
+                i
+            )?;
+
+            if tier.threshold == 0 {
+                validate!(
+                    tier.fee_bps == 0,
+                    ErrorCode::InvalidVaultUpdate,
+                    "unused management fee tier {} must have a zero fee",
+                    i
+                )?;
+                prev_threshold = u64::MAX;
+                continue;
+            }
+
+            let rate = tier
+                .fee_bps
+                .cast::<u128>()?
+                .safe_mul(PERCENTAGE_PRECISION)?
+                .safe_div(BPS_PRECISION.cast()?)?;
+            validate!(
+                tier.threshold > prev_threshold && rate <= prev_rate,
+                ErrorCode::InvalidVaultUpdate,
+                "management fee tiers must have increasing thresholds and non-increasing fees"
+            )?;
+            prev_threshold = tier.threshold;
+            prev_rate = rate;
+        }
+
+        for (i, tier) in management_fee_tiers.iter().enumerate() {
+            vault.management_fee_tier_thresholds[i] = tier.threshold;
+            vault.management_fee_tier_bps[i] = tier.fee_bps;
+        }
+    }
+
+    drop(vault);
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct UpdateVaultFeesParams {
+    pub referral_fee_share: Option<u32>,
+    pub entry_fee_bps: Option<u16>,
+    pub exit_fee_bps: Option<u16>,
+    pub early_exit_fee_bps: Option<u16>,
+    pub early_exit_fee_period: Option<u32>,
+    pub load_fees_to_manager: Option<bool>,
+    pub management_fee_tiers: Option<[ManagementFeeTier; 2]>,
+}
+
+#[derive(Debug, Clone, Copy, Default, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct ManagementFeeTier {
+    /// Depositor equity at which the tier starts. Zero for an unused tier
+    pub threshold: u64,
+    /// The annual management fee charged on depositor equity above the threshold, in bps
+    pub fee_bps: u16,
+}
+
+#[derive(Accounts)]
+pub struct UpdateVaultFees<'info> {
+    #[account(
+        mut,
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    pub manager: Signer<'info>,
+}
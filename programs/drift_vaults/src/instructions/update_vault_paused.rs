@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::is_risk_officer_for_vault;
+use crate::error::ErrorCode;
+use crate::state::{Vault, VaultPauser, VaultProtocolProvider, VaultRolesProvider};
+
+pub fn update_vault_paused<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, UpdateVaultPaused<'info>>,
+    paused: u8,
+) -> Result<()> {
+    let now = Clock::get()?.unix_timestamp;
+    let mut vault = ctx.accounts.vault.load_mut()?;
+    let authority = ctx.accounts.authority.key();
+
+    // backwards compatible: if last rem acct does not deserialize into [`VaultProtocol`] then it's a legacy vault.
+    let mut vp = ctx.vault_protocol();
+    vault.validate_vault_protocol(&vp)?;
+
+    let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
+
+    let is_protocol = vp.as_ref().map_or(false, |vp| vp.protocol == authority);
+
+    // if the vault has [`VaultRoles`], they can be anywhere in the rem accts before any [`VaultProtocol`].
+    let vault_roles = ctx.vault_roles(&vault);
+    let is_risk_officer =
+        is_risk_officer_for_vault(&ctx.accounts.vault, &vault_roles, &ctx.accounts.authority)?;
+
+    // the protocol takes precedence so a manager that is also the protocol keeps protocol powers
+    let pauser = if is_protocol {
+        VaultPauser::Protocol
+    } else if vault.manager == authority {
+        VaultPauser::Manager
+    } else if is_risk_officer {
+        VaultPauser::RiskOfficer
+    } else {
+        msg!("only the vault manager, protocol or risk officer can pause the vault");
+        return Err(ErrorCode::InvalidVaultUpdate.into());
+    };
+
+    vault.update_paused_by(pauser, paused, &mut vp, authority, now)?;
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateVaultPaused<'info> {
+    #[account(mut)]
+    pub vault: AccountLoader<'info, Vault>,
+    pub authority: Signer<'info>,
+}
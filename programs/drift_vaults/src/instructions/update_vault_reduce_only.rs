@@ -0,0 +1,133 @@
+use anchor_lang::prelude::*;
+use drift::cpi::accounts::UpdateUser;
+use drift::program::Drift;
+use drift::state::user::User;
+
+use crate::constraints::{
+    is_manager_for_vault, is_risk_officer_for_vault, is_user_for_vault, is_user_stats_for_vault,
+};
+use crate::drift_cpi::{UpdateSubAccountReduceOnlyCPI, UpdateUserReduceOnlyCPI};
+use crate::error::ErrorCode;
+use crate::state::{
+    DrawdownGuardProvider, RiskLimitsProvider, SubAccountProvider, Vault, VaultCompanion,
+    VaultRolesProvider,
+};
+use crate::{declare_vault_seeds, implement_update_user_reduce_only_cpi, validate};
+
+pub fn update_vault_reduce_only<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, UpdateVaultReduceOnly<'info>>,
+    reduce_only: bool,
+) -> Result<()> {
+    // if the vault has [`VaultRoles`], they can be anywhere in the rem accts.
+    let vault_roles = ctx.vault_roles(&*ctx.accounts.vault.load()?);
+    validate!(
+        is_manager_for_vault(&ctx.accounts.vault, &ctx.accounts.authority)?
+            || is_risk_officer_for_vault(
+                &ctx.accounts.vault,
+                &vault_roles,
+                &ctx.accounts.authority
+            )?,
+        ErrorCode::InvalidVaultRole,
+        "only the vault manager or risk officer can update reduce only"
+    )?;
+
+    let vault = ctx.accounts.vault.load()?;
+
+    if !reduce_only {
+        // a shut down or liquidating vault has to stay reduce-only
+        validate!(
+            !vault.shutdown,
+            ErrorCode::VaultShutdown,
+            "vault is shut down"
+        )?;
+        validate!(
+            !vault.in_liquidation(),
+            ErrorCode::OngoingLiquidation,
+            "vault is in liquidation"
+        )?;
+
+        // a tripped circuit breaker is lifted through reset_drawdown_guard or reset_risk_limits, which enforce
+        // their cooldown and re-check
+        let drawdown_guard = ctx.drawdown_guard(&vault);
+        validate!(
+            drawdown_guard.is_some() || !vault.has_companion(VaultCompanion::DrawdownGuard),
+            ErrorCode::InvalidDrawdownGuard,
+            "DrawdownGuard missing in remaining accounts"
+        )?;
+        if let Some(drawdown_guard) = drawdown_guard {
+            validate!(
+                !drawdown_guard.load()?.is_tripped(),
+                ErrorCode::InvalidDrawdownGuard,
+                "drawdown guard is tripped"
+            )?;
+        }
+
+        let risk_limits = ctx.risk_limits(&vault);
+        validate!(
+            risk_limits.is_some() || !vault.has_companion(VaultCompanion::RiskLimits),
+            ErrorCode::InvalidRiskLimits,
+            "RiskLimits missing in remaining accounts"
+        )?;
+        if let Some(risk_limits) = risk_limits {
+            validate!(
+                !risk_limits.load()?.is_breached(),
+                ErrorCode::InvalidRiskLimits,
+                "risk limits are breached"
+            )?;
+        }
+    }
+
+    // every sub-account follows the primary user, so they must all be in the rem accts
+    let sub_accounts = ctx.sub_accounts(&vault, &ctx.accounts.drift_user_stats)?;
+
+    msg!("vault reduce only: {}", reduce_only);
+
+    drop(vault);
+
+    ctx.drift_update_user_reduce_only(reduce_only)?;
+    for sub_account in sub_accounts.iter() {
+        ctx.drift_update_sub_account_reduce_only(sub_account, reduce_only)?;
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct UpdateVaultReduceOnly<'info> {
+    pub vault: AccountLoader<'info, Vault>,
+    /// The vault manager or risk officer, checked in the handler
+    pub authority: Signer<'info>,
+    #[account(
+        constraint = is_user_stats_for_vault(&vault, &drift_user_stats)?
+    )]
+    /// CHECK: checked by constraint
+    pub drift_user_stats: AccountInfo<'info>,
+    #[account(
+        mut,
+        constraint = is_user_for_vault(&vault, &drift_user.key())?
+    )]
+    /// CHECK: checked in drift cpi
+    pub drift_user: AccountLoader<'info, User>,
+    pub drift_program: Program<'info, Drift>,
+}
+
+impl<'info> UpdateUserReduceOnlyCPI for Context<'_, '_, '_, 'info, UpdateVaultReduceOnly<'info>> {
+    fn drift_update_user_reduce_only(&self, reduce_only: bool) -> Result<()> {
+        implement_update_user_reduce_only_cpi!(self, reduce_only);
+        Ok(())
+    }
+}
+
+impl<'c: 'info, 'info> UpdateSubAccountReduceOnlyCPI<'c>
+    for Context<'_, '_, 'c, 'info, UpdateVaultReduceOnly<'info>>
+{
+    fn drift_update_sub_account_reduce_only(
+        &self,
+        sub_account: &AccountLoader<'c, User>,
+        reduce_only: bool,
+    ) -> Result<()> {
+        let sub_account_id = sub_account.load()?.sub_account_id;
+        implement_update_user_reduce_only_cpi!(self, reduce_only, sub_account_id, sub_account);
+        Ok(())
+    }
+}
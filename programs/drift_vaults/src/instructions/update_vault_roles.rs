@@ -0,0 +1,46 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::{is_manager_for_vault, is_vault_for_vault_roles};
+use crate::state::{Vault, VaultRoles};
+
+pub fn update_vault_roles(
+    ctx: Context<UpdateVaultRoles>,
+    params: UpdateVaultRolesParams,
+) -> Result<()> {
+    let mut vault_roles = ctx.accounts.vault_roles.load_mut()?;
+
+    if let Some(risk_officer) = params.risk_officer {
+        vault_roles.risk_officer = risk_officer;
+    }
+
+    if let Some(keeper) = params.keeper {
+        vault_roles.keeper = keeper;
+    }
+
+    if let Some(operator) = params.operator {
+        vault_roles.operator = operator;
+    }
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct UpdateVaultRolesParams {
+    pub risk_officer: Option<Pubkey>,
+    pub keeper: Option<Pubkey>,
+    pub operator: Option<Pubkey>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateVaultRoles<'info> {
+    #[account(
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_vault_for_vault_roles(&vault_roles, &vault)?,
+    )]
+    pub vault_roles: AccountLoader<'info, VaultRoles>,
+    pub manager: Signer<'info>,
+}
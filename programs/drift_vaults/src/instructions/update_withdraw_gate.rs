@@ -0,0 +1,58 @@
+use anchor_lang::prelude::*;
+
+use crate::constraints::{is_manager_for_vault, is_vault_for_withdraw_gate};
+use crate::state::{Vault, WithdrawGate};
+use crate::{error::ErrorCode, validate};
+
+pub fn update_withdraw_gate(
+    ctx: Context<UpdateWithdrawGate>,
+    params: UpdateWithdrawGateParams,
+) -> Result<()> {
+    let vault = ctx.accounts.vault.load()?;
+    let mut withdraw_gate = ctx.accounts.withdraw_gate.load_mut()?;
+
+    // the gate can only be loosened once there are depositors relying on it
+    let has_depositors = vault.user_shares > 0;
+
+    if let Some(gate_bps) = params.gate_bps {
+        validate!(
+            !has_depositors || gate_bps >= withdraw_gate.gate_bps,
+            ErrorCode::InvalidWithdrawGate,
+            "new gate must be greater than or equal to existing gate"
+        )?;
+        withdraw_gate.gate_bps = gate_bps;
+    }
+
+    if let Some(epoch_duration) = params.epoch_duration {
+        validate!(
+            !has_depositors || epoch_duration <= withdraw_gate.epoch_duration,
+            ErrorCode::InvalidWithdrawGate,
+            "new epoch duration must be less than or equal to existing epoch duration"
+        )?;
+        withdraw_gate.epoch_duration = epoch_duration;
+    }
+
+    WithdrawGate::validate_params(withdraw_gate.gate_bps, withdraw_gate.epoch_duration, &vault)?;
+
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, AnchorSerialize, AnchorDeserialize, PartialEq, Eq)]
+pub struct UpdateWithdrawGateParams {
+    pub gate_bps: Option<u16>,
+    pub epoch_duration: Option<i64>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateWithdrawGate<'info> {
+    #[account(
+        constraint = is_manager_for_vault(&vault, &manager)?,
+    )]
+    pub vault: AccountLoader<'info, Vault>,
+    #[account(
+        mut,
+        constraint = is_vault_for_withdraw_gate(&withdraw_gate, &vault)?,
+    )]
+    pub withdraw_gate: AccountLoader<'info, WithdrawGate>,
+    pub manager: Signer<'info>,
+}
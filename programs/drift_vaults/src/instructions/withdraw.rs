@@ -9,25 +9,83 @@ use drift::state::user::User;
 use crate::constraints::{
     is_authority_for_vault_depositor, is_user_for_vault, is_user_stats_for_vault,
 };
-use crate::drift_cpi::{UpdateUserDelegateCPI, UpdateUserReduceOnlyCPI, WithdrawCPI};
-use crate::state::{Vault, VaultDepositor, VaultProtocolProvider};
+use crate::drift_cpi::{
+    UpdateSubAccountDelegateCPI, UpdateSubAccountReduceOnlyCPI, UpdateUserDelegateCPI,
+    UpdateUserReduceOnlyCPI, WithdrawCPI,
+};
+use crate::error::ErrorCode;
+use crate::state::{
+    ShareClassProvider, SubAccountProvider, Vault, VaultAllocationsProvider, VaultDepositor,
+    VaultDepositorBase, VaultPauseFlag, VaultProtocolProvider, VaultReferrerProvider,
+    WithdrawGateProvider,
+};
 use crate::token_cpi::TokenTransferCPI;
 use crate::{
     declare_vault_seeds, implement_update_user_delegate_cpi, implement_update_user_reduce_only_cpi,
-    implement_withdraw, AccountMapProvider,
+    implement_withdraw, validate, AccountMapProvider,
 };
 
-pub fn withdraw<'c: 'info, 'info>(ctx: Context<'_, '_, 'c, 'info, Withdraw<'info>>) -> Result<()> {
+pub fn withdraw<'c: 'info, 'info>(
+    ctx: Context<'_, '_, 'c, 'info, Withdraw<'info>>,
+    min_amount_out: Option<u64>,
+) -> Result<()> {
+    let (user_withdraw_amount, finishing_liquidation) = withdraw_from_vault_depositor(
+        &ctx,
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_depositor,
+        &ctx.accounts.drift_user,
+        &ctx.accounts.drift_user_stats,
+        min_amount_out,
+    )?;
+
+    ctx.drift_withdraw(user_withdraw_amount)?;
+
+    ctx.token_transfer(user_withdraw_amount)?;
+
+    if finishing_liquidation {
+        exit_liquidation(&ctx, &ctx.accounts.vault, &ctx.accounts.drift_user_stats)?;
+    }
+
+    Ok(())
+}
+
+/// Pays out the depositor's matured withdraw request, returning the amount and whether it finishes the depositor's
+/// liquidation of the vault. Every withdraw from a [`VaultDepositor`] goes through this; the caller then withdraws the
+/// amount from drift, pays it out and, if finishing a liquidation, calls [`exit_liquidation`].
+pub fn withdraw_from_vault_depositor<'c: 'info, 'info, T: anchor_lang::Bumps>(
+    ctx: &Context<'_, '_, 'c, 'info, T>,
+    vault: &AccountLoader<'info, Vault>,
+    vault_depositor: &AccountLoader<'info, VaultDepositor>,
+    drift_user: &AccountLoader<'info, User>,
+    drift_user_stats: &AccountInfo<'info>,
+    min_amount_out: Option<u64>,
+) -> Result<(u64, bool)> {
     let clock = &Clock::get()?;
-    let mut vault = ctx.accounts.vault.load_mut()?;
-    let mut vault_depositor = ctx.accounts.vault_depositor.load_mut()?;
+    let mut vault = vault.load_mut()?;
+    vault.validate_not_paused(VaultPauseFlag::Withdraw, clock.unix_timestamp)?;
+    let mut vault_depositor = vault_depositor.load_mut()?;
 
     // backwards compatible: if last rem acct does not deserialize into [`VaultProtocol`] then it's a legacy vault.
     let mut vp = ctx.vault_protocol();
     vault.validate_vault_protocol(&vp)?;
     let mut vp = vp.as_mut().map(|vp| vp.load_mut()).transpose()?;
 
-    let user = ctx.accounts.drift_user.load()?;
+    // if the depositor was referred, its [`VaultReferrer`] can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut vr = ctx.vault_referrer(&vault_depositor);
+    vault_depositor.validate_vault_referrer(&vr)?;
+    let mut vr = vr.as_mut().map(|vr| vr.load_mut()).transpose()?;
+
+    // if the depositor is in a [`ShareClass`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut sc = ctx.share_class(&vault_depositor);
+    vault_depositor.validate_share_class(&sc)?;
+    let mut sc = sc.as_mut().map(|sc| sc.load_mut()).transpose()?;
+
+    // if the vault has a [`WithdrawGate`], it can be anywhere in the rem accts before any [`VaultProtocol`].
+    let mut gate = ctx.withdraw_gate(&vault);
+    vault.validate_withdraw_gate(&gate)?;
+    let mut gate = gate.as_mut().map(|gate| gate.load_mut()).transpose()?;
+
+    let user = drift_user.load()?;
     let spot_market_index = vault.spot_market_index;
 
     let AccountMaps {
@@ -36,30 +94,97 @@ pub fn withdraw<'c: 'info, 'info>(ctx: Context<'_, '_, 'c, 'info, Withdraw<'info
         mut oracle_map,
     } = ctx.load_maps(clock.slot, Some(spot_market_index), vp.is_some())?;
 
-    let vault_equity =
-        vault.calculate_equity(&user, &perp_market_map, &spot_market_map, &mut oracle_map)?;
+    let sub_accounts = ctx.sub_accounts(&vault, drift_user_stats)?;
+    let allocations = ctx.vault_allocations(&vault)?;
+    let vault_equity = vault.calculate_equity(
+        &user,
+        &sub_accounts,
+        &allocations,
+        &perp_market_map,
+        &spot_market_map,
+        &mut oracle_map,
+        clock.unix_timestamp,
+    )?;
+
+    vault_depositor.settle_fees(
+        sc.as_deref_mut(),
+        vr.as_deref_mut(),
+        vault_equity,
+        &mut vault,
+        &mut vp,
+        clock.unix_timestamp,
+    )?;
 
+    // a gated vault only fills the part of the request that fits in the epoch's withdraw capacity
+    let remaining_request = match gate.as_mut() {
+        Some(gate) => gate.split_request(
+            &mut vault_depositor.last_withdraw_request,
+            &vault,
+            vault_equity,
+            clock.unix_timestamp,
+        )?,
+        None => None,
+    };
+
+    let class_shares_before = vault_depositor.get_vault_shares();
     let (user_withdraw_amount, finishing_liquidation) =
         vault_depositor.withdraw(vault_equity, &mut vault, &mut vp, clock.unix_timestamp)?;
 
-    msg!("user_withdraw_amount: {}", user_withdraw_amount);
+    if let Some(gate) = gate.as_mut() {
+        let authority = vault_depositor.authority;
+        gate.record_withdraw(
+            authority,
+            user_withdraw_amount,
+            remaining_request,
+            &mut vault_depositor.last_withdraw_request,
+            clock.unix_timestamp,
+        )?;
+    }
 
-    drop(vault);
-    drop(user);
-    drop(vp);
+    if let Some(sc) = sc.as_mut() {
+        sc.update_user_shares(class_shares_before, vault_depositor.get_vault_shares())?;
+    }
 
-    ctx.drift_withdraw(user_withdraw_amount)?;
+    msg!("user_withdraw_amount: {}", user_withdraw_amount);
 
-    ctx.token_transfer(user_withdraw_amount)?;
+    if let Some(min_amount_out) = min_amount_out {
+        validate!(
+            user_withdraw_amount >= min_amount_out,
+            ErrorCode::SlippageExceeded,
+            "withdraw paid {}, less than min_amount_out {}",
+            user_withdraw_amount,
+            min_amount_out
+        )?;
+    }
 
-    if finishing_liquidation {
-        let mut vault = ctx.accounts.vault.load_mut()?;
-        let vault_delegate = vault.delegate;
-        vault.reset_liquidation_delegate();
-        drop(vault);
+    Ok((user_withdraw_amount, finishing_liquidation))
+}
 
-        ctx.drift_update_user_delegate(vault_delegate)?;
-        ctx.drift_update_user_reduce_only(false)?;
+/// Hands the vault's drift users back from the liquidator once its withdraw is paid out
+pub fn exit_liquidation<'a, 'b, 'c: 'info, 'info, T: anchor_lang::Bumps>(
+    ctx: &Context<'a, 'b, 'c, 'info, T>,
+    vault: &AccountLoader<'info, Vault>,
+    drift_user_stats: &AccountInfo<'info>,
+) -> Result<()>
+where
+    Context<'a, 'b, 'c, 'info, T>: UpdateUserDelegateCPI
+        + UpdateUserReduceOnlyCPI
+        + UpdateSubAccountDelegateCPI<'c>
+        + UpdateSubAccountReduceOnlyCPI<'c>,
+{
+    let mut vault = vault.load_mut()?;
+    let vault_delegate = vault.delegate;
+    vault.reset_liquidation_delegate();
+    let sub_accounts = ctx.sub_accounts(&vault, drift_user_stats)?;
+    drop(vault);
+
+    ctx.drift_update_user_delegate(vault_delegate)?;
+    ctx.drift_update_user_reduce_only(false)?;
+    // sub-account delegates aren't tracked on the vault, so the manager restores them with
+    // `update_sub_account_delegate`
+    for sub_account in sub_accounts.iter() {
+        ctx.drift_update_sub_account_delegate(sub_account, Pubkey::default())?;
+        ctx.drift_update_sub_account_reduce_only(sub_account, false)?;
     }
 
     Ok(())
@@ -152,3 +277,31 @@ impl<'info> UpdateUserReduceOnlyCPI for Context<'_, '_, '_, 'info, Withdraw<'inf
         Ok(())
     }
 }
+
+impl<'c: 'info, 'info> UpdateSubAccountDelegateCPI<'c>
+    for Context<'_, '_, 'c, 'info, Withdraw<'info>>
+{
+    fn drift_update_sub_account_delegate(
+        &self,
+        sub_account: &AccountLoader<'c, User>,
+        delegate: Pubkey,
+    ) -> Result<()> {
+        let sub_account_id = sub_account.load()?.sub_account_id;
+        implement_update_user_delegate_cpi!(self, delegate, sub_account_id, sub_account);
+        Ok(())
+    }
+}
+
+impl<'c: 'info, 'info> UpdateSubAccountReduceOnlyCPI<'c>
+    for Context<'_, '_, 'c, 'info, Withdraw<'info>>
+{
+    fn drift_update_sub_account_reduce_only(
+        &self,
+        sub_account: &AccountLoader<'c, User>,
+        reduce_only: bool,
+    ) -> Result<()> {
+        let sub_account_id = sub_account.load()?.sub_account_id;
+        implement_update_user_reduce_only_cpi!(self, reduce_only, sub_account_id, sub_account);
+        Ok(())
+    }
+}
@@ -10,6 +10,7 @@ pub mod macros;
 mod state;
 mod tests;
 mod token_cpi;
+mod vault_cpi;
 
 declare_id!("vAuLTsyrvSfZRuRB3XgvkPwNGgYSs9YRYymVebLKoxR");
 
@@ -59,6 +60,13 @@ pub mod drift_vaults {
         instructions::update_vault(ctx, params)
     }
 
+    pub fn update_vault_fees<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, UpdateVaultFees<'info>>,
+        params: UpdateVaultFeesParams,
+    ) -> Result<()> {
+        instructions::update_vault_fees(ctx, params)
+    }
+
     pub fn initialize_vault_depositor(ctx: Context<InitializeVaultDepositor>) -> Result<()> {
         instructions::initialize_vault_depositor(ctx)
     }
@@ -82,14 +90,59 @@ pub mod drift_vaults {
         ctx: Context<'_, '_, 'info, 'info, RedeemTokens<'info>>,
         tokens_to_burn: u64,
     ) -> Result<()> {
-        instructions::redeem_tokens(ctx, tokens_to_burn)
+        instructions::redeem_tokens(ctx, tokens_to_burn, None)
+    }
+
+    pub fn redeem_tokens_with_min_amount<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RedeemTokens<'info>>,
+        tokens_to_burn: u64,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        instructions::redeem_tokens(ctx, tokens_to_burn, Some(min_amount_out))
+    }
+
+    pub fn request_tokenized_withdraw<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RequestTokenizedWithdraw<'info>>,
+        tokens_to_burn: u64,
+    ) -> Result<()> {
+        instructions::request_tokenized_withdraw(ctx, tokens_to_burn)
+    }
+
+    pub fn tokenized_withdraw<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, TokenizedWithdraw<'info>>,
+    ) -> Result<()> {
+        instructions::tokenized_withdraw(ctx)
     }
 
     pub fn deposit<'c: 'info, 'info>(
         ctx: Context<'_, '_, 'c, 'info, Deposit<'info>>,
         amount: u64,
     ) -> Result<()> {
-        instructions::deposit(ctx, amount)
+        instructions::deposit(ctx, amount, None)
+    }
+
+    pub fn deposit_with_min_shares<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, Deposit<'info>>,
+        amount: u64,
+        min_shares_out: u128,
+    ) -> Result<()> {
+        instructions::deposit(ctx, amount, Some(min_shares_out))
+    }
+
+    pub fn deposit_for<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, DepositFor<'info>>,
+        amount: u64,
+        min_shares_out: Option<u128>,
+    ) -> Result<()> {
+        instructions::deposit_for(ctx, amount, min_shares_out)
+    }
+
+    pub fn deposit_from_drift_user<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, DepositFromDriftUser<'info>>,
+        amount: u64,
+        min_shares_out: Option<u128>,
+    ) -> Result<()> {
+        instructions::deposit_from_drift_user(ctx, amount, min_shares_out)
     }
 
     pub fn request_withdraw<'c: 'info, 'info>(
@@ -109,7 +162,21 @@ pub mod drift_vaults {
     pub fn withdraw<'c: 'info, 'info>(
         ctx: Context<'_, '_, 'c, 'info, Withdraw<'info>>,
     ) -> Result<()> {
-        instructions::withdraw(ctx)
+        instructions::withdraw(ctx, None)
+    }
+
+    pub fn withdraw_with_min_amount<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, Withdraw<'info>>,
+        min_amount_out: u64,
+    ) -> Result<()> {
+        instructions::withdraw(ctx, Some(min_amount_out))
+    }
+
+    pub fn withdraw_to_drift_user<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, WithdrawToDriftUser<'info>>,
+        min_amount_out: Option<u64>,
+    ) -> Result<()> {
+        instructions::withdraw_to_drift_user(ctx, min_amount_out)
     }
 
     pub fn liquidate<'c: 'info, 'info>(
@@ -151,6 +218,359 @@ pub mod drift_vaults {
         instructions::manager_withdraw(ctx)
     }
 
+    pub fn initialize_manager_position<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, InitializeManagerPosition<'info>>,
+    ) -> Result<()> {
+        instructions::initialize_manager_position(ctx)
+    }
+
+    pub fn manager_tokenize_shares<'info>(
+        ctx: Context<'_, '_, 'info, 'info, ManagerTokenizeShares<'info>>,
+        amount: u64,
+        unit: WithdrawUnit,
+    ) -> Result<()> {
+        instructions::manager_tokenize_shares(ctx, amount, unit)
+    }
+
+    pub fn initialize_fee_recipients<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, InitializeFeeRecipients<'info>>,
+        params: FeeRecipientsParams,
+    ) -> Result<()> {
+        instructions::initialize_fee_recipients(ctx, params)
+    }
+
+    pub fn update_fee_recipients<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, UpdateFeeRecipients<'info>>,
+        recipients: Vec<FeeRecipientParams>,
+    ) -> Result<()> {
+        instructions::update_fee_recipients(ctx, recipients)
+    }
+
+    pub fn request_claim_fees<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, RequestClaimFees<'info>>,
+        recipient_index: u8,
+    ) -> Result<()> {
+        instructions::request_claim_fees(ctx, recipient_index)
+    }
+
+    pub fn claim_fees<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ClaimFees<'info>>,
+        recipient_index: u8,
+    ) -> Result<()> {
+        instructions::claim_fees(ctx, recipient_index)
+    }
+
+    pub fn initialize_vault_referrer(ctx: Context<InitializeVaultReferrer>) -> Result<()> {
+        instructions::initialize_vault_referrer(ctx)
+    }
+
+    pub fn initialize_vault_depositor_with_referrer(
+        ctx: Context<InitializeVaultDepositorWithReferrer>,
+    ) -> Result<()> {
+        instructions::initialize_vault_depositor_with_referrer(ctx)
+    }
+
+    pub fn referrer_request_withdraw<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ReferrerRequestWithdraw<'info>>,
+        withdraw_amount: u64,
+        withdraw_unit: WithdrawUnit,
+    ) -> Result<()> {
+        instructions::referrer_request_withdraw(ctx, withdraw_amount, withdraw_unit)
+    }
+
+    pub fn referrer_cancel_withdraw_request<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ReferrerCancelWithdrawRequest<'info>>,
+    ) -> Result<()> {
+        instructions::referrer_cancel_withdraw_request(ctx)
+    }
+
+    pub fn referrer_withdraw<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ReferrerWithdraw<'info>>,
+    ) -> Result<()> {
+        instructions::referrer_withdraw(ctx)
+    }
+
+    pub fn initialize_share_class(
+        ctx: Context<InitializeShareClass>,
+        params: ShareClassParams,
+    ) -> Result<()> {
+        instructions::initialize_share_class(ctx, params)
+    }
+
+    pub fn update_share_class(
+        ctx: Context<UpdateShareClass>,
+        params: UpdateShareClassParams,
+    ) -> Result<()> {
+        instructions::update_share_class(ctx, params)
+    }
+
+    pub fn join_share_class(ctx: Context<JoinShareClass>) -> Result<()> {
+        instructions::join_share_class(ctx)
+    }
+
+    pub fn update_vault_depositor_fee_override<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, UpdateVaultDepositorFeeOverride<'info>>,
+        params: FeeOverrideParams,
+    ) -> Result<()> {
+        instructions::update_vault_depositor_fee_override(ctx, params)
+    }
+
+    pub fn shutdown_vault<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ShutdownVault<'info>>,
+    ) -> Result<()> {
+        instructions::shutdown_vault(ctx)
+    }
+
+    pub fn shutdown_withdraw<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ShutdownWithdraw<'info>>,
+    ) -> Result<()> {
+        instructions::shutdown_withdraw(ctx)
+    }
+
+    pub fn shutdown_sweep_sub_account<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ShutdownSweepSubAccount<'info>>,
+    ) -> Result<()> {
+        instructions::shutdown_sweep_sub_account(ctx)
+    }
+
+    pub fn close_vault_depositor<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CloseVaultDepositor<'info>>,
+    ) -> Result<()> {
+        instructions::close_vault_depositor(ctx)
+    }
+
+    pub fn close_tokenized_vault_depositor(
+        ctx: Context<CloseTokenizedVaultDepositor>,
+    ) -> Result<()> {
+        instructions::close_tokenized_vault_depositor(ctx)
+    }
+
+    pub fn close_vault<'info>(ctx: Context<'_, '_, 'info, 'info, CloseVault<'info>>) -> Result<()> {
+        instructions::close_vault(ctx)
+    }
+
+    pub fn update_vault_paused<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, UpdateVaultPaused<'info>>,
+        paused: u8,
+    ) -> Result<()> {
+        instructions::update_vault_paused(ctx, paused)
+    }
+
+    pub fn initialize_withdraw_gate(
+        ctx: Context<InitializeWithdrawGate>,
+        params: WithdrawGateParams,
+    ) -> Result<()> {
+        instructions::initialize_withdraw_gate(ctx, params)
+    }
+
+    pub fn update_withdraw_gate(
+        ctx: Context<UpdateWithdrawGate>,
+        params: UpdateWithdrawGateParams,
+    ) -> Result<()> {
+        instructions::update_withdraw_gate(ctx, params)
+    }
+
+    pub fn update_min_manager_share(
+        ctx: Context<UpdateMinManagerShare>,
+        min_manager_share_bps: u16,
+    ) -> Result<()> {
+        instructions::update_min_manager_share(ctx, min_manager_share_bps)
+    }
+
+    pub fn initialize_drawdown_guard(
+        ctx: Context<InitializeDrawdownGuard>,
+        params: DrawdownGuardParams,
+    ) -> Result<()> {
+        instructions::initialize_drawdown_guard(ctx, params)
+    }
+
+    pub fn check_drawdown<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CheckDrawdown<'info>>,
+    ) -> Result<()> {
+        instructions::check_drawdown(ctx)
+    }
+
+    pub fn reset_drawdown_guard<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ResetDrawdownGuard<'info>>,
+    ) -> Result<()> {
+        instructions::reset_drawdown_guard(ctx)
+    }
+
+    pub fn initialize_risk_limits(
+        ctx: Context<InitializeRiskLimits>,
+        params: RiskLimitsParams,
+    ) -> Result<()> {
+        instructions::initialize_risk_limits(ctx, params)
+    }
+
+    pub fn update_risk_limits(
+        ctx: Context<UpdateRiskLimits>,
+        params: RiskLimitsParams,
+    ) -> Result<()> {
+        instructions::update_risk_limits(ctx, params)
+    }
+
+    pub fn check_vault_risk<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, CheckVaultRisk<'info>>,
+    ) -> Result<()> {
+        instructions::check_vault_risk(ctx)
+    }
+
+    pub fn reset_risk_limits<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ResetRiskLimits<'info>>,
+    ) -> Result<()> {
+        instructions::reset_risk_limits(ctx)
+    }
+
+    pub fn initialize_vault_roles(
+        ctx: Context<InitializeVaultRoles>,
+        params: VaultRolesParams,
+    ) -> Result<()> {
+        instructions::initialize_vault_roles(ctx, params)
+    }
+
+    pub fn update_vault_roles(
+        ctx: Context<UpdateVaultRoles>,
+        params: UpdateVaultRolesParams,
+    ) -> Result<()> {
+        instructions::update_vault_roles(ctx, params)
+    }
+
+    pub fn update_vault_reduce_only<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, UpdateVaultReduceOnly<'info>>,
+        reduce_only: bool,
+    ) -> Result<()> {
+        instructions::update_vault_reduce_only(ctx, reduce_only)
+    }
+
+    pub fn initialize_delegate_timelock(
+        ctx: Context<InitializeDelegateTimelock>,
+        delay: i64,
+    ) -> Result<()> {
+        instructions::initialize_delegate_timelock(ctx, delay)
+    }
+
+    pub fn cancel_delegate_change(ctx: Context<CancelDelegateChange>) -> Result<()> {
+        instructions::cancel_delegate_change(ctx)
+    }
+
+    pub fn apply_delegate_change<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ApplyDelegateChange<'info>>,
+    ) -> Result<()> {
+        instructions::apply_delegate_change(ctx)
+    }
+
+    pub fn initialize_sub_account<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, InitializeSubAccount<'info>>,
+        sub_account_id: u16,
+    ) -> Result<()> {
+        instructions::initialize_sub_account(ctx, sub_account_id)
+    }
+
+    pub fn update_sub_account_delegate<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, UpdateSubAccountDelegate<'info>>,
+        delegate: Pubkey,
+    ) -> Result<()> {
+        instructions::update_sub_account_delegate(ctx, delegate)
+    }
+
+    pub fn transfer_sub_account_deposit<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, TransferSubAccountDeposit<'info>>,
+        market_index: u16,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::transfer_sub_account_deposit(ctx, market_index, amount)
+    }
+
+    pub fn initialize_vault_allocations(ctx: Context<InitializeVaultAllocations>) -> Result<()> {
+        instructions::initialize_vault_allocations(ctx)
+    }
+
+    pub fn add_vault_allocation(ctx: Context<AddVaultAllocation>) -> Result<()> {
+        instructions::add_vault_allocation(ctx)
+    }
+
+    pub fn remove_vault_allocation(ctx: Context<RemoveVaultAllocation>) -> Result<()> {
+        instructions::remove_vault_allocation(ctx)
+    }
+
+    pub fn child_vault_deposit<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ChildVaultDeposit<'info>>,
+        amount: u64,
+        min_shares_out: Option<u128>,
+    ) -> Result<()> {
+        instructions::child_vault_deposit(ctx, amount, min_shares_out)
+    }
+
+    pub fn child_vault_request_withdraw<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ChildVaultRequestWithdraw<'info>>,
+        withdraw_amount: u64,
+        withdraw_unit: WithdrawUnit,
+    ) -> Result<()> {
+        instructions::child_vault_request_withdraw(ctx, withdraw_amount, withdraw_unit)
+    }
+
+    pub fn child_vault_withdraw<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, ChildVaultWithdraw<'info>>,
+        min_amount_out: Option<u64>,
+    ) -> Result<()> {
+        instructions::child_vault_withdraw(ctx, min_amount_out)
+    }
+
+    pub fn initialize_depositor_operator(
+        ctx: Context<InitializeDepositorOperator>,
+        params: DepositorOperatorParams,
+    ) -> Result<()> {
+        instructions::initialize_depositor_operator(ctx, params)
+    }
+
+    pub fn update_depositor_operator(
+        ctx: Context<UpdateDepositorOperator>,
+        params: DepositorOperatorParams,
+    ) -> Result<()> {
+        instructions::update_depositor_operator(ctx, params)
+    }
+
+    pub fn operator_request_withdraw<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, OperatorRequestWithdraw<'info>>,
+        withdraw_amount: u64,
+        withdraw_unit: WithdrawUnit,
+    ) -> Result<()> {
+        instructions::operator_request_withdraw(ctx, withdraw_amount, withdraw_unit)
+    }
+
+    pub fn operator_cancel_withdraw_request<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, OperatorCancelWithdrawRequest<'info>>,
+    ) -> Result<()> {
+        instructions::operator_cancel_withdraw_request(ctx)
+    }
+
+    pub fn operator_withdraw<'c: 'info, 'info>(
+        ctx: Context<'_, '_, 'c, 'info, OperatorWithdraw<'info>>,
+        min_amount_out: Option<u64>,
+    ) -> Result<()> {
+        instructions::operator_withdraw(ctx, min_amount_out)
+    }
+
+    pub fn request_vault_depositor_transfer(
+        ctx: Context<RequestVaultDepositorTransfer>,
+        new_authority: Pubkey,
+    ) -> Result<()> {
+        instructions::request_vault_depositor_transfer(ctx, new_authority)
+    }
+
+    pub fn cancel_vault_depositor_transfer(
+        ctx: Context<CancelVaultDepositorTransfer>,
+    ) -> Result<()> {
+        instructions::cancel_vault_depositor_transfer(ctx)
+    }
+
+    pub fn accept_vault_depositor_transfer(
+        ctx: Context<AcceptVaultDepositorTransfer>,
+    ) -> Result<()> {
+        instructions::accept_vault_depositor_transfer(ctx)
+    }
+
     pub fn apply_profit_share<'c: 'info, 'info>(
         ctx: Context<'_, '_, 'c, 'info, ApplyProfitShare<'info>>,
     ) -> Result<()> {
@@ -35,32 +35,54 @@ macro_rules! declare_vault_seeds {
 #[macro_export]
 macro_rules! implement_update_user_delegate_cpi {
     ( $self:expr, $delegate:expr ) => {
+        implement_update_user_delegate_cpi!($self, $delegate, 0)
+    };
+    ( $self:expr, $delegate:expr, $sub_account_id:expr ) => {
+        implement_update_user_delegate_cpi!(
+            $self,
+            $delegate,
+            $sub_account_id,
+            $self.accounts.drift_user
+        )
+    };
+    ( $self:expr, $delegate:expr, $sub_account_id:expr, $user:expr ) => {
         declare_vault_seeds!($self.accounts.vault, seeds);
 
         let cpi_accounts = UpdateUser {
-            user: $self.accounts.drift_user.to_account_info().clone(),
+            user: $user.to_account_info().clone(),
             authority: $self.accounts.vault.to_account_info().clone(),
         };
 
         let drift_program = $self.accounts.drift_program.to_account_info().clone();
         let cpi_context = CpiContext::new_with_signer(drift_program, cpi_accounts, seeds);
-        drift::cpi::update_user_delegate(cpi_context, 0, $delegate)?;
+        drift::cpi::update_user_delegate(cpi_context, $sub_account_id, $delegate)?;
     };
 }
 
 #[macro_export]
 macro_rules! implement_update_user_reduce_only_cpi {
     ( $self:expr, $reduce_only:expr ) => {
+        implement_update_user_reduce_only_cpi!($self, $reduce_only, 0)
+    };
+    ( $self:expr, $reduce_only:expr, $sub_account_id:expr ) => {
+        implement_update_user_reduce_only_cpi!(
+            $self,
+            $reduce_only,
+            $sub_account_id,
+            $self.accounts.drift_user
+        )
+    };
+    ( $self:expr, $reduce_only:expr, $sub_account_id:expr, $user:expr ) => {
         declare_vault_seeds!($self.accounts.vault, seeds);
 
         let cpi_accounts = UpdateUser {
-            user: $self.accounts.drift_user.to_account_info().clone(),
+            user: $user.to_account_info().clone(),
             authority: $self.accounts.vault.to_account_info().clone(),
         };
 
         let drift_program = $self.accounts.drift_program.to_account_info().clone();
         let cpi_context = CpiContext::new_with_signer(drift_program, cpi_accounts, seeds);
-        drift::cpi::update_user_reduce_only(cpi_context, 0, $reduce_only)?;
+        drift::cpi::update_user_reduce_only(cpi_context, $sub_account_id, $reduce_only)?;
     };
 }
 
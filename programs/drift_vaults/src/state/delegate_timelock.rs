@@ -0,0 +1,188 @@
+use crate::error::ErrorCode;
+use crate::events::{DelegateChangeAction, DelegateChangeRecord};
+use crate::state::Vault;
+use crate::{validate, Size};
+use static_assertions::const_assert_eq;
+
+use anchor_lang::prelude::*;
+use drift::math::safe_math::SafeMath;
+use drift_macros::assert_no_slop;
+
+/// Delays [`Vault`] delegate changes by `delay` seconds so depositors can see a queued delegate and withdraw before
+/// it gets trading control. Once it exists, `update_delegate` only queues changes and a permissionless crank applies
+/// them after the delay.
+#[assert_no_slop]
+#[account(zero_copy(unsafe))]
+#[derive(Default, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub struct DelegateTimelock {
+    /// The vault the delegate timelock belongs to
+    pub vault: Pubkey,
+    /// The delegate timelock account's pubkey. It is a pda of vault
+    pub pubkey: Pubkey,
+    /// The queued delegate
+    pub pending_delegate: Pubkey,
+    /// When the queued delegate can be applied. Zero if no change is queued
+    pub activation_ts: i64,
+    /// How long a delegate change is queued for, in seconds. Must be at least the vault redeem_period
+    pub delay: i64,
+    /// The drift sub-account the queued delegate is for. 0 is the vault's primary user
+    pub pending_sub_account_id: u16,
+    /// The bump for the delegate timelock pda
+    pub bump: u8,
+    pub padding1: [u8; 5],
+    pub padding: [u64; 4],
+}
+
+impl Size for DelegateTimelock {
+    const SIZE: usize = 152 + 8;
+}
+
+const_assert_eq!(
+    DelegateTimelock::SIZE,
+    std::mem::size_of::<DelegateTimelock>() + 8
+);
+
+impl DelegateTimelock {
+    pub fn new(vault: Pubkey, pubkey: Pubkey, delay: i64, bump: u8) -> Self {
+        Self {
+            vault,
+            pubkey,
+            pending_delegate: Pubkey::default(),
+            activation_ts: 0,
+            delay,
+            pending_sub_account_id: 0,
+            bump,
+            padding1: [0; 5],
+            padding: [0; 4],
+        }
+    }
+
+    pub fn validate_delay(delay: i64, vault: &Vault) -> Result<()> {
+        // depositors must be able to request and complete a withdraw before the new delegate takes over
+        validate!(
+            delay > 0 && delay >= vault.redeem_period,
+            ErrorCode::InvalidDelegateTimelock,
+            "delay must be at least the redeem period ({} seconds)",
+            vault.redeem_period
+        )?;
+
+        Ok(())
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.activation_ts != 0
+    }
+
+    /// Queues `delegate` for `sub_account_id`. There is one pending slot for the primary user and every
+    /// sub-account, so a queued change has to be applied or cancelled before another can be queued.
+    pub fn queue(
+        &mut self,
+        vault: &Vault,
+        delegate: Pubkey,
+        sub_account_id: u16,
+        now: i64,
+    ) -> Result<()> {
+        validate!(
+            !self.is_pending(),
+            ErrorCode::InvalidDelegateTimelock,
+            "delegate change for sub-account {} already queued",
+            self.pending_sub_account_id
+        )?;
+
+        self.pending_delegate = delegate;
+        self.pending_sub_account_id = sub_account_id;
+        self.activation_ts = now.safe_add(self.delay)?;
+
+        self.emit_record(vault, DelegateChangeAction::Queue, now);
+
+        Ok(())
+    }
+
+    pub fn cancel(&mut self, vault: &Vault, now: i64) -> Result<()> {
+        validate!(
+            self.is_pending(),
+            ErrorCode::InvalidDelegateTimelock,
+            "no delegate change queued"
+        )?;
+
+        self.emit_record(vault, DelegateChangeAction::Cancel, now);
+
+        self.pending_delegate = Pubkey::default();
+        self.pending_sub_account_id = 0;
+        self.activation_ts = 0;
+
+        Ok(())
+    }
+
+    /// Takes the queued delegate and its sub-account id once the delay has passed
+    pub fn apply(&mut self, vault: &Vault, now: i64) -> Result<(Pubkey, u16)> {
+        validate!(
+            self.is_pending(),
+            ErrorCode::InvalidDelegateTimelock,
+            "no delegate change queued"
+        )?;
+        validate!(
+            now >= self.activation_ts,
+            ErrorCode::DelegateTimelocked,
+            "delegate change can't be applied until {}",
+            self.activation_ts
+        )?;
+
+        self.emit_record(vault, DelegateChangeAction::Apply, now);
+
+        let pending = (self.pending_delegate, self.pending_sub_account_id);
+        self.pending_delegate = Pubkey::default();
+        self.pending_sub_account_id = 0;
+        self.activation_ts = 0;
+
+        Ok(pending)
+    }
+
+    fn emit_record(&self, vault: &Vault, action: DelegateChangeAction, now: i64) {
+        emit!(DelegateChangeRecord {
+            ts: now,
+            vault: self.vault,
+            action,
+            delegate_before: vault.delegate,
+            pending_delegate: self.pending_delegate,
+            sub_account_id: self.pending_sub_account_id,
+            activation_ts: self.activation_ts,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DelegateTimelock, Vault};
+    use anchor_lang::prelude::Pubkey;
+
+    #[test]
+    fn test_delegate_timelock() {
+        let now = 1000;
+        let vault = Vault {
+            redeem_period: 100,
+            ..Vault::default()
+        };
+        assert!(DelegateTimelock::validate_delay(99, &vault).is_err());
+        DelegateTimelock::validate_delay(100, &vault).unwrap();
+
+        let mut timelock = DelegateTimelock::new(Pubkey::default(), Pubkey::default(), 100, 0);
+        assert!(timelock.apply(&vault, now).is_err());
+        assert!(timelock.cancel(&vault, now).is_err());
+
+        let delegate = Pubkey::new_unique();
+        timelock.queue(&vault, delegate, 0, now).unwrap();
+        assert_eq!(timelock.activation_ts, 1100);
+        assert!(timelock.apply(&vault, now + 99).is_err());
+        assert_eq!(timelock.apply(&vault, now + 100).unwrap(), (delegate, 0));
+        assert!(!timelock.is_pending());
+
+        timelock.queue(&vault, delegate, 2, now).unwrap();
+        // a sub-account change can't overwrite the pending one, or the reverse
+        assert!(timelock.queue(&vault, delegate, 0, now).is_err());
+        assert_eq!(timelock.pending_sub_account_id, 2);
+        timelock.cancel(&vault, now).unwrap();
+        assert!(timelock.apply(&vault, now + 100).is_err());
+    }
+}
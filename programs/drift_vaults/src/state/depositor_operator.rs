@@ -0,0 +1,114 @@
+use crate::Size;
+use static_assertions::const_assert_eq;
+
+use anchor_lang::prelude::*;
+use drift_macros::assert_no_slop;
+
+/// Lets a [`VaultDepositor`](crate::VaultDepositor)'s authority name an operator, such as a custody bot, that can
+/// request, cancel and finalize withdraws for it. Operator withdraws are only paid to `withdraw_destination`, or to a
+/// token account the authority owns if no destination is pinned.
+#[assert_no_slop]
+#[account(zero_copy(unsafe))]
+#[derive(Default, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub struct DepositorOperator {
+    /// The vault depositor the operator acts for
+    pub vault_depositor: Pubkey,
+    /// The depositor operator account's pubkey. It is a pda of vault_depositor
+    pub pubkey: Pubkey,
+    /// The operator. Pubkey::default() if the depositor has none
+    pub operator: Pubkey,
+    /// The token account operator withdraws are paid to. Pubkey::default() if not pinned
+    pub withdraw_destination: Pubkey,
+    /// The bump for the depositor operator pda
+    pub bump: u8,
+    pub padding1: [u8; 7],
+    pub padding: [u64; 4],
+}
+
+impl Size for DepositorOperator {
+    const SIZE: usize = 168 + 8;
+}
+
+const_assert_eq!(
+    DepositorOperator::SIZE,
+    std::mem::size_of::<DepositorOperator>() + 8
+);
+
+impl DepositorOperator {
+    pub fn new(
+        vault_depositor: Pubkey,
+        pubkey: Pubkey,
+        operator: Pubkey,
+        withdraw_destination: Pubkey,
+        bump: u8,
+    ) -> Self {
+        Self {
+            vault_depositor,
+            pubkey,
+            operator,
+            withdraw_destination,
+            bump,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_operator(&self, signer: &Pubkey) -> bool {
+        self.operator != Pubkey::default() && self.operator == *signer
+    }
+
+    /// Whether an operator withdraw can be paid to `token_account`, owned by `token_account_owner`
+    pub fn is_withdraw_destination(
+        &self,
+        token_account: &Pubkey,
+        token_account_owner: &Pubkey,
+        depositor_authority: &Pubkey,
+    ) -> bool {
+        if self.withdraw_destination != Pubkey::default() {
+            self.withdraw_destination == *token_account
+        } else {
+            token_account_owner == depositor_authority
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::DepositorOperator;
+    use anchor_lang::prelude::Pubkey;
+
+    #[test]
+    fn test_depositor_operator_withdraw_destination() {
+        let authority = Pubkey::new_unique();
+        let operator = Pubkey::new_unique();
+        let owner_account = Pubkey::new_unique();
+        let other_account = Pubkey::new_unique();
+
+        let mut depositor_operator = DepositorOperator::new(
+            Pubkey::default(),
+            Pubkey::default(),
+            operator,
+            Pubkey::default(),
+            0,
+        );
+        assert!(depositor_operator.is_operator(&operator));
+        assert!(!depositor_operator.is_operator(&authority));
+
+        // unpinned: any account the depositor's authority owns
+        assert!(depositor_operator.is_withdraw_destination(&owner_account, &authority, &authority));
+        assert!(!depositor_operator.is_withdraw_destination(&other_account, &operator, &authority));
+
+        // pinned: only the pinned account, even if the authority owns others
+        depositor_operator.withdraw_destination = owner_account;
+        assert!(depositor_operator.is_withdraw_destination(&owner_account, &authority, &authority));
+        assert!(!depositor_operator.is_withdraw_destination(
+            &other_account,
+            &authority,
+            &authority
+        ));
+
+        // revoked
+        depositor_operator.operator = Pubkey::default();
+        assert!(!depositor_operator.is_operator(&Pubkey::default()));
+    }
+}
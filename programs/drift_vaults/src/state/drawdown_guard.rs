@@ -0,0 +1,255 @@
+use crate::constants::BPS_PRECISION;
+use crate::error::ErrorCode;
+use crate::events::DrawdownRecord;
+use crate::state::Vault;
+use crate::{validate, Size};
+use static_assertions::const_assert_eq;
+
+use anchor_lang::prelude::*;
+use drift::math::casting::Cast;
+use drift::math::constants::PRICE_PRECISION;
+use drift::math::safe_math::SafeMath;
+use drift_macros::assert_no_slop;
+
+/// Trips a circuit breaker on a [`Vault`] when its share price falls more than `max_drawdown_bps` below the
+/// high-water share price. A tripped vault is reduce-only on drift and stops taking deposits until the manager resets
+/// the guard, which is only allowed `reset_cooldown` seconds after it tripped.
+#[assert_no_slop]
+#[account(zero_copy(unsafe))]
+#[derive(Default, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub struct DrawdownGuard {
+    /// The vault the drawdown guard belongs to
+    pub vault: Pubkey,
+    /// The drawdown guard account's pubkey. It is a pda of vault
+    pub pubkey: Pubkey,
+    /// The highest vault equity per share seen since the guard was created or last reset
+    /// precision: PRICE_PRECISION
+    pub high_water_price: u128,
+    /// When the guard tripped. Zero if it is not tripped
+    pub tripped_ts: i64,
+    /// How long after tripping the manager must wait to reset the guard, in seconds
+    pub reset_cooldown: i64,
+    /// The vault shares_base high_water_price was recorded at
+    pub high_water_shares_base: u32,
+    /// The drawdown from high_water_price that trips the guard, in bps
+    pub max_drawdown_bps: u16,
+    /// The bump for the drawdown guard pda
+    pub bump: u8,
+    pub padding1: [u8; 1],
+    pub padding: [u64; 5],
+}
+
+impl Size for DrawdownGuard {
+    const SIZE: usize = 144 + 8;
+}
+
+const_assert_eq!(
+    DrawdownGuard::SIZE,
+    std::mem::size_of::<DrawdownGuard>() + 8
+);
+
+impl DrawdownGuard {
+    pub fn new(
+        vault: Pubkey,
+        pubkey: Pubkey,
+        max_drawdown_bps: u16,
+        reset_cooldown: i64,
+        bump: u8,
+    ) -> Self {
+        Self {
+            vault,
+            pubkey,
+            high_water_price: 0,
+            tripped_ts: 0,
+            reset_cooldown,
+            high_water_shares_base: 0,
+            max_drawdown_bps,
+            bump,
+            padding1: [0; 1],
+            padding: [0; 5],
+        }
+    }
+
+    pub fn validate_params(max_drawdown_bps: u16, reset_cooldown: i64) -> Result<()> {
+        validate!(
+            max_drawdown_bps > 0 && max_drawdown_bps.cast::<u64>()? < BPS_PRECISION,
+            ErrorCode::InvalidDrawdownGuard,
+            "max drawdown must be between 1 and {} bps",
+            BPS_PRECISION - 1
+        )?;
+
+        validate!(
+            reset_cooldown > 0,
+            ErrorCode::InvalidDrawdownGuard,
+            "reset cooldown must be positive"
+        )?;
+
+        Ok(())
+    }
+
+    pub fn is_tripped(&self) -> bool {
+        self.tripped_ts != 0
+    }
+
+    /// Vault equity per share, in PRICE_PRECISION
+    pub fn calculate_share_price(vault: &Vault, vault_equity: u64) -> Result<u128> {
+        if vault.total_shares == 0 {
+            return Ok(0);
+        }
+
+        Ok(vault_equity
+            .cast::<u128>()?
+            .safe_mul(PRICE_PRECISION)?
+            .safe_div(vault.total_shares)?)
+    }
+
+    /// Moves the high-water price up or trips the guard if the share price has drawn down too far. Returns true if
+    /// the guard tripped.
+    pub fn update(&mut self, vault: &Vault, vault_equity: u64, now: i64) -> Result<bool> {
+        if self.is_tripped() || vault.total_shares == 0 {
+            return Ok(false);
+        }
+
+        // a rebase divides shares by a power of ten, so the high-water price scales up with it
+        if vault.shares_base != self.high_water_shares_base {
+            let expo_diff = vault.shares_base.safe_sub(self.high_water_shares_base)?;
+            self.high_water_price = self.high_water_price.safe_mul(10_u128.pow(expo_diff))?;
+            self.high_water_shares_base = vault.shares_base;
+        }
+
+        let share_price = Self::calculate_share_price(vault, vault_equity)?;
+
+        if share_price >= self.high_water_price {
+            self.high_water_price = share_price;
+            return Ok(false);
+        }
+
+        let drawdown_bps = self
+            .high_water_price
+            .safe_sub(share_price)?
+            .safe_mul(BPS_PRECISION.cast()?)?
+            .safe_div(self.high_water_price)?;
+
+        if drawdown_bps < self.max_drawdown_bps.cast()? {
+            return Ok(false);
+        }
+
+        self.tripped_ts = now;
+
+        emit!(DrawdownRecord {
+            ts: now,
+            vault: self.vault,
+            vault_equity,
+            high_water_price: self.high_water_price,
+            share_price,
+            drawdown_bps: drawdown_bps.cast()?,
+            max_drawdown_bps: self.max_drawdown_bps,
+        });
+
+        Ok(true)
+    }
+
+    /// Clears a tripped guard once the cooldown has passed. The high-water price restarts from the next crank.
+    pub fn reset(&mut self, now: i64) -> Result<()> {
+        validate!(
+            self.is_tripped(),
+            ErrorCode::InvalidDrawdownGuard,
+            "drawdown guard is not tripped"
+        )?;
+
+        let reset_ts = self.tripped_ts.safe_add(self.reset_cooldown)?;
+        validate!(
+            now >= reset_ts,
+            ErrorCode::DrawdownGuardCooldown,
+            "drawdown guard can't be reset until {}",
+            reset_ts
+        )?;
+
+        self.tripped_ts = 0;
+        self.high_water_price = 0;
+
+        Ok(())
+    }
+}
+
+pub trait DrawdownGuardProvider<'a> {
+    /// The vault's [`DrawdownGuard`], if it is anywhere in the remaining accounts
+    fn drawdown_guard(&self, vault: &Vault) -> Option<AccountLoader<'a, DrawdownGuard>>;
+}
+
+impl<'a: 'info, 'info, T: anchor_lang::Bumps> DrawdownGuardProvider<'a>
+    for Context<'_, '_, 'a, 'info, T>
+{
+    fn drawdown_guard(&self, vault: &Vault) -> Option<AccountLoader<'a, DrawdownGuard>> {
+        self.remaining_accounts
+            .iter()
+            .filter_map(|account_info| {
+                AccountLoader::<'a, DrawdownGuard>::try_from(account_info).ok()
+            })
+            .find(|account| {
+                account
+                    .load()
+                    .map(|account| account.vault == vault.pubkey)
+                    .unwrap_or(false)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{DrawdownGuard, Vault};
+    use anchor_lang::prelude::Pubkey;
+    use drift::math::constants::{PRICE_PRECISION, QUOTE_PRECISION_U64};
+
+    #[test]
+    fn test_drawdown_guard_trips_and_resets() {
+        let mut now = 1000;
+        let mut vault = Vault {
+            total_shares: 100 * QUOTE_PRECISION_U64 as u128,
+            ..Vault::default()
+        };
+        let mut guard = DrawdownGuard::new(Pubkey::default(), Pubkey::default(), 2_000, 100, 0);
+
+        // price rises to 1.5 and sets the high-water mark
+        assert!(!guard
+            .update(&vault, 100 * QUOTE_PRECISION_U64, now)
+            .unwrap());
+        assert_eq!(guard.high_water_price, PRICE_PRECISION);
+        assert!(!guard
+            .update(&vault, 150 * QUOTE_PRECISION_U64, now)
+            .unwrap());
+        assert_eq!(guard.high_water_price, 3 * PRICE_PRECISION / 2);
+
+        // a 10x rebase keeps the high-water mark in step with the share price
+        vault.total_shares /= 10;
+        vault.shares_base = 1;
+        assert!(!guard
+            .update(&vault, 130 * QUOTE_PRECISION_U64, now)
+            .unwrap());
+        assert_eq!(guard.high_water_price, 15 * PRICE_PRECISION);
+        assert_eq!(guard.high_water_shares_base, 1);
+
+        // 20% below the high-water mark trips the guard
+        assert!(guard
+            .update(&vault, 120 * QUOTE_PRECISION_U64, now)
+            .unwrap());
+        assert!(guard.is_tripped());
+        assert!(!guard
+            .update(&vault, 100 * QUOTE_PRECISION_U64, now)
+            .unwrap());
+
+        // the manager can only reset after the cooldown
+        now += 99;
+        assert!(guard.reset(now).is_err());
+        now += 1;
+        guard.reset(now).unwrap();
+        assert!(!guard.is_tripped());
+        assert!(guard.reset(now).is_err());
+
+        assert!(!guard
+            .update(&vault, 100 * QUOTE_PRECISION_U64, now)
+            .unwrap());
+        assert_eq!(guard.high_water_price, 10 * PRICE_PRECISION);
+    }
+}
@@ -33,6 +33,9 @@ pub struct VaultDepositorRecord {
     pub profit_share: u64,
     pub management_fee: i64,
     pub management_fee_shares: i64,
+
+    /// entry fee on deposits, exit fee on withdraws
+    pub entry_exit_fee: u64,
 }
 
 #[event]
@@ -66,6 +69,9 @@ pub struct VaultDepositorV1Record {
     pub manager_profit_share: u64,
     pub management_fee: i64,
     pub management_fee_shares: i64,
+
+    /// entry fee on deposits, exit fee on withdraws
+    pub entry_exit_fee: u64,
 }
 
 #[derive(Clone, Copy, BorshSerialize, BorshDeserialize, PartialEq, Eq, Default)]
@@ -94,3 +100,169 @@ pub struct ShareTransferRecord {
     pub to_depositor_shares_before: u128,
     pub to_depositor_shares_after: u128,
 }
+
+#[event]
+#[derive(Default)]
+pub struct VaultReferrerRecord {
+    pub ts: i64,
+    pub vault: Pubkey,
+    pub vault_referrer: Pubkey,
+    /// the referred depositor for fee payments, the referrer authority otherwise
+    pub depositor_authority: Pubkey,
+    pub action: VaultDepositorAction,
+    pub amount: u64,
+    pub vault_equity_before: u64,
+    pub referrer_shares_before: u128,
+    pub referrer_shares_after: u128,
+    pub total_vault_shares_after: u128,
+    pub profit_share: u64,
+    pub management_fee: u64,
+}
+
+#[event]
+#[derive(Default)]
+pub struct VaultDepositorFeeOverrideRecord {
+    pub ts: i64,
+    pub vault: Pubkey,
+    pub depositor_authority: Pubkey,
+    pub fee_override: bool,
+    pub profit_share_override_before: u32,
+    pub profit_share_override_after: u32,
+    pub management_fee_override_before: u32,
+    pub management_fee_override_after: u32,
+}
+
+#[event]
+#[derive(Default)]
+pub struct ManagementFeeRecord {
+    pub ts: i64,
+    pub vault: Pubkey,
+    pub depositor_equity: u64,
+    /// the vault's base management fee
+    pub management_fee: i64,
+    /// the effective management fee charged after applying fee tiers
+    pub management_fee_rate: i64,
+    pub management_fee_shares: i64,
+}
+
+#[event]
+#[derive(Default)]
+pub struct VaultPauseRecord {
+    pub ts: i64,
+    pub vault: Pubkey,
+    /// the manager, risk officer, protocol or drawdown guard that updated the pause flags
+    pub authority: Pubkey,
+    pub paused_before: u8,
+    pub paused_after: u8,
+    pub withdraw_pause_end_ts: i64,
+}
+
+#[event]
+#[derive(Default)]
+pub struct ManagerMinShareRecord {
+    pub ts: i64,
+    pub vault: Pubkey,
+    pub min_manager_share_bps: u16,
+    pub manager_capital_shares: u128,
+    pub total_shares: u128,
+}
+
+#[event]
+#[derive(Default)]
+pub struct WithdrawGateRecord {
+    pub ts: i64,
+    pub vault: Pubkey,
+    pub depositor_authority: Pubkey,
+    pub epoch_start_ts: i64,
+    pub epoch_capacity: u64,
+    /// the amount withdrawn in the epoch, including this withdraw
+    pub epoch_withdrawn: u64,
+    /// the amount paid out of the request
+    pub amount: u64,
+    /// the shares left pending until the next epoch
+    pub remaining_shares: u128,
+    /// the value left pending until the next epoch
+    pub remaining_value: u64,
+}
+
+#[event]
+#[derive(Default)]
+pub struct DrawdownRecord {
+    pub ts: i64,
+    pub vault: Pubkey,
+    pub vault_equity: u64,
+    /// precision: PRICE_PRECISION
+    pub high_water_price: u128,
+    /// precision: PRICE_PRECISION
+    pub share_price: u128,
+    pub drawdown_bps: u16,
+    pub max_drawdown_bps: u16,
+}
+
+#[event]
+#[derive(Default)]
+pub struct RiskLimitRecord {
+    pub ts: i64,
+    pub vault: Pubkey,
+    pub violation: RiskLimitViolation,
+    pub market_index: u16,
+    /// the offending position notional (QUOTE_PRECISION) or leverage (BPS_PRECISION)
+    pub value: u64,
+    /// true if the delegate was revoked, false if the drift user was made reduce-only
+    pub revoked_delegate: bool,
+}
+
+#[derive(Clone, Copy, BorshSerialize, BorshDeserialize, PartialEq, Eq, Default, Debug)]
+pub enum RiskLimitViolation {
+    #[default]
+    PerpMarket,
+    SpotMarket,
+    PositionNotional,
+    Leverage,
+}
+
+#[event]
+#[derive(Default)]
+pub struct DelegateChangeRecord {
+    pub ts: i64,
+    pub vault: Pubkey,
+    pub action: DelegateChangeAction,
+    pub delegate_before: Pubkey,
+    pub pending_delegate: Pubkey,
+    /// the drift sub-account the pending delegate is for, 0 for the vault's primary user
+    pub sub_account_id: u16,
+    pub activation_ts: i64,
+}
+
+#[derive(Clone, Copy, BorshSerialize, BorshDeserialize, PartialEq, Eq, Default)]
+pub enum DelegateChangeAction {
+    #[default]
+    Queue,
+    Cancel,
+    Apply,
+}
+
+#[event]
+#[derive(Default)]
+pub struct VaultDepositorTransferRecord {
+    pub ts: i64,
+    pub vault: Pubkey,
+    pub vault_depositor_before: Pubkey,
+    pub vault_depositor_after: Pubkey,
+    pub authority_before: Pubkey,
+    pub authority_after: Pubkey,
+    pub vault_shares: u128,
+}
+
+#[event]
+#[derive(Default)]
+pub struct FeeClaimRecord {
+    pub ts: i64,
+    pub vault: Pubkey,
+    pub fee_recipients: Pubkey,
+    pub token_account: Pubkey,
+    pub is_protocol: bool,
+    pub shares: u128,
+    pub amount: u64,
+    pub vault_equity_before: u64,
+}
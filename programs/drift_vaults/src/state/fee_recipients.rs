@@ -0,0 +1,503 @@
+use std::cell::RefMut;
+
+use crate::error::ErrorCode;
+use crate::state::events::FeeClaimRecord;
+use crate::state::vault::Vault;
+use crate::state::withdraw_request::WithdrawRequest;
+use crate::{validate, ManagerPosition, Size, VaultDepositorBase, VaultProtocol};
+use static_assertions::const_assert_eq;
+
+use anchor_lang::prelude::*;
+use bytemuck::Zeroable;
+use drift::math::casting::Cast;
+use drift::math::insurance::if_shares_to_vault_amount as depositor_shares_to_vault_amount;
+use drift::math::safe_math::SafeMath;
+use drift_macros::assert_no_slop;
+
+pub const MAX_FEE_RECIPIENTS: usize = 4;
+pub const FEE_RECIPIENT_WEIGHT_PRECISION: u16 = 10_000;
+
+#[assert_no_slop]
+#[derive(
+    Default, AnchorSerialize, AnchorDeserialize, Copy, Clone, Eq, PartialEq, Debug, Zeroable,
+)]
+pub struct FeeRecipient {
+    /// The token account fees are paid out to
+    pub token_account: Pubkey,
+    /// Fee shares allocated to this recipient that have not been claimed yet
+    pub shares: u128,
+    /// The pending claim. Fee shares are paid out once the vault's redeem_period has passed, like any other withdraw
+    pub last_withdraw_request: WithdrawRequest,
+    /// Share of the fees this recipient receives: FEE_RECIPIENT_WEIGHT_PRECISION
+    pub weight_bps: u16,
+    pub padding: [u8; 14],
+}
+
+/// Where the manager's (or protocol's) fee shares are paid out to. Fee shares are split among the recipients by
+/// weight when claimed, and each recipient can claim its allocation without the manager or protocol signing.
+#[assert_no_slop]
+#[account(zero_copy(unsafe))]
+#[derive(Default, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub struct FeeRecipients {
+    /// The vault the fee recipients belong to
+    pub vault: Pubkey,
+    /// The fee recipients account's pubkey. It is a pda of vault and is_protocol
+    pub pubkey: Pubkey,
+    pub recipients: [FeeRecipient; 4],
+    /// The exponent for recipient shares decimal places
+    pub vault_shares_base: u32,
+    /// Number of entries in recipients that are in use
+    pub num_recipients: u8,
+    /// Whether these recipients receive the protocol's fees instead of the manager's
+    pub is_protocol: bool,
+    /// The bump for the fee recipients pda
+    pub bump: u8,
+    pub padding1: [u8; 1],
+    pub padding: [u64; 4],
+}
+
+impl Size for FeeRecipients {
+    const SIZE: usize = 488 + 8;
+}
+
+const_assert_eq!(
+    FeeRecipients::SIZE,
+    std::mem::size_of::<FeeRecipients>() + 8
+);
+
+impl FeeRecipients {
+    pub fn new(
+        vault: Pubkey,
+        pubkey: Pubkey,
+        is_protocol: bool,
+        vault_shares_base: u32,
+        bump: u8,
+    ) -> Self {
+        Self {
+            vault,
+            pubkey,
+            recipients: [FeeRecipient::default(); MAX_FEE_RECIPIENTS],
+            vault_shares_base,
+            num_recipients: 0,
+            is_protocol,
+            bump,
+            padding1: [0; 1],
+            padding: [0; 4],
+        }
+    }
+
+    /// The manager signs for manager fee recipients and the protocol signs for protocol fee recipients.
+    pub fn validate_authority(
+        is_protocol: bool,
+        vault: &Vault,
+        vault_protocol: &Option<RefMut<VaultProtocol>>,
+        authority: &Pubkey,
+    ) -> Result<()> {
+        if is_protocol {
+            match vault_protocol {
+                Some(vp) => validate!(
+                    vp.protocol.eq(authority),
+                    ErrorCode::InvalidFeeRecipients,
+                    "{} is not the vault protocol",
+                    authority
+                )?,
+                None => return Err(ErrorCode::VaultProtocolMissing.into()),
+            }
+        } else {
+            validate!(
+                vault.manager.eq(authority),
+                ErrorCode::InvalidFeeRecipients,
+                "{} is not the vault manager",
+                authority
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn allocated_shares(&self) -> Result<u128> {
+        let mut allocated_shares: u128 = 0;
+        for recipient in self.recipients.iter() {
+            allocated_shares = allocated_shares.safe_add(recipient.shares)?;
+        }
+        Ok(allocated_shares)
+    }
+
+    /// Replaces the recipients. Only allowed once every allocation has been claimed.
+    pub fn set_recipients(&mut self, recipients: &[(Pubkey, u16)]) -> Result<()> {
+        validate!(
+            !recipients.is_empty() && recipients.len() <= MAX_FEE_RECIPIENTS,
+            ErrorCode::InvalidFeeRecipients,
+            "must have between 1 and {} fee recipients",
+            MAX_FEE_RECIPIENTS
+        )?;
+
+        validate!(
+            self.allocated_shares()? == 0,
+            ErrorCode::InvalidFeeRecipients,
+            "all fee recipients must claim before recipients can be updated"
+        )?;
+
+        let mut total_weight: u16 = 0;
+        for (token_account, weight_bps) in recipients.iter() {
+            validate!(
+                *weight_bps > 0,
+                ErrorCode::InvalidFeeRecipients,
+                "fee recipient {} has zero weight",
+                token_account
+            )?;
+            total_weight = total_weight.safe_add(*weight_bps)?;
+        }
+
+        validate!(
+            total_weight == FEE_RECIPIENT_WEIGHT_PRECISION,
+            ErrorCode::InvalidFeeRecipients,
+            "fee recipient weights sum to {} != {}",
+            total_weight,
+            FEE_RECIPIENT_WEIGHT_PRECISION
+        )?;
+
+        self.recipients = [FeeRecipient::default(); MAX_FEE_RECIPIENTS];
+        for (i, (token_account, weight_bps)) in recipients.iter().enumerate() {
+            self.recipients[i].token_account = *token_account;
+            self.recipients[i].weight_bps = *weight_bps;
+        }
+        self.num_recipients = recipients.len().cast()?;
+
+        Ok(())
+    }
+
+    pub fn apply_rebase(&mut self, vault: &Vault) -> Result<()> {
+        if vault.shares_base != self.vault_shares_base {
+            validate!(
+                vault.shares_base > self.vault_shares_base,
+                ErrorCode::InvalidVaultRebase,
+                "Rebase expo out of bounds"
+            )?;
+
+            let expo_diff = (vault.shares_base - self.vault_shares_base).cast::<u32>()?;
+            let rebase_divisor = 10_u128.pow(expo_diff);
+
+            for recipient in self.recipients.iter_mut() {
+                recipient.shares = recipient.shares.safe_div(rebase_divisor)?;
+                recipient.last_withdraw_request.rebase(rebase_divisor)?;
+            }
+
+            self.vault_shares_base = vault.shares_base;
+        }
+
+        Ok(())
+    }
+
+    /// Splits fee shares not yet allocated to a recipient by weight. Rounding dust stays unallocated until
+    /// the next distribution.
+    pub fn distribute(&mut self, fee_shares: u128) -> Result<u128> {
+        let unallocated_shares = fee_shares.saturating_sub(self.allocated_shares()?);
+
+        let mut distributed_shares: u128 = 0;
+        for recipient in self.recipients[..self.num_recipients as usize].iter_mut() {
+            let recipient_shares = unallocated_shares
+                .safe_mul(recipient.weight_bps.cast()?)?
+                .safe_div(FEE_RECIPIENT_WEIGHT_PRECISION.cast()?)?;
+            recipient.shares = recipient.shares.safe_add(recipient_shares)?;
+            distributed_shares = distributed_shares.safe_add(recipient_shares)?;
+        }
+
+        Ok(distributed_shares)
+    }
+
+    /// Settles the manager's (or protocol's) fees and splits any new fee shares among the recipients.
+    fn settle(
+        &mut self,
+        vault_equity: u64,
+        vault: &mut Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+        manager_position: &mut Option<RefMut<ManagerPosition>>,
+        now: i64,
+    ) -> Result<()> {
+        let fee_shares = if self.is_protocol {
+            vault.apply_rebase(vault_protocol, vault_equity)?;
+            vault.apply_fee(vault_protocol, vault_equity, now)?;
+            self.apply_rebase(vault)?;
+
+            match vault_protocol {
+                Some(vp) => {
+                    validate!(
+                        vp.fee_recipients,
+                        ErrorCode::InvalidFeeRecipients,
+                        "VaultProtocol does not pay out to fee recipients"
+                    )?;
+                    vp.protocol_profit_and_fee_shares
+                }
+                None => return Err(ErrorCode::VaultProtocolMissing.into()),
+            }
+        } else {
+            match manager_position {
+                Some(mp) => {
+                    validate!(
+                        mp.fee_recipients,
+                        ErrorCode::InvalidFeeRecipients,
+                        "ManagerPosition does not pay out to fee recipients"
+                    )?;
+                    mp.settle(vault, vault_protocol, vault_equity, now)?;
+                    self.apply_rebase(vault)?;
+                    mp.fee_shares
+                }
+                None => return Err(ErrorCode::ManagerPositionMissing.into()),
+            }
+        };
+
+        self.distribute(fee_shares)?;
+
+        Ok(())
+    }
+
+    fn validate_recipient_index(&self, recipient_index: usize) -> Result<()> {
+        validate!(
+            recipient_index < self.num_recipients as usize,
+            ErrorCode::InvalidFeeRecipients,
+            "fee recipient index {} out of bounds",
+            recipient_index
+        )?;
+
+        Ok(())
+    }
+
+    /// Requests a claim of everything allocated to the recipient at `recipient_index`. It can be claimed once the
+    /// vault's redeem_period has passed.
+    pub fn request_claim(
+        &mut self,
+        recipient_index: usize,
+        vault_equity: u64,
+        vault: &mut Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+        manager_position: &mut Option<RefMut<ManagerPosition>>,
+        now: i64,
+    ) -> Result<()> {
+        self.validate_recipient_index(recipient_index)?;
+
+        self.settle(vault_equity, vault, vault_protocol, manager_position, now)?;
+
+        let recipient = &mut self.recipients[recipient_index];
+        let n_shares = recipient.shares;
+
+        validate!(
+            n_shares > 0,
+            ErrorCode::InvalidVaultWithdrawSize,
+            "No fee shares to claim for {}",
+            recipient.token_account
+        )?;
+
+        let value: u64 =
+            depositor_shares_to_vault_amount(n_shares, vault.total_shares, vault_equity)?;
+
+        recipient
+            .last_withdraw_request
+            .set(n_shares, n_shares, value, vault_equity, now)?;
+        vault.total_withdraw_requested = vault.total_withdraw_requested.safe_add(value)?;
+
+        Ok(())
+    }
+
+    /// Pays out the pending claim of the recipient at `recipient_index`. Returns the token amount to withdraw from
+    /// drift and transfer to the recipient.
+    pub fn claim(
+        &mut self,
+        recipient_index: usize,
+        vault_equity: u64,
+        vault: &mut Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+        manager_position: &mut Option<RefMut<ManagerPosition>>,
+        now: i64,
+    ) -> Result<u64> {
+        self.validate_recipient_index(recipient_index)?;
+
+        self.recipients[recipient_index]
+            .last_withdraw_request
+            .check_redeem_period_finished(vault, now)?;
+
+        self.settle(vault_equity, vault, vault_protocol, manager_position, now)?;
+
+        let recipient = &mut self.recipients[recipient_index];
+        let n_shares = recipient.last_withdraw_request.shares;
+
+        validate!(
+            n_shares > 0,
+            ErrorCode::InvalidVaultWithdraw,
+            "Must request a claim for {} and wait the redeem_period ({} seconds)",
+            recipient.token_account,
+            vault.redeem_period
+        )?;
+
+        let amount: u64 =
+            depositor_shares_to_vault_amount(n_shares, vault.total_shares, vault_equity)?;
+        let n_tokens = amount.min(recipient.last_withdraw_request.value);
+
+        recipient.shares = recipient.shares.safe_sub(n_shares)?;
+
+        vault.total_shares = vault.total_shares.safe_sub(n_shares)?;
+        vault.total_withdraws = vault.total_withdraws.saturating_add(n_tokens);
+        vault.net_deposits = vault.net_deposits.safe_sub(n_tokens.cast()?)?;
+        vault.total_withdraw_requested = vault
+            .total_withdraw_requested
+            .safe_sub(recipient.last_withdraw_request.value)?;
+
+        if self.is_protocol {
+            if let Some(vp) = vault_protocol {
+                vp.protocol_profit_and_fee_shares =
+                    vp.protocol_profit_and_fee_shares.safe_sub(n_shares)?;
+                vp.protocol_total_withdraws = vp.protocol_total_withdraws.saturating_add(n_tokens);
+            }
+        } else if let Some(mp) = manager_position {
+            mp.set_vault_shares(mp.get_vault_shares().safe_sub(n_shares)?);
+            mp.fee_shares = mp.fee_shares.safe_sub(n_shares)?;
+            mp.total_withdraws = mp.total_withdraws.saturating_add(n_tokens);
+            mp.net_deposits = mp.net_deposits.safe_sub(n_tokens.cast()?)?;
+            vault.manager_total_withdraws = vault.manager_total_withdraws.saturating_add(n_tokens);
+            vault.manager_net_deposits = vault.manager_net_deposits.safe_sub(n_tokens.cast()?)?;
+        }
+
+        recipient.last_withdraw_request.reset(now)?;
+
+        emit!(FeeClaimRecord {
+            ts: now,
+            vault: vault.pubkey,
+            fee_recipients: self.pubkey,
+            token_account: recipient.token_account,
+            is_protocol: self.is_protocol,
+            shares: n_shares,
+            amount: n_tokens,
+            vault_equity_before: vault_equity,
+        });
+
+        Ok(n_tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use crate::{FeeRecipients, ManagerPosition, Vault, VaultDepositorBase, WithdrawUnit};
+    use anchor_lang::prelude::Pubkey;
+
+    #[test]
+    fn test_claim_manager_fees() {
+        let now = 1337;
+        let vault = &mut Vault::default();
+        vault.total_shares = 2_000_000;
+        vault.user_shares = 1_000_000;
+        vault.manager_net_deposits = 900_000;
+        vault.manager_total_deposits = 900_000;
+        let vault_equity = 2_000_000;
+
+        let mp = RefCell::new(ManagerPosition::new(
+            Pubkey::default(),
+            Pubkey::default(),
+            Pubkey::default(),
+            0,
+            now,
+        ));
+        let mut mp = Some(mp.borrow_mut());
+        {
+            let mp = mp.as_mut().unwrap();
+            mp.initialize(vault, &mut None, vault_equity, now).unwrap();
+            mp.fee_recipients = true;
+            assert_eq!(mp.fee_shares, 100_000);
+            assert_eq!(mp.capital_shares().unwrap(), 900_000);
+        }
+
+        let treasury = Pubkey::new_unique();
+        let ops = Pubkey::new_unique();
+        let mut fee_recipients =
+            FeeRecipients::new(Pubkey::default(), Pubkey::default(), false, 0, 0);
+
+        // weights must sum to 100%
+        assert!(fee_recipients
+            .set_recipients(&[(treasury, 7_000), (ops, 2_000)])
+            .is_err());
+        fee_recipients
+            .set_recipients(&[(treasury, 7_000), (ops, 3_000)])
+            .unwrap();
+
+        // claims wait out the redeem period like every other withdraw
+        vault.redeem_period = 100;
+        assert!(fee_recipients
+            .claim(0, vault_equity, vault, &mut None, &mut mp, now)
+            .is_err());
+        fee_recipients
+            .request_claim(0, vault_equity, vault, &mut None, &mut mp, now)
+            .unwrap();
+        assert_eq!(
+            fee_recipients.recipients[0].last_withdraw_request.shares,
+            70_000
+        );
+        assert_eq!(vault.total_withdraw_requested, 70_000);
+        assert!(fee_recipients
+            .claim(0, vault_equity, vault, &mut None, &mut mp, now + 99)
+            .is_err());
+
+        let now = now + 100;
+        let claim_amount = fee_recipients
+            .claim(0, vault_equity, vault, &mut None, &mut mp, now)
+            .unwrap();
+        assert_eq!(claim_amount, 70_000);
+        assert_eq!(fee_recipients.recipients[0].shares, 0);
+        assert!(!fee_recipients.recipients[0].last_withdraw_request.pending());
+        assert_eq!(fee_recipients.recipients[1].shares, 30_000);
+        assert_eq!(vault.total_shares, 1_930_000);
+        assert_eq!(vault.total_withdraw_requested, 0);
+        assert_eq!(vault.manager_total_withdraws, 70_000);
+
+        // recipients can't be replaced while shares are still allocated
+        assert!(fee_recipients
+            .set_recipients(&[(treasury, 10_000)])
+            .is_err());
+
+        {
+            let mp = mp.as_mut().unwrap();
+            assert_eq!(mp.get_vault_shares(), 930_000);
+            assert_eq!(mp.fee_shares, 30_000);
+            assert_eq!(mp.capital_shares().unwrap(), 900_000);
+            assert_eq!(
+                vault.get_manager_shares(&mut None).unwrap(),
+                mp.get_vault_shares()
+            );
+        }
+
+        // nothing left to claim for the treasury until more fees accrue
+        let vault_equity = vault_equity - claim_amount;
+        assert!(fee_recipients
+            .request_claim(0, vault_equity, vault, &mut None, &mut mp, now)
+            .is_err());
+
+        // the claim pays the value at request time if the vault has gained since
+        fee_recipients
+            .request_claim(1, vault_equity, vault, &mut None, &mut mp, now)
+            .unwrap();
+        let now = now + 100;
+        let claim_amount = fee_recipients
+            .claim(1, vault_equity + 19_300, vault, &mut None, &mut mp, now)
+            .unwrap();
+        assert_eq!(claim_amount, 30_000);
+        assert_eq!(fee_recipients.allocated_shares().unwrap(), 0);
+        assert_eq!(mp.as_ref().unwrap().fee_shares, 0);
+        assert_eq!(vault.total_shares, 1_900_000);
+
+        // emulate depositors paying profit share, the manager can't withdraw shares owed to the fee recipients
+        vault.user_shares -= 10_000;
+        assert!(mp
+            .as_mut()
+            .unwrap()
+            .request_withdraw(
+                900_001,
+                WithdrawUnit::Shares,
+                1_900_000,
+                vault,
+                &mut None,
+                now,
+            )
+            .is_err());
+    }
+}
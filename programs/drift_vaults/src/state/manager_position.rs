@@ -0,0 +1,590 @@
+use std::cell::RefMut;
+
+use crate::constants::BPS_PRECISION;
+use crate::error::{ErrorCode, VaultResult};
+use crate::events::ManagerMinShareRecord;
+use crate::state::vault::{Vault, VaultCompanion};
+use crate::state::withdraw_request::WithdrawRequest;
+use crate::{validate, Size, VaultDepositorBase};
+use crate::{VaultProtocol, WithdrawUnit};
+use static_assertions::const_assert_eq;
+
+use anchor_lang::prelude::*;
+use drift::math::casting::Cast;
+use drift::math::insurance::vault_amount_to_if_shares as vault_amount_to_depositor_shares;
+use drift::math::safe_math::SafeMath;
+use drift_macros::assert_no_slop;
+
+/// Explicit accounting for the manager's stake in a [`Vault`]. The vault still tracks manager shares implicitly as
+/// `total_shares - user_shares - protocol_profit_and_fee_shares`; this account mirrors them and splits them into
+/// capital the manager deposited and shares accrued from management fees and profit share.
+#[assert_no_slop]
+#[account(zero_copy(unsafe))]
+#[derive(Default, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub struct ManagerPosition {
+    /// The vault the manager position belongs to
+    pub vault: Pubkey,
+    /// The manager position account's pubkey. It is a pda of vault
+    pub pubkey: Pubkey,
+    /// The vault manager at the time the position was initialized
+    pub authority: Pubkey,
+    /// All manager shares as of the last sync with the vault, capital and fee shares combined
+    vault_shares: u128,
+    /// The portion of vault_shares accrued from management fees and profit share
+    pub fee_shares: u128,
+    /// The manager's pending withdraw request, mirrors vault.last_manager_withdraw_request
+    pub last_withdraw_request: WithdrawRequest,
+    /// creation ts of the manager position
+    pub last_valid_ts: i64,
+    /// lifetime net deposits of the manager for the vault
+    pub net_deposits: i64,
+    /// lifetime total deposits
+    pub total_deposits: u64,
+    /// lifetime total withdraws
+    pub total_withdraws: u64,
+    /// unused, the manager does not pay profit share on its own stake
+    pub cumulative_profit_share_amount: i64,
+    /// unused, the manager does not pay profit share on its own stake
+    pub profit_share_fee_paid: u64,
+    /// The exponent for vault_shares decimal places
+    pub vault_shares_base: u32,
+    /// The bump for the manager position pda
+    pub bump: u8,
+    /// Whether fee shares are paid out through [`crate::FeeRecipients`] instead of withdrawn by the manager
+    pub fee_recipients: bool,
+    pub padding1: [u8; 2],
+    pub padding: [u64; 8],
+}
+
+impl Size for ManagerPosition {
+    const SIZE: usize = 280 + 8;
+}
+
+const_assert_eq!(
+    ManagerPosition::SIZE,
+    std::mem::size_of::<ManagerPosition>() + 8
+);
+
+impl VaultDepositorBase for ManagerPosition {
+    fn get_authority(&self) -> Pubkey {
+        self.authority
+    }
+    fn get_pubkey(&self) -> Pubkey {
+        self.pubkey
+    }
+
+    fn get_vault_shares(&self) -> u128 {
+        self.vault_shares
+    }
+    fn set_vault_shares(&mut self, shares: u128) {
+        self.vault_shares = shares;
+    }
+
+    fn get_vault_shares_base(&self) -> u32 {
+        self.vault_shares_base
+    }
+    fn set_vault_shares_base(&mut self, base: u32) {
+        self.vault_shares_base = base;
+    }
+
+    fn get_net_deposits(&self) -> i64 {
+        self.net_deposits
+    }
+    fn set_net_deposits(&mut self, amount: i64) {
+        self.net_deposits = amount;
+    }
+
+    fn get_cumulative_profit_share_amount(&self) -> i64 {
+        self.cumulative_profit_share_amount
+    }
+    fn set_cumulative_profit_share_amount(&mut self, amount: i64) {
+        self.cumulative_profit_share_amount = amount;
+    }
+
+    fn get_profit_share_fee_paid(&self) -> u64 {
+        self.profit_share_fee_paid
+    }
+    fn set_profit_share_fee_paid(&mut self, amount: u64) {
+        self.profit_share_fee_paid = amount;
+    }
+
+    /// Manager capital never pays exit fees, so its shares are treated as held since the vault's inception.
+    fn get_deposit_ts(&self) -> i64 {
+        0
+    }
+    fn set_deposit_ts(&mut self, _ts: i64) {}
+
+    /// Manager shares are not part of vault.user_shares and never pay profit share.
+    fn calculate_profit_share_and_update(
+        &mut self,
+        _total_amount: u64,
+        _vault: &Vault,
+        _vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+    ) -> Result<(u128, u128)> {
+        Ok((0, 0))
+    }
+}
+
+impl ManagerPosition {
+    pub fn new(vault: Pubkey, pubkey: Pubkey, authority: Pubkey, bump: u8, now: i64) -> Self {
+        Self {
+            vault,
+            pubkey,
+            authority,
+            vault_shares: 0,
+            fee_shares: 0,
+            last_withdraw_request: WithdrawRequest::default(),
+            last_valid_ts: now,
+            net_deposits: 0,
+            total_deposits: 0,
+            total_withdraws: 0,
+            cumulative_profit_share_amount: 0,
+            profit_share_fee_paid: 0,
+            vault_shares_base: 0,
+            bump,
+            fee_recipients: false,
+            padding1: [0; 2],
+            padding: [0; 8],
+        }
+    }
+
+    /// Shares the manager deposited, as opposed to shares accrued from fees.
+    pub fn capital_shares(&self) -> VaultResult<u128> {
+        self.vault_shares.safe_sub(self.fee_shares)
+    }
+
+    /// Seeds the position from the vault's implicit manager accounting. Manager shares in excess of what
+    /// `manager_net_deposits` buys at the current share price are attributed to fees.
+    pub fn initialize(
+        &mut self,
+        vault: &mut Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+        vault_equity: u64,
+        now: i64,
+    ) -> Result<()> {
+        vault.apply_rebase(vault_protocol, vault_equity)?;
+        vault.apply_fee(vault_protocol, vault_equity, now)?;
+
+        let manager_shares = vault.get_manager_shares(vault_protocol)?;
+        let capital_shares = if vault.manager_net_deposits > 0 {
+            vault_amount_to_depositor_shares(
+                vault.manager_net_deposits.cast()?,
+                vault.total_shares,
+                vault_equity,
+            )?
+            .min(manager_shares)
+        } else {
+            0
+        };
+
+        self.vault_shares_base = vault.shares_base;
+        self.vault_shares = manager_shares;
+        self.fee_shares = manager_shares.safe_sub(capital_shares)?;
+        self.net_deposits = vault.manager_net_deposits;
+        self.total_deposits = vault.manager_total_deposits;
+        self.total_withdraws = vault.manager_total_withdraws;
+        self.last_withdraw_request = vault.last_manager_withdraw_request;
+
+        vault.add_companion(VaultCompanion::ManagerPosition);
+
+        Ok(())
+    }
+
+    pub fn apply_rebase(
+        &mut self,
+        vault: &mut Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+        vault_equity: u64,
+    ) -> Result<Option<u128>> {
+        let rebase_divisor =
+            VaultDepositorBase::apply_rebase(self, vault, vault_protocol, vault_equity)?;
+
+        if let Some(rebase_divisor) = rebase_divisor {
+            self.fee_shares = self.fee_shares.safe_div(rebase_divisor)?;
+            self.last_withdraw_request.rebase(rebase_divisor)?;
+        }
+
+        Ok(rebase_divisor)
+    }
+
+    /// Catches the position up with manager shares accrued since the last sync. Any growth is fees, since
+    /// deposits and withdrawals through the position update vault_shares directly. Expects the vault to be
+    /// rebased and fees applied.
+    pub fn sync(
+        &mut self,
+        vault: &Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+    ) -> Result<u128> {
+        self.validate_base(vault)?;
+
+        let manager_shares = vault.get_manager_shares(vault_protocol)?;
+
+        let fee_shares_accrued = manager_shares.saturating_sub(self.vault_shares);
+        self.fee_shares = self
+            .fee_shares
+            .safe_add(fee_shares_accrued)?
+            .min(manager_shares);
+        self.vault_shares = manager_shares;
+
+        Ok(fee_shares_accrued)
+    }
+
+    /// Removes `n_shares` from the position, taking capital and fee shares pro rata. If fee shares are paid
+    /// out through fee recipients only capital shares are removed.
+    fn remove_shares(&mut self, n_shares: u128, vault_shares_before: u128) -> Result<()> {
+        if vault_shares_before == 0 {
+            return Ok(());
+        }
+
+        if self.fee_recipients {
+            validate!(
+                self.vault_shares >= self.fee_shares,
+                ErrorCode::InvalidVaultWithdrawSize,
+                "Manager shares ({}) fell below shares owed to fee recipients ({})",
+                self.vault_shares,
+                self.fee_shares
+            )?;
+            return Ok(());
+        }
+
+        let fee_shares_removed = n_shares
+            .safe_mul(self.fee_shares)?
+            .safe_div(vault_shares_before)?;
+        self.fee_shares = self
+            .fee_shares
+            .safe_sub(fee_shares_removed)?
+            .min(self.vault_shares);
+
+        Ok(())
+    }
+
+    /// Rebases, applies vault fees and syncs fee shares accrued since the last sync.
+    pub fn settle(
+        &mut self,
+        vault: &mut Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+        vault_equity: u64,
+        now: i64,
+    ) -> Result<()> {
+        self.apply_rebase(vault, vault_protocol, vault_equity)?;
+        // fees applied here are not charged again by the vault within the same timestamp
+        vault.apply_fee(vault_protocol, vault_equity, now)?;
+        self.sync(vault, vault_protocol)?;
+        Ok(())
+    }
+
+    pub fn deposit(
+        &mut self,
+        amount: u64,
+        vault_equity: u64,
+        vault: &mut Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+        now: i64,
+    ) -> Result<()> {
+        self.settle(vault, vault_protocol, vault_equity, now)?;
+
+        vault.manager_deposit(vault_protocol, amount, vault_equity, now)?;
+
+        self.vault_shares = vault.get_manager_shares(vault_protocol)?;
+        self.total_deposits = self.total_deposits.saturating_add(amount);
+        self.net_deposits = self.net_deposits.safe_add(amount.cast()?)?;
+
+        Ok(())
+    }
+
+    pub fn request_withdraw(
+        &mut self,
+        withdraw_amount: u64,
+        withdraw_unit: WithdrawUnit,
+        vault_equity: u64,
+        vault: &mut Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+        now: i64,
+    ) -> Result<()> {
+        self.settle(vault, vault_protocol, vault_equity, now)?;
+
+        vault.manager_request_withdraw(
+            vault_protocol,
+            withdraw_amount,
+            withdraw_unit,
+            vault_equity,
+            now,
+        )?;
+
+        self.last_withdraw_request = vault.last_manager_withdraw_request;
+
+        self.validate_capital_shares(self.last_withdraw_request.shares)?;
+        self.validate_min_manager_share(vault, self.last_withdraw_request.shares)?;
+
+        Ok(())
+    }
+
+    /// Checks the manager's capital stays at least vault.min_manager_share_bps of total vault shares once `n_shares`
+    /// of the manager's shares leave the vault. There is no minimum once the vault is shut down or has no
+    /// depositors besides the dead shares.
+    pub fn validate_min_manager_share(&self, vault: &Vault, n_shares: u128) -> Result<()> {
+        if vault.min_manager_share_bps == 0
+            || vault.shutdown
+            || vault.user_shares <= vault.dead_shares()
+        {
+            return Ok(());
+        }
+
+        // fee shares leave alongside capital unless they are paid out through fee recipients
+        let capital_shares_removed = if self.fee_recipients || self.vault_shares == 0 {
+            n_shares
+        } else {
+            n_shares
+                .safe_mul(self.capital_shares()?)?
+                .safe_div(self.vault_shares)?
+        };
+        let capital_shares = self
+            .capital_shares()?
+            .saturating_sub(capital_shares_removed);
+        let total_shares = vault.total_shares.safe_sub(n_shares)?;
+
+        validate!(
+            capital_shares.safe_mul(BPS_PRECISION.cast()?)?
+                >= total_shares.safe_mul(vault.min_manager_share_bps.cast()?)?,
+            ErrorCode::ManagerBelowMinShare,
+            "manager capital shares ({}) would be below {} bps of total shares ({})",
+            capital_shares,
+            vault.min_manager_share_bps,
+            total_shares
+        )?;
+
+        Ok(())
+    }
+
+    pub fn emit_min_manager_share_record(&self, vault: &Vault, now: i64) -> Result<()> {
+        emit!(ManagerMinShareRecord {
+            ts: now,
+            vault: self.vault,
+            min_manager_share_bps: vault.min_manager_share_bps,
+            manager_capital_shares: self.capital_shares()?,
+            total_shares: vault.total_shares,
+        });
+
+        Ok(())
+    }
+
+    /// Fee shares belong to the fee recipients, so the manager can only move capital shares.
+    pub fn validate_capital_shares(&self, n_shares: u128) -> Result<()> {
+        if self.fee_recipients {
+            validate!(
+                n_shares <= self.capital_shares()?,
+                ErrorCode::InvalidVaultWithdrawSize,
+                "Manager can only move capital shares ({}) when fees are paid to fee recipients, requested {}",
+                self.capital_shares()?,
+                n_shares
+            )?;
+        }
+
+        Ok(())
+    }
+
+    pub fn cancel_withdraw_request(
+        &mut self,
+        vault_equity: u64,
+        vault: &mut Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+        now: i64,
+    ) -> Result<()> {
+        self.settle(vault, vault_protocol, vault_equity, now)?;
+
+        vault.manager_cancel_withdraw_request(vault_protocol, vault_equity, now)?;
+
+        self.vault_shares = vault.get_manager_shares(vault_protocol)?;
+        self.fee_shares = self.fee_shares.min(self.vault_shares);
+        self.last_withdraw_request.reset(now)?;
+
+        Ok(())
+    }
+
+    pub fn withdraw(
+        &mut self,
+        vault_equity: u64,
+        vault: &mut Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+        now: i64,
+    ) -> Result<u64> {
+        self.settle(vault, vault_protocol, vault_equity, now)?;
+
+        let vault_shares_before = self.vault_shares;
+        let n_shares = vault.last_manager_withdraw_request.shares;
+
+        self.validate_min_manager_share(vault, n_shares)?;
+
+        let withdraw_amount = vault.manager_withdraw(vault_protocol, vault_equity, now)?;
+
+        self.vault_shares = vault.get_manager_shares(vault_protocol)?;
+        self.remove_shares(n_shares, vault_shares_before)?;
+        self.total_withdraws = self.total_withdraws.saturating_add(withdraw_amount);
+        self.net_deposits = self.net_deposits.safe_sub(withdraw_amount.cast()?)?;
+        self.last_withdraw_request.reset(now)?;
+
+        if vault.min_manager_share_bps > 0 {
+            self.emit_min_manager_share_record(vault, now)?;
+        }
+
+        Ok(withdraw_amount)
+    }
+
+    /// Moves `n_shares` out of the manager's implicit shares and into vault.user_shares after they were
+    /// transferred to a depositor (e.g. a [`crate::TokenizedVaultDepositor`]) via `transfer_shares`.
+    pub fn on_shares_transferred(
+        &mut self,
+        n_shares: u128,
+        vault_shares_before: u128,
+        net_deposits_before: i64,
+        vault: &mut Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+    ) -> Result<()> {
+        vault.user_shares = vault.user_shares.safe_add(n_shares)?;
+
+        let net_deposits_moved = net_deposits_before.safe_sub(self.net_deposits)?;
+        vault.manager_net_deposits = vault.manager_net_deposits.safe_sub(net_deposits_moved)?;
+
+        self.remove_shares(n_shares, vault_shares_before)?;
+
+        // picks up profit share paid by the receiving depositor during the transfer
+        self.sync(vault, vault_protocol)?;
+
+        Ok(())
+    }
+}
+
+pub trait ManagerPositionProvider<'a> {
+    /// The vault's [`ManagerPosition`], if it is anywhere in the remaining accounts
+    fn manager_position(&self, vault: &Vault) -> Option<AccountLoader<'a, ManagerPosition>>;
+}
+
+impl<'a: 'info, 'info, T: anchor_lang::Bumps> ManagerPositionProvider<'a>
+    for Context<'_, '_, 'a, 'info, T>
+{
+    fn manager_position(&self, vault: &Vault) -> Option<AccountLoader<'a, ManagerPosition>> {
+        self.remaining_accounts
+            .iter()
+            .filter_map(|account_info| {
+                AccountLoader::<'a, ManagerPosition>::try_from(account_info).ok()
+            })
+            .find(|mp| {
+                mp.load()
+                    .map(|mp| mp.vault == vault.pubkey)
+                    .unwrap_or(false)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ManagerPosition, Vault, VaultCompanion, VaultDepositorBase, WithdrawUnit};
+    use anchor_lang::prelude::Pubkey;
+
+    #[test]
+    fn test_manager_position_fee_and_capital_shares() {
+        let now = 1337;
+        let vault = &mut Vault::default();
+        vault.total_shares = 2_000_000;
+        vault.user_shares = 1_000_000;
+        vault.manager_net_deposits = 1_000_000;
+        vault.manager_total_deposits = 1_000_000;
+        let vault_equity = 2_000_000;
+
+        let mut mp = ManagerPosition::new(
+            Pubkey::default(),
+            Pubkey::default(),
+            Pubkey::default(),
+            0,
+            now,
+        );
+        mp.initialize(vault, &mut None, vault_equity, now).unwrap();
+        assert!(vault.has_companion(VaultCompanion::ManagerPosition));
+        assert_eq!(mp.get_vault_shares(), 1_000_000);
+        assert_eq!(mp.fee_shares, 0);
+        assert_eq!(mp.capital_shares().unwrap(), 1_000_000);
+
+        // emulate depositors paying profit share to the manager
+        vault.user_shares -= 100_000;
+        let fee_shares_accrued = mp.sync(vault, &mut None).unwrap();
+        assert_eq!(fee_shares_accrued, 100_000);
+        assert_eq!(mp.get_vault_shares(), 1_100_000);
+        assert_eq!(mp.fee_shares, 100_000);
+        assert_eq!(mp.capital_shares().unwrap(), 1_000_000);
+
+        // withdraw half of the manager shares, capital and fee shares leave pro rata
+        mp.request_withdraw(
+            550_000,
+            WithdrawUnit::Shares,
+            vault_equity,
+            vault,
+            &mut None,
+            now,
+        )
+        .unwrap();
+        assert_eq!(
+            mp.last_withdraw_request,
+            vault.last_manager_withdraw_request
+        );
+
+        let withdraw_amount = mp.withdraw(vault_equity, vault, &mut None, now).unwrap();
+        assert_eq!(withdraw_amount, 550_000);
+        assert_eq!(mp.get_vault_shares(), 550_000);
+        assert_eq!(mp.fee_shares, 50_000);
+        assert_eq!(mp.capital_shares().unwrap(), 500_000);
+        assert_eq!(mp.net_deposits, 450_000);
+        assert!(!mp.last_withdraw_request.pending());
+        assert_eq!(
+            vault.get_manager_shares(&mut None).unwrap(),
+            mp.get_vault_shares()
+        );
+    }
+
+    #[test]
+    fn test_min_manager_share() {
+        let now = 1337;
+        let vault = &mut Vault::default();
+        vault.total_shares = 2_000_000;
+        vault.user_shares = 1_000_000;
+        vault.manager_net_deposits = 1_000_000;
+        vault.manager_total_deposits = 1_000_000;
+        let vault_equity = 2_000_000;
+
+        let mut mp = ManagerPosition::new(
+            Pubkey::default(),
+            Pubkey::default(),
+            Pubkey::default(),
+            0,
+            now,
+        );
+        mp.initialize(vault, &mut None, vault_equity, now).unwrap();
+        vault.min_manager_share_bps = 4_000; // 40%
+
+        // 600k of 1.6M shares is below 40%
+        assert!(mp.validate_min_manager_share(vault, 400_000).is_err());
+
+        // 700k of 1.7M shares is above 40%
+        mp.request_withdraw(
+            300_000,
+            WithdrawUnit::Shares,
+            vault_equity,
+            vault,
+            &mut None,
+            now,
+        )
+        .unwrap();
+        mp.withdraw(vault_equity, vault, &mut None, now).unwrap();
+        assert_eq!(mp.capital_shares().unwrap(), 700_000);
+        mp.validate_min_manager_share(vault, 0).unwrap();
+
+        // depositors joining dilute the manager below the minimum, blocking further deposits
+        vault.user_shares += 200_000;
+        vault.total_shares += 200_000;
+        assert!(mp.validate_min_manager_share(vault, 0).is_err());
+
+        // the minimum no longer applies once the vault is shut down
+        vault.shutdown().unwrap();
+        mp.validate_min_manager_share(vault, 700_000).unwrap();
+    }
+}
@@ -1,17 +1,45 @@
 pub use account_maps::*;
+pub use delegate_timelock::*;
+pub use depositor_operator::*;
+pub use drawdown_guard::*;
+pub use fee_recipients::*;
+pub use manager_position::*;
+pub use risk_limits::*;
+pub use share_class::*;
+pub use sub_accounts::*;
 pub use tokenized_vault_depositor::*;
+pub use tokenized_withdraw_request::*;
 pub use traits::*;
 pub use vault::*;
+pub use vault_allocations::*;
 pub use vault_depositor::*;
+pub use vault_depositor_transfer::*;
 pub use vault_protocol::*;
+pub use vault_referrer::*;
+pub use vault_roles::*;
+pub use withdraw_gate::*;
 pub use withdraw_unit::*;
 
 pub mod account_maps;
+mod delegate_timelock;
+mod depositor_operator;
+mod drawdown_guard;
 pub mod events;
+mod fee_recipients;
+mod manager_position;
+mod risk_limits;
+mod share_class;
+mod sub_accounts;
 mod tokenized_vault_depositor;
+mod tokenized_withdraw_request;
 pub mod traits;
 mod vault;
+mod vault_allocations;
 mod vault_depositor;
+mod vault_depositor_transfer;
 mod vault_protocol;
+mod vault_referrer;
+mod vault_roles;
+mod withdraw_gate;
 pub mod withdraw_request;
 mod withdraw_unit;
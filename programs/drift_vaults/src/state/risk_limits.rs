@@ -0,0 +1,335 @@
+use crate::constants::BPS_PRECISION;
+use crate::error::ErrorCode;
+use crate::events::RiskLimitViolation;
+use crate::state::Vault;
+use crate::{validate, Size};
+use static_assertions::const_assert_eq;
+
+use anchor_lang::prelude::*;
+use drift::math::casting::Cast;
+use drift::math::margin::calculate_user_equity;
+use drift::math::position::calculate_base_asset_value_with_oracle_price;
+use drift::math::safe_math::SafeMath;
+use drift::math::spot_balance::get_token_value;
+use drift::state::oracle_map::OracleMap;
+use drift::state::perp_market_map::PerpMarketMap;
+use drift::state::spot_market_map::SpotMarketMap;
+use drift::state::user::User;
+use drift_macros::assert_no_slop;
+
+/// Number of market indexes a market allowlist can hold
+pub const RISK_LIMITS_MAX_MARKETS: u16 = 256;
+
+/// Strategy mandate for a [`Vault`]'s drift users. The permissionless `check_vault_risk` crank makes the users
+/// reduce-only or revokes the delegate when any of them trades outside the allowed markets or above the leverage or
+/// position notional limits.
+#[assert_no_slop]
+#[account(zero_copy(unsafe))]
+#[derive(Default, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub struct RiskLimits {
+    /// The vault the risk limits belong to
+    pub vault: Pubkey,
+    /// The risk limits account's pubkey. It is a pda of vault
+    pub pubkey: Pubkey,
+    /// Bitmap of the perp market indexes the vault can hold positions in
+    pub allowed_perp_markets: [u64; 4],
+    /// Bitmap of the spot market indexes the vault can hold positions in. The vault's own spot market is always allowed
+    pub allowed_spot_markets: [u64; 4],
+    /// The max notional of any single position, 0 for no limit
+    /// precision: QUOTE_PRECISION
+    pub max_position_notional: u64,
+    /// When the limits were last breached. Zero if they aren't breached
+    pub breached_ts: i64,
+    /// The max total position notional over equity, 0 for no limit
+    /// precision: BPS_PRECISION
+    pub max_leverage: u32,
+    /// If true a breach revokes the delegate instead of making the drift user reduce-only
+    pub revoke_delegate: bool,
+    /// The bump for the risk limits pda
+    pub bump: u8,
+    pub padding1: [u8; 2],
+    pub padding: [u64; 4],
+}
+
+impl Size for RiskLimits {
+    const SIZE: usize = 184 + 8;
+}
+
+const_assert_eq!(RiskLimits::SIZE, std::mem::size_of::<RiskLimits>() + 8);
+
+impl RiskLimits {
+    pub fn new(vault: Pubkey, pubkey: Pubkey, bump: u8) -> Self {
+        Self {
+            vault,
+            pubkey,
+            bump,
+            ..Self::default()
+        }
+    }
+
+    pub fn update_limits(
+        &mut self,
+        allowed_perp_markets: &[u16],
+        allowed_spot_markets: &[u16],
+        max_leverage: u32,
+        max_position_notional: u64,
+        revoke_delegate: bool,
+    ) -> Result<()> {
+        self.allowed_perp_markets = Self::market_bitmap(allowed_perp_markets)?;
+        self.allowed_spot_markets = Self::market_bitmap(allowed_spot_markets)?;
+        validate!(
+            max_leverage == 0 || max_leverage.cast::<u64>()? >= BPS_PRECISION,
+            ErrorCode::InvalidRiskLimits,
+            "max leverage must be 0 or at least {}",
+            BPS_PRECISION
+        )?;
+        self.max_leverage = max_leverage;
+        self.max_position_notional = max_position_notional;
+        self.revoke_delegate = revoke_delegate;
+
+        Ok(())
+    }
+
+    /// Checks `self` allows nothing `before` did not
+    pub fn validate_tightened(&self, before: &RiskLimits) -> Result<()> {
+        let markets_tightened = self
+            .allowed_perp_markets
+            .iter()
+            .zip(before.allowed_perp_markets.iter())
+            .chain(
+                self.allowed_spot_markets
+                    .iter()
+                    .zip(before.allowed_spot_markets.iter()),
+            )
+            .all(|(after, before)| after & !before == 0);
+
+        validate!(
+            markets_tightened,
+            ErrorCode::InvalidRiskLimits,
+            "market allowlists can only be narrowed"
+        )?;
+
+        validate!(
+            Self::is_limit_tightened(self.max_leverage.cast()?, before.max_leverage.cast()?)
+                && Self::is_limit_tightened(
+                    self.max_position_notional,
+                    before.max_position_notional
+                ),
+            ErrorCode::InvalidRiskLimits,
+            "leverage and position notional limits can only be lowered"
+        )?;
+
+        Ok(())
+    }
+
+    fn is_limit_tightened(after: u64, before: u64) -> bool {
+        before == 0 || (after != 0 && after <= before)
+    }
+
+    fn market_bitmap(market_indexes: &[u16]) -> Result<[u64; 4]> {
+        let mut bitmap = [0_u64; 4];
+        for market_index in market_indexes {
+            validate!(
+                *market_index < RISK_LIMITS_MAX_MARKETS,
+                ErrorCode::InvalidRiskLimits,
+                "market index {} must be less than {}",
+                market_index,
+                RISK_LIMITS_MAX_MARKETS
+            )?;
+            bitmap[(*market_index / 64) as usize] |= 1_u64 << (market_index % 64);
+        }
+
+        Ok(bitmap)
+    }
+
+    fn is_market_allowed(bitmap: &[u64; 4], market_index: u16) -> bool {
+        market_index < RISK_LIMITS_MAX_MARKETS
+            && bitmap[(market_index / 64) as usize] & (1_u64 << (market_index % 64)) != 0
+    }
+
+    pub fn is_perp_market_allowed(&self, market_index: u16) -> bool {
+        Self::is_market_allowed(&self.allowed_perp_markets, market_index)
+    }
+
+    pub fn is_spot_market_allowed(&self, vault: &Vault, market_index: u16) -> bool {
+        market_index == vault.spot_market_index
+            || Self::is_market_allowed(&self.allowed_spot_markets, market_index)
+    }
+
+    pub fn is_breached(&self) -> bool {
+        self.breached_ts != 0
+    }
+
+    /// Returns the first limit the drift user breaks, with the market index and the offending value (notional in
+    /// QUOTE_PRECISION, leverage in BPS_PRECISION)
+    pub fn find_violation(
+        &self,
+        vault: &Vault,
+        user: &User,
+        perp_market_map: &PerpMarketMap,
+        spot_market_map: &SpotMarketMap,
+        oracle_map: &mut OracleMap,
+    ) -> Result<Option<(RiskLimitViolation, u16, u64)>> {
+        let mut total_notional: u128 = 0;
+
+        for perp_position in user.perp_positions.iter().filter(|p| !p.is_available()) {
+            let market_index = perp_position.market_index;
+            if !self.is_perp_market_allowed(market_index) {
+                return Ok(Some((RiskLimitViolation::PerpMarket, market_index, 0)));
+            }
+
+            let perp_market = perp_market_map.get_ref(&market_index)?;
+            let oracle_price = oracle_map.get_price_data(&perp_market.amm.oracle)?.price;
+            let notional = calculate_base_asset_value_with_oracle_price(
+                perp_position.base_asset_amount.cast()?,
+                oracle_price,
+            )?;
+
+            if self.max_position_notional != 0 && notional > self.max_position_notional.cast()? {
+                return Ok(Some((
+                    RiskLimitViolation::PositionNotional,
+                    market_index,
+                    notional.cast()?,
+                )));
+            }
+
+            total_notional = total_notional.safe_add(notional)?;
+        }
+
+        for spot_position in user.spot_positions.iter().filter(|p| !p.is_available()) {
+            let market_index = spot_position.market_index;
+            if !self.is_spot_market_allowed(vault, market_index) {
+                return Ok(Some((RiskLimitViolation::SpotMarket, market_index, 0)));
+            }
+
+            let spot_market = spot_market_map.get_ref(&market_index)?;
+            let token_amount = spot_position.get_signed_token_amount(&spot_market)?;
+
+            // deposits of the vault's own token are its capital, not a position
+            if market_index == vault.spot_market_index && token_amount >= 0 {
+                continue;
+            }
+
+            let oracle_price = oracle_map.get_price_data(&spot_market.oracle)?.price;
+            let notional =
+                get_token_value(token_amount, spot_market.decimals, oracle_price)?.unsigned_abs();
+
+            if self.max_position_notional != 0 && notional > self.max_position_notional.cast()? {
+                return Ok(Some((
+                    RiskLimitViolation::PositionNotional,
+                    market_index,
+                    notional.cast()?,
+                )));
+            }
+
+            total_notional = total_notional.safe_add(notional)?;
+        }
+
+        if self.max_leverage == 0 || total_notional == 0 {
+            return Ok(None);
+        }
+
+        let (equity, _) =
+            calculate_user_equity(user, perp_market_map, spot_market_map, oracle_map)?;
+
+        let leverage = if equity > 0 {
+            total_notional
+                .safe_mul(BPS_PRECISION.cast()?)?
+                .safe_div(equity.unsigned_abs())?
+                .min(u64::MAX as u128)
+                .cast::<u64>()?
+        } else {
+            u64::MAX
+        };
+
+        if leverage > self.max_leverage.cast()? {
+            return Ok(Some((
+                RiskLimitViolation::Leverage,
+                vault.spot_market_index,
+                leverage,
+            )));
+        }
+
+        Ok(None)
+    }
+}
+
+pub trait RiskLimitsProvider<'a> {
+    /// The vault's [`RiskLimits`], if it is anywhere in the remaining accounts
+    fn risk_limits(&self, vault: &Vault) -> Option<AccountLoader<'a, RiskLimits>>;
+}
+
+impl<'a: 'info, 'info, T: anchor_lang::Bumps> RiskLimitsProvider<'a>
+    for Context<'_, '_, 'a, 'info, T>
+{
+    fn risk_limits(&self, vault: &Vault) -> Option<AccountLoader<'a, RiskLimits>> {
+        self.remaining_accounts
+            .iter()
+            .filter_map(|account_info| AccountLoader::<'a, RiskLimits>::try_from(account_info).ok())
+            .find(|account| {
+                account
+                    .load()
+                    .map(|account| account.vault == vault.pubkey)
+                    .unwrap_or(false)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RiskLimits, Vault};
+    use anchor_lang::prelude::Pubkey;
+
+    #[test]
+    fn test_risk_limits_market_allowlists() {
+        let vault = Vault {
+            spot_market_index: 0,
+            ..Vault::default()
+        };
+        let mut limits = RiskLimits::new(Pubkey::default(), Pubkey::default(), 0);
+        limits
+            .update_limits(&[0, 1, 70, 255], &[1], 30_000, 0, false)
+            .unwrap();
+
+        assert!(limits.is_perp_market_allowed(0));
+        assert!(limits.is_perp_market_allowed(70));
+        assert!(limits.is_perp_market_allowed(255));
+        assert!(!limits.is_perp_market_allowed(2));
+        assert!(!limits.is_perp_market_allowed(256));
+
+        // the vault's own spot market is always allowed
+        assert!(limits.is_spot_market_allowed(&vault, 0));
+        assert!(limits.is_spot_market_allowed(&vault, 1));
+        assert!(!limits.is_spot_market_allowed(&vault, 2));
+
+        assert!(limits.update_limits(&[256], &[], 0, 0, false).is_err());
+        assert!(limits.update_limits(&[], &[], 9_999, 0, false).is_err());
+    }
+
+    #[test]
+    fn test_risk_limits_can_only_tighten() {
+        let mut before = RiskLimits::new(Pubkey::default(), Pubkey::default(), 0);
+        before
+            .update_limits(&[0, 1], &[1], 30_000, 0, false)
+            .unwrap();
+
+        let mut after = RiskLimits::new(Pubkey::default(), Pubkey::default(), 0);
+        after
+            .update_limits(&[0], &[], 20_000, 1_000_000, true)
+            .unwrap();
+        assert!(after.validate_tightened(&before).is_ok());
+
+        // allowing a new market
+        after.update_limits(&[0, 2], &[], 20_000, 0, true).unwrap();
+        assert!(after.validate_tightened(&before).is_err());
+
+        // raising leverage
+        after.update_limits(&[0], &[], 40_000, 0, true).unwrap();
+        assert!(after.validate_tightened(&before).is_err());
+
+        // removing the leverage limit
+        after.update_limits(&[0], &[], 0, 0, true).unwrap();
+        assert!(after.validate_tightened(&before).is_err());
+    }
+}
@@ -0,0 +1,286 @@
+use crate::error::ErrorCode;
+use crate::state::{Vault, VaultDepositor};
+use crate::{validate, Size};
+use static_assertions::const_assert_eq;
+
+use anchor_lang::prelude::*;
+use drift::math::casting::Cast;
+use drift::math::safe_math::SafeMath;
+use drift_macros::assert_no_slop;
+
+/// A set of fee terms inside a [`Vault`], e.g. a founders class with lower fees. Depositors in a class pay the
+/// class `profit_share` instead of `vault.profit_share`, and the class `management_fee` instead of the vault wide one.
+/// The vault wide fee minted on a member's shares is rebated, or topped up to the class fee, by moving shares between
+/// the member and the manager, so fees in one class never dilute another.
+#[assert_no_slop]
+#[account(zero_copy(unsafe))]
+#[derive(Default, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub struct ShareClass {
+    /// The vault the share class belongs to
+    pub vault: Pubkey,
+    /// The share class account's pubkey. It is a pda of vault and class_id
+    pub pubkey: Pubkey,
+    /// The name of the share class
+    pub name: [u8; 32],
+    /// The shares held by depositors in the class
+    pub user_shares: u128,
+    /// Total management fee paid by depositors in the class
+    pub total_management_fee: u64,
+    /// Total profit share paid by depositors in the class
+    pub total_profit_share: u64,
+    /// The annual fee charged on the equity of depositors in the class: PERCENTAGE_PRECISION
+    pub management_fee: u32,
+    /// Percentage the manager charges on all profits realized by depositors in the class: PERCENTAGE_PRECISION
+    pub profit_share: u32,
+    /// The exponent for user_shares decimal places
+    pub vault_shares_base: u32,
+    /// Number of depositors in the class
+    pub num_depositors: u32,
+    /// The id stored on member [`VaultDepositor`]s. Ids start at 1, 0 is the vault's default terms
+    pub class_id: u8,
+    /// The bump for the share class pda
+    pub bump: u8,
+    pub padding1: [u8; 6],
+    pub padding: [u64; 4],
+}
+
+impl Size for ShareClass {
+    const SIZE: usize = 184 + 8;
+}
+
+const_assert_eq!(ShareClass::SIZE, std::mem::size_of::<ShareClass>() + 8);
+
+impl ShareClass {
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        vault: Pubkey,
+        pubkey: Pubkey,
+        class_id: u8,
+        name: [u8; 32],
+        management_fee: u32,
+        profit_share: u32,
+        shares_base: u32,
+        bump: u8,
+    ) -> Self {
+        Self {
+            vault,
+            pubkey,
+            name,
+            user_shares: 0,
+            total_management_fee: 0,
+            total_profit_share: 0,
+            management_fee,
+            profit_share,
+            vault_shares_base: shares_base,
+            num_depositors: 0,
+            class_id,
+            bump,
+            padding1: [0; 6],
+            padding: [0; 4],
+        }
+    }
+
+    pub fn apply_rebase(&mut self, vault: &Vault) -> Result<()> {
+        if vault.shares_base != self.vault_shares_base {
+            validate!(
+                vault.shares_base > self.vault_shares_base,
+                ErrorCode::InvalidVaultRebase,
+                "Rebase expo out of bounds"
+            )?;
+
+            let expo_diff = (vault.shares_base - self.vault_shares_base).cast::<u32>()?;
+            let rebase_divisor = 10_u128.pow(expo_diff);
+
+            msg!(
+                "rebasing share class: base: {} -> {} ",
+                self.vault_shares_base,
+                vault.shares_base,
+            );
+
+            self.user_shares = self.user_shares.safe_div(rebase_divisor)?;
+            self.vault_shares_base = vault.shares_base;
+        }
+
+        Ok(())
+    }
+
+    /// Updates the class totals after a member's shares changed from `shares_before` to `shares_after`.
+    pub fn update_user_shares(&mut self, shares_before: u128, shares_after: u128) -> Result<()> {
+        // member shares round down on rebase, so the class total can only be ahead of their sum
+        self.user_shares = self
+            .user_shares
+            .saturating_sub(shares_before)
+            .safe_add(shares_after)?;
+
+        Ok(())
+    }
+}
+
+pub trait ShareClassProvider<'a> {
+    /// The depositor's [`ShareClass`], if it is in one and the class is anywhere in the remaining accounts
+    fn share_class(
+        &self,
+        vault_depositor: &VaultDepositor,
+    ) -> Option<AccountLoader<'a, ShareClass>>;
+}
+
+impl<'a: 'info, 'info, T: anchor_lang::Bumps> ShareClassProvider<'a>
+    for Context<'_, '_, 'a, 'info, T>
+{
+    fn share_class(
+        &self,
+        vault_depositor: &VaultDepositor,
+    ) -> Option<AccountLoader<'a, ShareClass>> {
+        if vault_depositor.share_class == 0 {
+            return None;
+        }
+
+        self.remaining_accounts
+            .iter()
+            .filter_map(|account_info| AccountLoader::<'a, ShareClass>::try_from(account_info).ok())
+            .find(|sc| {
+                sc.load()
+                    .map(|sc| {
+                        sc.vault == vault_depositor.vault
+                            && sc.class_id == vault_depositor.share_class
+                    })
+                    .unwrap_or(false)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ShareClass, Vault, VaultDepositor, VaultDepositorBase};
+    use anchor_lang::prelude::Pubkey;
+    use drift::math::constants::{ONE_YEAR, QUOTE_PRECISION_U64};
+    use drift::math::insurance::if_shares_to_vault_amount as depositor_shares_to_vault_amount;
+
+    #[test]
+    fn test_share_class_fees_do_not_dilute_default_depositors() {
+        let mut now = 0;
+        let mut vault = Vault::default();
+        let mut vp = None;
+        vault.profit_share = 200_000; // 20%
+
+        let mut sc = ShareClass::new(
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            1,
+            [0; 32],
+            10_000,  // 1% management fee
+            100_000, // 10% profit share
+            0,
+            0,
+        );
+
+        let vd_a =
+            &mut VaultDepositor::new(Pubkey::default(), Pubkey::default(), Pubkey::default(), now);
+        let vd_b =
+            &mut VaultDepositor::new(Pubkey::default(), Pubkey::default(), Pubkey::default(), now);
+        vd_b.share_class = sc.class_id;
+        vd_b.class_management_fee = sc.management_fee;
+        vd_b.class_profit_share = sc.profit_share;
+
+        let amount = 100 * QUOTE_PRECISION_U64;
+        vd_a.deposit(amount, 0, &mut vault, &mut vp, now).unwrap();
+        vd_b.deposit(amount, amount, &mut vault, &mut vp, now)
+            .unwrap();
+        sc.update_user_shares(0, vd_b.get_vault_shares()).unwrap();
+        assert_eq!(vault.total_shares, 200_000_000);
+
+        // vault doubles over a year
+        let vault_equity = 4 * amount;
+        now += ONE_YEAR as i64;
+
+        vd_b.settle_fees(Some(&mut sc), None, vault_equity, &mut vault, &mut vp, now)
+            .unwrap();
+
+        // $10 class profit share and 1% of the remaining $190 as management fee
+        assert_eq!(sc.total_profit_share, 10_000_000);
+        assert_eq!(sc.total_management_fee, 1_900_000);
+        assert_eq!(vd_b.get_vault_shares(), 94_050_000);
+        assert_eq!(sc.user_shares, 94_050_000);
+        assert_eq!(vault.manager_total_fee, 1_900_000);
+        // class fees move shares to the manager without minting
+        assert_eq!(vault.total_shares, 200_000_000);
+
+        // settling again at the same timestamp is a noop
+        vd_b.settle_fees(Some(&mut sc), None, vault_equity, &mut vault, &mut vp, now)
+            .unwrap();
+        assert_eq!(vd_b.get_vault_shares(), 94_050_000);
+
+        // the default depositor only pays the vault profit share
+        vd_a.apply_profit_share(vault_equity, &mut vault, &mut vp)
+            .unwrap();
+        assert_eq!(vd_a.get_vault_shares(), 90_000_000);
+        assert_eq!(vd_a.get_profit_share_fee_paid(), 20_000_000);
+        assert_eq!(
+            vault.get_manager_shares(&mut vp).unwrap(),
+            200_000_000 - 90_000_000 - 94_050_000
+        );
+    }
+
+    #[test]
+    fn test_share_class_fee_below_vault_fee() {
+        let mut now = 0;
+        let mut vault = Vault::default();
+        let mut vp = None;
+        vault.management_fee = 20_000; // 2%
+
+        let mut sc = ShareClass::new(
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            1,
+            [0; 32],
+            10_000, // 1% management fee
+            0,
+            0,
+            0,
+        );
+
+        let vd_a =
+            &mut VaultDepositor::new(Pubkey::default(), Pubkey::default(), Pubkey::default(), now);
+        let vd_b =
+            &mut VaultDepositor::new(Pubkey::default(), Pubkey::default(), Pubkey::default(), now);
+        vd_b.share_class = sc.class_id;
+        vd_b.class_management_fee = sc.management_fee;
+
+        let amount = 100 * QUOTE_PRECISION_U64;
+        vd_a.deposit(amount, 0, &mut vault, &mut vp, now).unwrap();
+        vd_b.deposit(amount, amount, &mut vault, &mut vp, now)
+            .unwrap();
+        sc.update_user_shares(0, vd_b.get_vault_shares()).unwrap();
+
+        // flat vault over a year
+        let vault_equity = 2 * amount;
+        now += ONE_YEAR as i64;
+
+        vd_b.settle_fees(Some(&mut sc), None, vault_equity, &mut vault, &mut vp, now)
+            .unwrap();
+
+        // the vault wide 2% was minted on every depositor, the class member is rebated down to its 1%
+        assert_eq!(vault.total_shares, 204_081_600);
+        assert_eq!(sc.total_management_fee, 980_000);
+        assert_eq!(vd_b.get_vault_shares(), 100_999_999);
+        assert_eq!(sc.user_shares, 100_999_999);
+        assert_eq!(vault.manager_total_fee, 3_020_000);
+
+        // the default depositor pays 2% and the class member about 1%
+        let equity_a = depositor_shares_to_vault_amount(
+            vd_a.get_vault_shares(),
+            vault.total_shares,
+            vault_equity,
+        )
+        .unwrap();
+        let equity_b = depositor_shares_to_vault_amount(
+            vd_b.get_vault_shares(),
+            vault.total_shares,
+            vault_equity,
+        )
+        .unwrap();
+        assert_eq!(equity_a, 98_000_015);
+        assert_eq!(equity_b, 98_980_014);
+    }
+}
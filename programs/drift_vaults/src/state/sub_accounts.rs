@@ -0,0 +1,114 @@
+use crate::error::ErrorCode;
+use crate::state::{Vault, VaultCompanion};
+use crate::validate;
+
+use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
+use drift::math::casting::Cast;
+use drift::state::user::{User, UserStats};
+
+/// Max number of drift users a vault can own, including its primary user (sub-account 0)
+pub const MAX_VAULT_SUB_ACCOUNTS: u16 = 8;
+
+pub trait SubAccountProvider<'a> {
+    /// The vault's drift users other than its primary `Vault.user`. Every sub-account the vault's drift user stats
+    /// count must be in the remaining accounts, after the market accounts, or the vault equity would be understated.
+    fn sub_accounts(
+        &self,
+        vault: &Vault,
+        user_stats: &AccountInfo,
+    ) -> Result<Vec<AccountLoader<'a, User>>>;
+
+    /// Same as [`SubAccountProvider::sub_accounts`] for instructions without a `drift_user_stats` account. A vault
+    /// with sub-accounts needs its user stats in the remaining accounts too, after the market accounts.
+    fn sub_accounts_in_remaining_accounts(
+        &self,
+        vault: &Vault,
+    ) -> Result<Vec<AccountLoader<'a, User>>>;
+}
+
+impl<'a: 'info, 'info, T: anchor_lang::Bumps> SubAccountProvider<'a>
+    for Context<'_, '_, 'a, 'info, T>
+{
+    fn sub_accounts(
+        &self,
+        vault: &Vault,
+        user_stats: &AccountInfo,
+    ) -> Result<Vec<AccountLoader<'a, User>>> {
+        if !vault.has_companion(VaultCompanion::SubAccounts) {
+            return Ok(vec![]);
+        }
+
+        let number_of_sub_accounts = load_number_of_sub_accounts(user_stats)?;
+
+        let mut sub_accounts: Vec<AccountLoader<'a, User>> = vec![];
+        for account_info in self.remaining_accounts.iter() {
+            if account_info.key() == vault.user
+                || sub_accounts.iter().any(|s| s.key() == account_info.key())
+            {
+                continue;
+            }
+
+            let sub_account = match AccountLoader::<'a, User>::try_from(account_info) {
+                Ok(sub_account) => sub_account,
+                Err(_) => continue,
+            };
+
+            if sub_account.load()?.authority == vault.pubkey {
+                sub_accounts.push(sub_account);
+            }
+        }
+
+        validate!(
+            sub_accounts.len().cast::<u16>()? == number_of_sub_accounts.saturating_sub(1),
+            ErrorCode::SubAccountsMissing,
+            "expected {} vault sub-accounts in remaining accounts, found {}",
+            number_of_sub_accounts.saturating_sub(1),
+            sub_accounts.len()
+        )?;
+
+        Ok(sub_accounts)
+    }
+
+    fn sub_accounts_in_remaining_accounts(
+        &self,
+        vault: &Vault,
+    ) -> Result<Vec<AccountLoader<'a, User>>> {
+        if !vault.has_companion(VaultCompanion::SubAccounts) {
+            return Ok(vec![]);
+        }
+
+        let user_stats = self
+            .remaining_accounts
+            .iter()
+            .find(|a| a.key() == vault.user_stats);
+        let user_stats = match user_stats {
+            Some(user_stats) => user_stats,
+            None => {
+                msg!("vault user stats not found in remaining accounts");
+                return Err(ErrorCode::SubAccountsMissing.into());
+            }
+        };
+
+        self.sub_accounts(vault, user_stats)
+    }
+}
+
+/// The number of drift users counted by `user_stats`. An [`AccountLoader`] needs the account borrowed for `'info`,
+/// which a named account in the instruction's accounts isn't, so this reads the account data directly.
+fn load_number_of_sub_accounts(user_stats: &AccountInfo) -> Result<u16> {
+    if user_stats.owner != &drift::ID {
+        return Err(anchor_lang::error::ErrorCode::AccountOwnedByWrongProgram.into());
+    }
+
+    let data = user_stats.try_borrow_data()?;
+    let size = std::mem::size_of::<UserStats>();
+    if data.len() < 8 + size {
+        return Err(anchor_lang::error::ErrorCode::AccountDidNotDeserialize.into());
+    }
+    if data[..8] != UserStats::discriminator() {
+        return Err(anchor_lang::error::ErrorCode::AccountDiscriminatorMismatch.into());
+    }
+
+    Ok(bytemuck::from_bytes::<UserStats>(&data[8..8 + size]).number_of_sub_accounts)
+}
@@ -3,7 +3,7 @@ use std::cell::RefMut;
 use crate::error::ErrorCode;
 use crate::events::{VaultDepositorAction, VaultDepositorRecord, VaultDepositorV1Record};
 use crate::state::vault::Vault;
-use crate::{validate, VaultFee, VaultProtocol};
+use crate::{validate, TokenizedWithdrawRequest, VaultFee, VaultProtocol};
 use crate::{Size, VaultDepositorBase};
 use static_assertions::const_assert_eq;
 
@@ -51,7 +51,13 @@ pub struct TokenizedVaultDepositor {
     /// The bump for the vault pda
     pub bump: u8,
     pub padding1: [u8; 3],
-    pub padding: [u64; 10],
+    /// Number of open [`TokenizedWithdrawRequest`]s holding shares redeemed from this depositor
+    pub num_withdraw_requests: u32,
+    pub padding2: [u8; 4],
+    /// Share-weighted average ts the tokenized shares were deposited at, carried over from the depositors that
+    /// tokenized them. Used for early exit fees
+    pub deposit_ts: i64,
+    pub padding: [u64; 8],
 }
 
 impl Size for TokenizedVaultDepositor {
@@ -105,6 +111,13 @@ impl VaultDepositorBase for TokenizedVaultDepositor {
     fn set_profit_share_fee_paid(&mut self, amount: u64) {
         self.profit_share_fee_paid = amount;
     }
+
+    fn get_deposit_ts(&self) -> i64 {
+        self.deposit_ts
+    }
+    fn set_deposit_ts(&mut self, ts: i64) {
+        self.deposit_ts = ts;
+    }
 }
 
 impl TokenizedVaultDepositor {
@@ -131,10 +144,26 @@ impl TokenizedVaultDepositor {
             vault_shares_base,
             bump,
             padding1: [0; 3],
-            padding: [0; 10],
+            num_withdraw_requests: 0,
+            padding2: [0; 4],
+            deposit_ts: now,
+            padding: [0; 8],
         }
     }
 
+    pub fn check_can_close(&self, mint_supply: u64) -> Result<()> {
+        validate!(
+            self.vault_shares == 0 && mint_supply == 0 && self.num_withdraw_requests == 0,
+            ErrorCode::CannotCloseAccount,
+            "tokenized vault depositor has {} shares, {} tokens and {} withdraw requests outstanding",
+            self.vault_shares,
+            mint_supply,
+            self.num_withdraw_requests
+        )?;
+
+        Ok(())
+    }
+
     fn apply_rebase(
         &mut self,
         vault: &mut Vault,
@@ -226,6 +255,7 @@ impl TokenizedVaultDepositor {
                         .cast()?,
                     management_fee: management_fee_payment,
                     management_fee_shares,
+                    entry_exit_fee: 0,
                 });
             }
             Some(_) => {
@@ -251,6 +281,7 @@ impl TokenizedVaultDepositor {
                     management_fee_shares,
                     protocol_shares_before,
                     protocol_shares_after: vault.get_protocol_shares(vault_protocol),
+                    entry_exit_fee: 0,
                 });
             }
         }
@@ -320,6 +351,7 @@ impl TokenizedVaultDepositor {
                         .cast()?,
                     management_fee: management_fee_payment,
                     management_fee_shares,
+                    entry_exit_fee: 0,
                 });
             }
             Some(_) => {
@@ -345,17 +377,144 @@ impl TokenizedVaultDepositor {
                     management_fee_shares,
                     protocol_shares_before,
                     protocol_shares_after: vault.get_protocol_shares(vault_protocol),
+                    entry_exit_fee: 0,
                 });
             }
         }
 
         Ok((shares_to_redeem, vault_protocol.take()))
     }
+
+    /// Moves the shares backing `tokens_to_burn` out of this depositor and into the holder's
+    /// [`TokenizedWithdrawRequest`]. The tokens must be burned by the caller.
+    #[allow(clippy::too_many_arguments)]
+    pub fn request_withdraw_tokens(
+        self: &mut TokenizedVaultDepositor,
+        withdraw_request: &mut TokenizedWithdrawRequest,
+        vault: &mut Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+        mint_supply: u64,
+        vault_equity: u64,
+        tokens_to_burn: u64,
+        now: i64,
+    ) -> Result<u128> {
+        self.apply_rebase(vault, vault_protocol, vault_equity)?;
+        withdraw_request.apply_rebase(vault)?;
+
+        let VaultFee {
+            management_fee_payment,
+            management_fee_shares,
+            protocol_fee_payment,
+            protocol_fee_shares,
+        } = vault.apply_fee(vault_protocol, vault_equity, now)?;
+        let (manager_profit_share, protocol_profit_share) =
+            self.apply_profit_share(vault_equity, vault, vault_protocol)?;
+
+        let vault_shares_before = self.checked_vault_shares(vault)?;
+        let total_vault_shares_before = vault.total_shares;
+        let user_vault_shares_before = vault.user_shares;
+        let protocol_shares_before = vault.get_protocol_shares(vault_protocol);
+
+        let n_shares: u128 = depositor_shares_to_vault_amount(
+            tokens_to_burn.cast()?,
+            mint_supply.cast()?,
+            vault_shares_before.cast()?,
+        )?
+        .cast()?;
+
+        validate!(
+            n_shares > 0,
+            ErrorCode::InvalidVaultWithdrawSize,
+            "Requested n_shares = 0"
+        )?;
+
+        let withdraw_value: u64 =
+            depositor_shares_to_vault_amount(n_shares, vault.total_shares, vault_equity)?;
+
+        msg!(
+            "tokens_to_burn: {}, tokenized_vd.vault_shares: {}, token_supply_before: {}, n_shares: {}, withdraw_value: {}",
+            tokens_to_burn,
+            vault_shares_before,
+            mint_supply,
+            n_shares,
+            withdraw_value
+        );
+
+        withdraw_request.last_withdraw_request.set(
+            vault_shares_before,
+            n_shares,
+            withdraw_value,
+            vault_equity,
+            now,
+        )?;
+        withdraw_request.deposit_ts = self.deposit_ts;
+        vault.total_withdraw_requested = vault.total_withdraw_requested.safe_add(withdraw_value)?;
+        self.num_withdraw_requests = self.num_withdraw_requests.safe_add(1)?;
+
+        self.decrease_vault_shares(n_shares, vault)?;
+        self.last_vault_shares = self.checked_vault_shares(vault)?;
+        self.total_withdraws = self.total_withdraws.saturating_add(withdraw_value);
+        self.net_deposits = self.net_deposits.safe_sub(withdraw_value.cast()?)?;
+
+        match vault_protocol {
+            None => {
+                emit!(VaultDepositorRecord {
+                    ts: now,
+                    vault: vault.pubkey,
+                    depositor_authority: withdraw_request.authority,
+                    action: VaultDepositorAction::WithdrawRequest,
+                    amount: withdraw_value,
+                    spot_market_index: vault.spot_market_index,
+                    vault_equity_before: vault_equity,
+                    vault_shares_before,
+                    user_vault_shares_before,
+                    total_vault_shares_before,
+                    vault_shares_after: self.last_vault_shares,
+                    total_vault_shares_after: vault.total_shares,
+                    user_vault_shares_after: vault.user_shares,
+                    profit_share: manager_profit_share
+                        .safe_add(protocol_profit_share)?
+                        .cast()?,
+                    management_fee: management_fee_payment,
+                    management_fee_shares,
+                    entry_exit_fee: 0,
+                });
+            }
+            Some(_) => {
+                emit!(VaultDepositorV1Record {
+                    ts: now,
+                    vault: vault.pubkey,
+                    depositor_authority: withdraw_request.authority,
+                    action: VaultDepositorAction::WithdrawRequest,
+                    amount: withdraw_value,
+                    spot_market_index: vault.spot_market_index,
+                    vault_equity_before: vault_equity,
+                    vault_shares_before,
+                    user_vault_shares_before,
+                    total_vault_shares_before,
+                    vault_shares_after: self.last_vault_shares,
+                    total_vault_shares_after: vault.total_shares,
+                    user_vault_shares_after: vault.user_shares,
+                    protocol_profit_share,
+                    protocol_fee: protocol_fee_payment,
+                    protocol_fee_shares,
+                    manager_profit_share,
+                    management_fee: management_fee_payment,
+                    management_fee_shares,
+                    protocol_shares_before,
+                    protocol_shares_after: vault.get_protocol_shares(vault_protocol),
+                    entry_exit_fee: 0,
+                });
+            }
+        }
+
+        Ok(n_shares)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{TokenizedVaultDepositor, Vault, VaultDepositorBase};
+    use crate::{TokenizedVaultDepositor, TokenizedWithdrawRequest, Vault, VaultDepositorBase};
     use anchor_lang::prelude::Pubkey;
     use drift::math::constants::PERCENTAGE_PRECISION;
     use drift::math::safe_math::SafeMath;
@@ -458,6 +617,73 @@ mod tests {
         assert_eq!(tvd.last_vault_shares, tvd.vault_shares);
     }
 
+    #[test]
+    fn test_request_withdraw_tokens() {
+        let now = 1337;
+        let vault = &mut Vault::default();
+        vault.redeem_period = 100;
+        vault.total_shares = 1_000_000;
+        vault.user_shares = 1_000_000;
+        let mut tvd = TokenizedVaultDepositor::new(
+            Pubkey::default(),
+            Pubkey::default(),
+            Pubkey::default(),
+            0,
+            0,
+            now,
+        );
+        tvd.vault_shares = 500_000;
+        tvd.last_vault_shares = tvd.vault_shares;
+        let mut withdraw_request = TokenizedWithdrawRequest::new(
+            Pubkey::default(),
+            Pubkey::default(),
+            Pubkey::default(),
+            Pubkey::default(),
+            0,
+            0,
+        );
+
+        let total_supply = 500_000u64;
+        let vault_equity = 1_000_000u64;
+
+        // burn 50% of tokens into a withdraw request
+        let tokens_to_burn = total_supply / 2;
+        let n_shares = tvd
+            .request_withdraw_tokens(
+                &mut withdraw_request,
+                vault,
+                &mut None,
+                total_supply,
+                vault_equity,
+                tokens_to_burn,
+                now,
+            )
+            .expect("request_withdraw_tokens");
+        assert_eq!(n_shares, 250_000);
+        assert_eq!(tvd.vault_shares, 250_000);
+        assert_eq!(tvd.last_vault_shares, tvd.vault_shares);
+        assert_eq!(withdraw_request.last_withdraw_request.shares, n_shares);
+        assert_eq!(withdraw_request.last_withdraw_request.value, 250_000);
+        assert_eq!(vault.total_withdraw_requested, 250_000);
+        assert_eq!(tvd.num_withdraw_requests, 1);
+        assert!(tvd.check_can_close(total_supply - tokens_to_burn).is_err());
+        // shares stay in the vault until the request is withdrawn
+        assert_eq!(vault.total_shares, 1_000_000);
+
+        assert!(withdraw_request
+            .withdraw(vault_equity, vault, &mut None, now + 50)
+            .is_err());
+
+        let withdraw_amount = withdraw_request
+            .withdraw(vault_equity, vault, &mut None, now + 100)
+            .expect("withdraw");
+        assert_eq!(withdraw_amount, 250_000);
+        assert_eq!(vault.total_shares, 750_000);
+        assert_eq!(vault.user_shares, 750_000);
+        assert_eq!(vault.total_withdraw_requested, 0);
+        assert!(!withdraw_request.last_withdraw_request.pending());
+    }
+
     #[test]
     fn test_tokenize_shares_with_rebase() {
         let mut now = 1337;
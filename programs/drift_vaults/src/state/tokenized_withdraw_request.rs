@@ -0,0 +1,222 @@
+use std::cell::RefMut;
+
+use crate::error::ErrorCode;
+use crate::events::{VaultDepositorAction, VaultDepositorRecord, VaultDepositorV1Record};
+use crate::state::vault::Vault;
+use crate::state::withdraw_request::WithdrawRequest;
+use crate::Size;
+use crate::{validate, VaultFee, VaultProtocol};
+use static_assertions::const_assert_eq;
+
+use anchor_lang::prelude::*;
+use drift::math::casting::Cast;
+use drift::math::insurance::if_shares_to_vault_amount as depositor_shares_to_vault_amount;
+use drift::math::safe_math::SafeMath;
+use drift_macros::assert_no_slop;
+
+#[assert_no_slop]
+#[account(zero_copy(unsafe))]
+#[derive(Default, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub struct TokenizedWithdrawRequest {
+    /// The vault the tokens were redeemed from
+    pub vault: Pubkey,
+    /// The withdraw request account's pubkey. It is a pda of tokenized_vault_depositor and authority
+    pub pubkey: Pubkey,
+    /// The TokenizedVaultDepositor whose tokens were burned into this request
+    pub tokenized_vault_depositor: Pubkey,
+    /// The token holder that burned the tokens and receives the withdrawal
+    pub authority: Pubkey,
+    /// The pending withdraw request. The shares are held here on behalf of the authority and are no longer
+    /// counted in the TokenizedVaultDepositor's vault_shares
+    pub last_withdraw_request: WithdrawRequest,
+    /// The exponent for the request shares decimal places, rebased alongside the vault
+    pub vault_shares_base: u32,
+    /// The bump for the withdraw request pda
+    pub bump: u8,
+    pub padding1: [u8; 3],
+    /// The tokenized depositor's deposit_ts when the tokens were burned. Used for early exit fees
+    pub deposit_ts: i64,
+    pub padding: [u64; 3],
+}
+
+impl Size for TokenizedWithdrawRequest {
+    const SIZE: usize = 200 + 8;
+}
+
+const_assert_eq!(
+    TokenizedWithdrawRequest::SIZE,
+    std::mem::size_of::<TokenizedWithdrawRequest>() + 8
+);
+
+impl TokenizedWithdrawRequest {
+    pub fn new(
+        vault: Pubkey,
+        pubkey: Pubkey,
+        tokenized_vault_depositor: Pubkey,
+        authority: Pubkey,
+        vault_shares_base: u32,
+        bump: u8,
+    ) -> Self {
+        Self {
+            vault,
+            pubkey,
+            tokenized_vault_depositor,
+            authority,
+            last_withdraw_request: WithdrawRequest::default(),
+            vault_shares_base,
+            bump,
+            padding1: [0; 3],
+            deposit_ts: 0,
+            padding: [0; 3],
+        }
+    }
+
+    /// Brings the request shares up to the vault's shares_base. Expects the vault to already be rebased.
+    pub fn apply_rebase(&mut self, vault: &Vault) -> Result<Option<u128>> {
+        let mut rebase_divisor: Option<u128> = None;
+
+        if vault.shares_base != self.vault_shares_base {
+            validate!(
+                vault.shares_base > self.vault_shares_base,
+                ErrorCode::InvalidVaultRebase,
+                "Rebase expo out of bounds"
+            )?;
+
+            let expo_diff = (vault.shares_base - self.vault_shares_base).cast::<u32>()?;
+            let divisor = 10_u128.pow(expo_diff);
+
+            msg!(
+                "rebasing tokenized withdraw request: base: {} -> {} ",
+                self.vault_shares_base,
+                vault.shares_base,
+            );
+
+            self.vault_shares_base = vault.shares_base;
+            self.last_withdraw_request.rebase(divisor)?;
+            rebase_divisor = Some(divisor);
+        }
+
+        Ok(rebase_divisor)
+    }
+
+    pub fn withdraw(
+        &mut self,
+        vault_equity: u64,
+        vault: &mut Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+        now: i64,
+    ) -> Result<u64> {
+        self.last_withdraw_request
+            .check_redeem_period_finished(vault, now)?;
+
+        vault.apply_rebase(vault_protocol, vault_equity)?;
+        self.apply_rebase(vault)?;
+
+        let n_shares = self.last_withdraw_request.shares;
+
+        validate!(
+            n_shares > 0,
+            ErrorCode::InvalidVaultWithdraw,
+            "Must submit withdraw request and wait the redeem_period ({} seconds)",
+            vault.redeem_period
+        )?;
+
+        let total_vault_shares_before = vault.total_shares;
+        let user_vault_shares_before = vault.user_shares;
+        let protocol_shares_before = vault.get_protocol_shares(vault_protocol);
+
+        let VaultFee {
+            management_fee_payment,
+            management_fee_shares,
+            protocol_fee_payment,
+            protocol_fee_shares,
+        } = vault.apply_fee(vault_protocol, vault_equity, now)?;
+
+        let amount: u64 =
+            depositor_shares_to_vault_amount(n_shares, vault.total_shares, vault_equity)?;
+
+        let withdraw_amount = amount.min(self.last_withdraw_request.value);
+        msg!(
+            "amount={}, last_withdraw_request_value={}",
+            amount,
+            self.last_withdraw_request.value
+        );
+
+        let exit_fee = vault.calculate_exit_fee(withdraw_amount, self.deposit_ts, now)?;
+        let exit_fee_shares = vault
+            .calculate_load_fee_shares(exit_fee, vault_equity)?
+            .min(n_shares);
+        let withdraw_amount = withdraw_amount.safe_sub(exit_fee)?;
+        msg!("exit_fee={}, exit_fee_shares={}", exit_fee, exit_fee_shares);
+
+        vault.total_withdraws = vault.total_withdraws.saturating_add(withdraw_amount);
+        vault.net_deposits = vault.net_deposits.safe_sub(withdraw_amount.cast()?)?;
+        // shares backing the exit fee stay outstanding as manager shares
+        vault.total_shares = vault
+            .total_shares
+            .safe_sub(n_shares.safe_sub(exit_fee_shares)?)?;
+        vault.user_shares = vault.user_shares.safe_sub(n_shares)?;
+
+        if exit_fee_shares > 0 {
+            vault.manager_total_fee = vault.manager_total_fee.safe_add(exit_fee.cast()?)?;
+        }
+        vault.total_withdraw_requested = vault
+            .total_withdraw_requested
+            .safe_sub(self.last_withdraw_request.value)?;
+
+        self.last_withdraw_request.reset(now)?;
+
+        match vault_protocol {
+            None => {
+                emit!(VaultDepositorRecord {
+                    ts: now,
+                    vault: vault.pubkey,
+                    depositor_authority: self.authority,
+                    action: VaultDepositorAction::Withdraw,
+                    amount: withdraw_amount,
+                    spot_market_index: vault.spot_market_index,
+                    vault_equity_before: vault_equity,
+                    vault_shares_before: n_shares,
+                    user_vault_shares_before,
+                    total_vault_shares_before,
+                    vault_shares_after: 0,
+                    total_vault_shares_after: vault.total_shares,
+                    user_vault_shares_after: vault.user_shares,
+                    profit_share: 0,
+                    management_fee: management_fee_payment,
+                    management_fee_shares,
+                    entry_exit_fee: exit_fee,
+                });
+            }
+            Some(_) => {
+                emit!(VaultDepositorV1Record {
+                    ts: now,
+                    vault: vault.pubkey,
+                    depositor_authority: self.authority,
+                    action: VaultDepositorAction::Withdraw,
+                    amount: withdraw_amount,
+                    spot_market_index: vault.spot_market_index,
+                    vault_equity_before: vault_equity,
+                    vault_shares_before: n_shares,
+                    user_vault_shares_before,
+                    total_vault_shares_before,
+                    vault_shares_after: 0,
+                    total_vault_shares_after: vault.total_shares,
+                    user_vault_shares_after: vault.user_shares,
+                    protocol_profit_share: 0,
+                    protocol_fee: protocol_fee_payment,
+                    protocol_fee_shares,
+                    manager_profit_share: 0,
+                    management_fee: management_fee_payment,
+                    management_fee_shares,
+                    protocol_shares_before,
+                    protocol_shares_after: vault.get_protocol_shares(vault_protocol),
+                    entry_exit_fee: exit_fee,
+                });
+            }
+        }
+
+        Ok(withdraw_amount)
+    }
+}
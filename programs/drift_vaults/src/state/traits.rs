@@ -39,6 +39,14 @@ pub trait VaultDepositorBase {
     fn get_profit_share_fee_paid(&self) -> u64;
     fn set_profit_share_fee_paid(&mut self, amount: u64);
 
+    fn get_deposit_ts(&self) -> i64;
+    fn set_deposit_ts(&mut self, ts: i64);
+
+    /// The share of profits the manager charges this depositor: PERCENTAGE_PRECISION
+    fn get_manager_profit_share(&self, vault: &Vault) -> u32 {
+        vault.profit_share
+    }
+
     fn validate_base(&self, vault: &Vault) -> Result<()> {
         validate!(
             self.get_vault_shares_base() == vault.shares_base,
@@ -78,6 +86,28 @@ pub trait VaultDepositorBase {
         Ok(())
     }
 
+    /// Moves deposit_ts towards `deposit_ts` in proportion to the `n_shares` being added. Must be called before
+    /// the shares are added.
+    fn update_deposit_ts(&mut self, n_shares: u128, deposit_ts: i64) -> Result<()> {
+        let vault_shares = self.get_vault_shares().cast::<i128>()?;
+        let n_shares = n_shares.cast::<i128>()?;
+        let total_shares = vault_shares.safe_add(n_shares)?;
+
+        if total_shares == 0 {
+            return Ok(());
+        }
+
+        self.set_deposit_ts(
+            vault_shares
+                .safe_mul(self.get_deposit_ts().cast()?)?
+                .safe_add(n_shares.safe_mul(deposit_ts.cast()?)?)?
+                .safe_div(total_shares)?
+                .cast()?,
+        );
+
+        Ok(())
+    }
+
     fn calculate_profit_share_and_update(
         &mut self,
         total_amount: u64,
@@ -92,7 +122,7 @@ pub trait VaultDepositorBase {
             let profit_u128 = profit.cast::<u128>()?;
 
             let manager_profit_share_amount = profit_u128
-                .safe_mul(vault.profit_share.cast()?)?
+                .safe_mul(self.get_manager_profit_share(vault).cast()?)?
                 .safe_div(PERCENTAGE_PRECISION)?;
             let protocol_profit_share_amount = match vault_protocol {
                 None => 0,
@@ -282,6 +312,8 @@ pub trait VaultDepositorBase {
         let to_depositor_shares_before = to.checked_vault_shares(vault)?;
 
         self.decrease_vault_shares(n_shares, vault)?;
+        // the shares keep their holding time, so exit fees can't be reset by moving them between depositors
+        to.update_deposit_ts(n_shares, self.get_deposit_ts())?;
         to.increase_vault_shares(n_shares, vault)?;
 
         self.set_net_deposits(self.get_net_deposits().safe_sub(withdraw_value.cast()?)?);
@@ -332,6 +364,7 @@ pub trait VaultDepositorBase {
                         .cast()?,
                     management_fee: management_fee_payment,
                     management_fee_shares,
+                    entry_exit_fee: 0,
                 });
 
                 emit!(VaultDepositorRecord {
@@ -353,6 +386,7 @@ pub trait VaultDepositorBase {
                         .cast()?,
                     management_fee: management_fee_payment,
                     management_fee_shares,
+                    entry_exit_fee: 0,
                 });
             }
             Some(_) => {
@@ -378,6 +412,7 @@ pub trait VaultDepositorBase {
                     management_fee_shares,
                     protocol_shares_before,
                     protocol_shares_after: vault.get_protocol_shares(vault_protocol),
+                    entry_exit_fee: 0,
                 });
 
                 emit!(VaultDepositorV1Record {
@@ -402,6 +437,7 @@ pub trait VaultDepositorBase {
                     management_fee_shares,
                     protocol_shares_before,
                     protocol_shares_after: vault.get_protocol_shares(vault_protocol),
+                    entry_exit_fee: 0,
                 });
             }
         }
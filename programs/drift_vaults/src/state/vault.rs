@@ -17,12 +17,18 @@ use drift::state::user::User;
 use drift_macros::assert_no_slop;
 use static_assertions::const_assert_eq;
 
-use crate::constants::TIME_FOR_LIQUIDATION;
+use crate::constants::{
+    BPS_PRECISION, MAX_WITHDRAW_PAUSE, TIME_FOR_LIQUIDATION, VAULT_DEAD_SHARES,
+};
 use crate::error::{ErrorCode, VaultResult};
-use crate::events::{VaultDepositorAction, VaultDepositorV1Record};
+use crate::events::{
+    ManagementFeeRecord, VaultDepositorAction, VaultDepositorV1Record, VaultPauseRecord,
+};
 use crate::state::events::VaultDepositorRecord;
 use crate::state::withdraw_request::WithdrawRequest;
-use crate::state::{VaultFee, VaultProtocol};
+use crate::state::{
+    ChildVaultPosition, ManagerPosition, VaultDepositorBase, VaultFee, VaultProtocol, WithdrawGate,
+};
 use crate::{validate, Size, VaultDepositor, WithdrawUnit};
 
 #[assert_no_slop]
@@ -49,8 +55,8 @@ pub struct Vault {
     pub liquidation_delegate: Pubkey,
     /// The sum of all shares held by the users (vault depositors)
     pub user_shares: u128,
-    /// The sum of all shares: deposits from users, manager deposits, manager profit/fee, and protocol profit/fee.
-    /// The manager deposits are total_shares - user_shares - protocol_profit_and_fee_shares.
+    /// The sum of all shares: deposits from users, manager deposits, manager profit/fee, referrer profit/fee, and protocol profit/fee.
+    /// The manager deposits are total_shares - user_shares - referrer_shares - protocol_profit_and_fee_shares.
     pub total_shares: u128,
     /// Last fee update unix timestamp
     pub last_fee_update_ts: i64,
@@ -91,8 +97,12 @@ pub struct Vault {
     pub shares_base: u32,
     /// Percentage the manager charges on all profits realized by depositors: PERCENTAGE_PRECISION
     pub profit_share: u32,
-    /// Vault manager only collect incentive fees during periods when returns are higher than this amount: PERCENTAGE_PRECISION
-    pub hurdle_rate: u32,
+    /// Vault manager only collect incentive fees during periods when returns are higher than this amount: PERCENTAGE_PRECISION.
+    /// Unused, it can only be set to zero
+    pub hurdle_rate: u16,
+    /// The share of total vault shares the manager must keep as capital in its [`ManagerPosition`] while there are
+    /// depositors, in bps. Zero for no minimum
+    pub min_manager_share_bps: u16,
     /// The spot market index the vault deposits into/withdraws from
     pub spot_market_index: u16,
     /// The bump for the vault pda
@@ -101,8 +111,39 @@ pub struct Vault {
     pub permissioned: bool,
     /// The optional [`VaultProtocol`] account.
     pub vault_protocol: bool,
-    pub padding1: [u8; 7],
-    pub padding: [u64; 7],
+    /// Bitmask of [`VaultCompanion`]s, the optional accounts the vault has
+    pub companions: u8,
+    /// Bitmask of [`VaultPauseFlag`]s for the vault actions currently halted
+    pub paused: u8,
+    /// Bitmask of the [`VaultPauseFlag`]s a tripped circuit breaker set. They are cleared when the breaker is reset
+    pub breaker_paused: u8,
+    /// Percentage of the manager profit share and management fee paid by referred depositors that goes to their
+    /// referrer: PERCENTAGE_PRECISION
+    pub referral_fee_share: u32,
+    /// The shares from profit share and management fee owed to all referrers
+    pub referrer_shares: u128,
+    /// Seconds after a deposit over which the early exit fee decays linearly to zero
+    pub early_exit_fee_period: u32,
+    /// Fee charged on deposits, in bps of the deposit amount
+    pub entry_fee_bps: u16,
+    /// Fee charged on withdraws, in bps of the withdraw amount
+    pub exit_fee_bps: u16,
+    /// Additional exit fee charged on withdraws within early_exit_fee_period of depositing, in bps
+    pub early_exit_fee_bps: u16,
+    /// Whether entry and exit fees are paid to the manager as shares. If false they are left in the vault for
+    /// the remaining depositors.
+    pub load_fees_to_manager: bool,
+    /// Whether the vault is winding down. Deposits are blocked, the drift user is reduce only and there is no
+    /// redeem_period. Depositors can be withdrawn by anyone once positions are flat.
+    pub shutdown: bool,
+    /// The annual management fee charged on depositor equity above each of management_fee_tier_thresholds, in bps.
+    /// Equity below the first threshold is charged management_fee.
+    pub management_fee_tier_bps: [u16; 2],
+    /// Depositor equity at which each management fee tier starts. Zero thresholds are unused tiers.
+    pub management_fee_tier_thresholds: [u64; 2],
+    /// When the current or last withdraw pause lapses. Withdraws can't be paused again until MAX_WITHDRAW_PAUSE
+    /// after this
+    pub withdraw_pause_end_ts: i64,
 }
 
 impl Vault {
@@ -116,6 +157,47 @@ impl Size for Vault {
 }
 const_assert_eq!(Vault::SIZE, std::mem::size_of::<Vault>() + 8);
 
+#[derive(Clone, Copy, PartialEq, Debug, Eq)]
+pub enum VaultPauseFlag {
+    Deposit = 0b00000001,
+    RequestWithdraw = 0b00000010,
+    Withdraw = 0b00000100,
+    Tokenize = 0b00001000,
+    /// Fee recipient claims (request_claim_fees and claim_fees). Fees still accrue, and the manager, protocol and
+    /// referrers can still withdraw their shares
+    Fees = 0b00010000,
+}
+
+impl VaultPauseFlag {
+    pub const ALL: u8 = 0b00011111;
+}
+
+/// Optional accounts a vault can have besides its [`VaultProtocol`]
+#[derive(Clone, Copy, PartialEq, Debug, Eq)]
+pub enum VaultCompanion {
+    ManagerPosition = 0b00000001,
+    WithdrawGate = 0b00000010,
+    VaultAllocations = 0b00000100,
+    DrawdownGuard = 0b00001000,
+    RiskLimits = 0b00010000,
+    DelegateTimelock = 0b00100000,
+    /// Drift users besides `Vault.user`, see [`crate::state::SubAccountProvider`]
+    SubAccounts = 0b01000000,
+    /// Not an account: set once another vault's [`crate::state::VaultAllocations`] holds this vault as a child. It is
+    /// never cleared, since a child is valued without allocations of its own.
+    ParentVault = 0b10000000,
+}
+
+/// Who is updating a vault's pause flags
+#[derive(Clone, Copy, PartialEq, Debug, Eq)]
+pub enum VaultPauser {
+    Manager,
+    /// Can add pause flags but not clear them
+    RiskOfficer,
+    /// Flags it sets can only be cleared by the protocol
+    Protocol,
+}
+
 impl Vault {
     pub fn apply_fee(
         &mut self,
@@ -126,6 +208,7 @@ impl Vault {
         let depositor_equity =
             depositor_shares_to_vault_amount(self.user_shares, self.total_shares, vault_equity)?
                 .cast::<i128>()?;
+        let management_fee = self.get_management_fee(depositor_equity.cast()?)?;
         let management_fee_payment: i128 = 0;
         let mut management_fee_shares: i128 = 0;
         let protocol_fee_payment: i128 = 0;
@@ -137,7 +220,7 @@ impl Vault {
 
             // default behavior in legacy [`Vault`], manager taxes equity - 1 if tax is >= equity
             let management_fee_payment = depositor_equity
-                .safe_mul(vault.management_fee.cast()?)?
+                .safe_mul(management_fee.cast()?)?
                 .safe_div(PERCENTAGE_PRECISION_I128)?
                 .safe_mul(since_last.cast()?)?
                 .safe_div(ONE_YEAR.cast()?)?
@@ -181,8 +264,7 @@ impl Vault {
             Some(vp) => {
                 if self.management_fee != 0 && vp.protocol_fee != 0 && depositor_equity > 0 {
                     let since_last = now.safe_sub(self.last_fee_update_ts)?;
-                    let total_fee = self
-                        .management_fee
+                    let total_fee = management_fee
                         .safe_add(vp.protocol_fee.cast()?)?
                         .cast::<i128>()?;
 
@@ -194,7 +276,7 @@ impl Vault {
                         .safe_mul(since_last.cast()?)?
                         .safe_div(ONE_YEAR.cast()?)?;
                     let management_fee_payment = total_fee_payment
-                        .safe_mul(self.management_fee.cast()?)?
+                        .safe_mul(management_fee.cast()?)?
                         .safe_div(total_fee)?;
                     let protocol_fee_payment = total_fee_payment
                         .min(depositor_equity.saturating_sub(1))
@@ -319,6 +401,17 @@ impl Vault {
         // this will underflow if there is an issue with protocol fee calc
         self.get_manager_shares(vault_protocol)?;
 
+        if self.has_management_fee_tiers() && management_fee_shares != 0 {
+            emit!(ManagementFeeRecord {
+                ts: now,
+                vault: self.pubkey,
+                depositor_equity: depositor_equity.cast()?,
+                management_fee: self.management_fee,
+                management_fee_rate: management_fee,
+                management_fee_shares: management_fee_shares.cast()?,
+            });
+        }
+
         Ok(VaultFee {
             management_fee_payment: management_fee_payment.cast::<i64>()?,
             management_fee_shares: management_fee_shares.cast::<i64>()?,
@@ -332,10 +425,14 @@ impl Vault {
         vault_protocol: &mut Option<RefMut<VaultProtocol>>,
     ) -> VaultResult<u128> {
         Ok(match vault_protocol {
-            None => self.total_shares.safe_sub(self.user_shares)?,
+            None => self
+                .total_shares
+                .safe_sub(self.user_shares)?
+                .safe_sub(self.referrer_shares)?,
             Some(vp) => self
                 .total_shares
                 .safe_sub(self.user_shares)?
+                .safe_sub(self.referrer_shares)?
                 .safe_sub(vp.protocol_profit_and_fee_shares)?,
         })
     }
@@ -347,6 +444,46 @@ impl Vault {
         }
     }
 
+    pub fn has_management_fee_tiers(&self) -> bool {
+        self.management_fee_tier_thresholds[0] != 0
+    }
+
+    /// The effective annual management fee on `depositor_equity`: PERCENTAGE_PRECISION. With tiers, each slice of
+    /// equity is charged its tier's rate (capped at the rate below it) and the result is the blended rate.
+    pub fn get_management_fee(&self, depositor_equity: u64) -> Result<i64> {
+        if !self.has_management_fee_tiers() || self.management_fee <= 0 || depositor_equity == 0 {
+            return Ok(self.management_fee);
+        }
+
+        let equity = depositor_equity.cast::<u128>()?;
+        let mut rate = self.management_fee.cast::<u128>()?;
+        let mut tier_start = 0_u128;
+        let mut fee = 0_u128;
+
+        for (threshold, fee_bps) in self
+            .management_fee_tier_thresholds
+            .iter()
+            .zip(self.management_fee_tier_bps.iter())
+        {
+            let threshold = threshold.cast::<u128>()?;
+            if threshold == 0 || equity <= threshold {
+                break;
+            }
+
+            fee = fee.safe_add(threshold.safe_sub(tier_start)?.safe_mul(rate)?)?;
+            tier_start = threshold;
+            rate = fee_bps
+                .cast::<u128>()?
+                .safe_mul(PERCENTAGE_PRECISION)?
+                .safe_div(BPS_PRECISION.cast()?)?
+                .min(rate);
+        }
+
+        fee = fee.safe_add(equity.safe_sub(tier_start)?.safe_mul(rate)?)?;
+
+        Ok(fee.safe_div(equity)?.cast()?)
+    }
+
     pub fn get_profit_share(&self, vault_protocol: &Option<&VaultProtocol>) -> VaultResult<u32> {
         Ok(match vault_protocol {
             None => self.profit_share,
@@ -354,6 +491,50 @@ impl Vault {
         })
     }
 
+    /// The entry fee owed on a deposit of `amount`.
+    pub fn calculate_entry_fee(&self, amount: u64) -> Result<u64> {
+        Ok(amount
+            .cast::<u128>()?
+            .safe_mul(self.entry_fee_bps.cast()?)?
+            .safe_div(BPS_PRECISION.cast()?)?
+            .cast()?)
+    }
+
+    /// The exit fee owed on a withdraw of `amount` by a depositor whose shares were deposited at `deposit_ts`.
+    /// The early exit fee decays linearly to zero over `early_exit_fee_period`.
+    pub fn calculate_exit_fee(&self, amount: u64, deposit_ts: i64, now: i64) -> Result<u64> {
+        let mut fee_bps = self.exit_fee_bps.cast::<u128>()?;
+
+        if self.early_exit_fee_bps > 0 && self.early_exit_fee_period > 0 {
+            let period = self.early_exit_fee_period.cast::<u128>()?;
+            let held = now.safe_sub(deposit_ts)?.max(0).cast::<u128>()?;
+            if held < period {
+                fee_bps = fee_bps.safe_add(
+                    self.early_exit_fee_bps
+                        .cast::<u128>()?
+                        .safe_mul(period.safe_sub(held)?)?
+                        .safe_div(period)?,
+                )?;
+            }
+        }
+
+        Ok(amount
+            .cast::<u128>()?
+            .safe_mul(fee_bps)?
+            .safe_div(BPS_PRECISION.cast()?)?
+            .cast()?)
+    }
+
+    /// The shares minted to the manager for an entry or exit fee. Zero if load fees are left in the vault for
+    /// the remaining depositors.
+    pub fn calculate_load_fee_shares(&self, fee: u64, vault_equity: u64) -> Result<u128> {
+        if !self.load_fees_to_manager || fee == 0 {
+            return Ok(0);
+        }
+
+        vault_amount_to_depositor_shares(fee, self.total_shares, vault_equity)
+    }
+
     pub fn apply_rebase(
         &mut self,
         vault_protocol: &mut Option<RefMut<VaultProtocol>>,
@@ -367,6 +548,7 @@ impl Vault {
             if expo_diff != 0 {
                 self.total_shares = self.total_shares.safe_div(_rebase_divisor)?;
                 self.user_shares = self.user_shares.safe_div(_rebase_divisor)?;
+                self.referrer_shares = self.referrer_shares.safe_div(_rebase_divisor)?;
                 self.shares_base = self.shares_base.safe_add(expo_diff)?;
                 if let Some(vp) = vault_protocol {
                     vp.protocol_profit_and_fee_shares = vp
@@ -387,14 +569,19 @@ impl Vault {
         Ok(rebase_divisor)
     }
 
+    /// Sums the equity of the primary drift user and every sub-account, converted to the vault's spot market, and
+    /// what the vault's child vault positions would pay out at `now`
     pub fn calculate_equity(
         &self,
         user: &User,
+        sub_accounts: &[AccountLoader<User>],
+        allocations: &[ChildVaultPosition],
         perp_market_map: &PerpMarketMap,
         spot_market_map: &SpotMarketMap,
         oracle_map: &mut OracleMap,
-    ) -> VaultResult<u64> {
-        let (vault_equity, all_oracles_valid) =
+        now: i64,
+    ) -> Result<u64> {
+        let (mut vault_equity, all_oracles_valid) =
             calculate_user_equity(user, perp_market_map, spot_market_map, oracle_map)?;
 
         validate!(
@@ -402,6 +589,23 @@ impl Vault {
             ErrorCode::InvalidEquityValue,
             "oracle invalid"
         )?;
+
+        for sub_account in sub_accounts {
+            let (sub_account_equity, all_oracles_valid) = calculate_user_equity(
+                &*sub_account.load()?,
+                perp_market_map,
+                spot_market_map,
+                oracle_map,
+            )?;
+
+            validate!(
+                all_oracles_valid,
+                ErrorCode::InvalidEquityValue,
+                "oracle invalid"
+            )?;
+
+            vault_equity = vault_equity.safe_add(sub_account_equity)?;
+        }
         validate!(
             vault_equity >= 0,
             ErrorCode::InvalidEquityValue,
@@ -415,10 +619,45 @@ impl Vault {
             .price
             .cast::<i128>()?;
 
-        Ok(vault_equity
+        let mut vault_equity = vault_equity
             .safe_mul(spot_market_precision)?
             .safe_div(oracle_price)?
-            .cast::<u64>()?)
+            .cast::<u64>()?;
+        drop(spot_market);
+
+        for allocation in allocations {
+            let child_vault = allocation.vault.load()?;
+            let child_vault_equity = child_vault.calculate_equity(
+                &*allocation.user.load()?,
+                &allocation.sub_accounts,
+                &[],
+                perp_market_map,
+                spot_market_map,
+                oracle_map,
+                now,
+            )?;
+
+            let child_vault_protocol = allocation
+                .vault_protocol
+                .as_ref()
+                .map(|vp| vp.load())
+                .transpose()?;
+            let share_class = allocation
+                .share_class
+                .as_ref()
+                .map(|sc| sc.load())
+                .transpose()?;
+            vault_equity = vault_equity.safe_add(ChildVaultPosition::calculate_value(
+                &child_vault,
+                child_vault_protocol.as_deref(),
+                &*allocation.vault_depositor.load()?,
+                share_class.as_deref(),
+                child_vault_equity,
+                now,
+            )?)?;
+        }
+
+        Ok(vault_equity)
     }
 
     pub fn manager_deposit(
@@ -428,6 +667,12 @@ impl Vault {
         vault_equity: u64,
         now: i64,
     ) -> Result<()> {
+        validate!(
+            !self.shutdown,
+            ErrorCode::VaultShutdown,
+            "vault is shut down"
+        )?;
+
         self.apply_rebase(vault_protocol, vault_equity)?;
         let VaultFee {
             management_fee_payment,
@@ -770,6 +1015,288 @@ impl Vault {
         self.liquidation_start_ts = 0;
     }
 
+    pub fn has_companion(&self, companion: VaultCompanion) -> bool {
+        self.companions & companion as u8 != 0
+    }
+
+    pub fn add_companion(&mut self, companion: VaultCompanion) {
+        self.companions |= companion as u8;
+    }
+
+    pub fn is_paused(&self, flag: VaultPauseFlag, now: i64) -> bool {
+        let paused = self.paused & flag as u8 != 0;
+        match flag {
+            // withdraw pauses lapse on their own so they can't be used to trap funds
+            VaultPauseFlag::Withdraw => paused && now < self.withdraw_pause_end_ts,
+            _ => paused,
+        }
+    }
+
+    pub fn validate_not_paused(&self, flag: VaultPauseFlag, now: i64) -> VaultResult {
+        validate!(
+            !self.is_paused(flag, now),
+            ErrorCode::VaultPaused,
+            "vault action {:?} is paused",
+            flag
+        )?;
+
+        Ok(())
+    }
+
+    pub fn update_paused(&mut self, paused: u8, authority: Pubkey, now: i64) -> VaultResult {
+        validate!(
+            paused & !VaultPauseFlag::ALL == 0,
+            ErrorCode::InvalidVaultUpdate,
+            "invalid pause flags {:#010b}",
+            paused
+        )?;
+
+        let paused_before = self.paused;
+        let withdraws_paused = self.is_paused(VaultPauseFlag::Withdraw, now);
+        let pause_withdraws = paused & VaultPauseFlag::Withdraw as u8 != 0;
+
+        if pause_withdraws && !withdraws_paused {
+            let next_pause_ts = self.withdraw_pause_end_ts.safe_add(MAX_WITHDRAW_PAUSE)?;
+            validate!(
+                now >= next_pause_ts,
+                ErrorCode::VaultPaused,
+                "withdraws can't be paused again until {}",
+                next_pause_ts
+            )?;
+            self.withdraw_pause_end_ts = now.safe_add(MAX_WITHDRAW_PAUSE)?;
+        } else if !pause_withdraws && withdraws_paused {
+            self.withdraw_pause_end_ts = now;
+        }
+
+        self.paused = paused;
+
+        emit!(VaultPauseRecord {
+            ts: now,
+            vault: self.pubkey,
+            authority,
+            paused_before,
+            paused_after: self.paused,
+            withdraw_pause_end_ts: self.withdraw_pause_end_ts,
+        });
+
+        Ok(())
+    }
+
+    /// Updates the pause flags on behalf of `pauser`. Flags set by the protocol can only be cleared by the protocol,
+    /// flags set by a tripped circuit breaker only by resetting it, and the risk officer can only add flags.
+    pub fn update_paused_by(
+        &mut self,
+        pauser: VaultPauser,
+        paused: u8,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+        authority: Pubkey,
+        now: i64,
+    ) -> VaultResult {
+        let mut active = self.paused;
+        if !self.is_paused(VaultPauseFlag::Withdraw, now) {
+            active &= !(VaultPauseFlag::Withdraw as u8);
+        }
+        let cleared = active & !paused;
+
+        let protocol_paused = vault_protocol.as_ref().map_or(0, |vp| vp.protocol_paused);
+        let locked = match pauser {
+            VaultPauser::Manager => protocol_paused | self.breaker_paused,
+            VaultPauser::RiskOfficer => VaultPauseFlag::ALL,
+            VaultPauser::Protocol => self.breaker_paused,
+        };
+        validate!(
+            cleared & locked == 0,
+            ErrorCode::InvalidVaultUpdate,
+            "pause flags {:#010b} can't be cleared by the {:?}",
+            cleared & locked,
+            pauser
+        )?;
+
+        if let Some(vp) = vault_protocol {
+            if pauser == VaultPauser::Protocol {
+                vp.protocol_paused |= paused & !active;
+            }
+            vp.protocol_paused &= paused;
+        }
+
+        self.update_paused(paused, authority, now)
+    }
+
+    /// Pauses deposits for a tripped circuit breaker. The pause stays until the breaker is reset.
+    pub fn set_breaker_paused(&mut self, authority: Pubkey, now: i64) -> VaultResult {
+        self.breaker_paused |= VaultPauseFlag::Deposit as u8;
+        self.update_deposits_paused(true, authority, now)
+    }
+
+    /// Lifts a reset circuit breaker's deposit pause, unless the protocol paused deposits as well
+    pub fn clear_breaker_paused(
+        &mut self,
+        vault_protocol: &Option<RefMut<VaultProtocol>>,
+        authority: Pubkey,
+        now: i64,
+    ) -> VaultResult {
+        self.breaker_paused &= !(VaultPauseFlag::Deposit as u8);
+
+        let protocol_paused = vault_protocol.as_ref().map_or(0, |vp| vp.protocol_paused);
+        if protocol_paused & VaultPauseFlag::Deposit as u8 != 0 {
+            return Ok(());
+        }
+
+        self.update_deposits_paused(false, authority, now)
+    }
+
+    /// Sets or clears only the deposit pause, leaving the other flags as they are
+    fn update_deposits_paused(&mut self, paused: bool, authority: Pubkey, now: i64) -> VaultResult {
+        let mut flags = if paused {
+            self.paused | VaultPauseFlag::Deposit as u8
+        } else {
+            self.paused & !(VaultPauseFlag::Deposit as u8)
+        };
+
+        // a lapsed withdraw pause must not be restarted
+        if !self.is_paused(VaultPauseFlag::Withdraw, now) {
+            flags &= !(VaultPauseFlag::Withdraw as u8);
+        }
+
+        self.update_paused(flags, authority, now)
+    }
+
+    pub fn shutdown(&mut self) -> VaultResult {
+        validate!(
+            !self.shutdown,
+            ErrorCode::VaultShutdown,
+            "vault already shut down"
+        )?;
+        validate!(
+            !self.in_liquidation(),
+            ErrorCode::OngoingLiquidation,
+            "can't shut down vault in liquidation"
+        )?;
+
+        self.shutdown = true;
+        self.redeem_period = 0;
+
+        Ok(())
+    }
+
+    /// Whether the next deposit mints the vault's first shares. The caller locks VAULT_DEAD_SHARES of it so the
+    /// first depositor never holds every share and can't inflate the share price with a donation to the drift user.
+    /// Equity donated before the first deposit doesn't matter, only that no shares exist yet.
+    pub fn is_first_deposit(&self) -> bool {
+        self.total_shares == 0
+    }
+
+    /// Moves VAULT_DEAD_SHARES of the manager's first deposit into user shares no depositor owns.
+    pub fn lock_manager_dead_shares(
+        &mut self,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+    ) -> Result<()> {
+        let manager_shares = self.get_manager_shares(vault_protocol)?;
+        validate!(
+            manager_shares > VAULT_DEAD_SHARES,
+            ErrorCode::InvalidVaultDeposit,
+            "first deposit must mint more than {} shares",
+            VAULT_DEAD_SHARES
+        )?;
+
+        self.user_shares = self.user_shares.safe_add(VAULT_DEAD_SHARES)?;
+
+        Ok(())
+    }
+
+    /// The VAULT_DEAD_SHARES locked by the first deposit, at the vault's current shares_base. Rounded up, rebasing
+    /// vault.user_shares can leave a fraction of them behind.
+    pub fn dead_shares(&self) -> u128 {
+        match 10_u128.checked_pow(self.shares_base) {
+            Some(rebase_divisor) if rebase_divisor <= VAULT_DEAD_SHARES => {
+                (VAULT_DEAD_SHARES + rebase_divisor - 1) / rebase_divisor
+            }
+            _ => 1,
+        }
+    }
+
+    /// A vault can be closed once it is shut down and every share has been withdrawn.
+    pub fn check_can_close(&self) -> VaultResult {
+        validate!(
+            self.shutdown,
+            ErrorCode::CannotCloseAccount,
+            "vault is not shut down"
+        )?;
+        // only the dead shares locked by the first deposit are left once every owner has withdrawn, or no shares at
+        // all if the vault never took a deposit
+        validate!(
+            self.total_shares == self.user_shares
+                && self.user_shares <= self.dead_shares()
+                && self.total_withdraw_requested == 0,
+            ErrorCode::CannotCloseAccount,
+            "vault has {} shares and {} requested withdraws outstanding",
+            self.total_shares,
+            self.total_withdraw_requested
+        )?;
+
+        Ok(())
+    }
+
+    /// Checks the drift user has no perp positions, open orders or balances outside the vault's spot market.
+    pub fn check_positions_flat(&self, user: &User) -> VaultResult {
+        for perp_position in user.perp_positions.iter() {
+            validate!(
+                perp_position.is_available(),
+                ErrorCode::VaultPositionsNotFlat,
+                "perp market {} position is not flat",
+                perp_position.market_index
+            )?;
+        }
+
+        for spot_position in user.spot_positions.iter() {
+            validate!(
+                spot_position.open_orders == 0
+                    && (spot_position.market_index == self.spot_market_index
+                        || spot_position.scaled_balance == 0),
+                ErrorCode::VaultPositionsNotFlat,
+                "spot market {} position is not flat",
+                spot_position.market_index
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// Checks a sub-account is flat and holds no balances at all, since depositors are only paid out of the primary
+    /// drift user.
+    pub fn check_sub_account_flat(&self, sub_account: &User) -> VaultResult {
+        self.check_positions_flat(sub_account)?;
+
+        validate!(
+            sub_account
+                .spot_positions
+                .iter()
+                .all(|spot_position| spot_position.scaled_balance == 0),
+            ErrorCode::VaultPositionsNotFlat,
+            "sub-account {} still holds a balance",
+            sub_account.sub_account_id
+        )?;
+
+        Ok(())
+    }
+
+    /// Checks the vault has withdrawn everything from its child vaults, since depositors are only paid out of the
+    /// primary drift user.
+    pub fn check_allocations_flat(&self, allocations: &[ChildVaultPosition]) -> Result<()> {
+        for allocation in allocations {
+            let vault_depositor = allocation.vault_depositor.load()?;
+            validate!(
+                vault_depositor.get_vault_shares() == 0,
+                ErrorCode::VaultPositionsNotFlat,
+                "vault still holds {} shares of child vault {}",
+                vault_depositor.get_vault_shares(),
+                vault_depositor.vault
+            )?;
+        }
+
+        Ok(())
+    }
+
     pub fn protocol_request_withdraw(
         &mut self,
         vault_protocol: &mut Option<RefMut<VaultProtocol>>,
@@ -786,6 +1313,14 @@ impl Vault {
             )?;
         }
 
+        if let Some(vp) = vault_protocol {
+            validate!(
+                !vp.fee_recipients,
+                ErrorCode::InvalidFeeRecipients,
+                "Protocol fees are paid out to fee recipients, use claim_fees"
+            )?;
+        }
+
         let rebase_divisor = self.apply_rebase(vault_protocol, vault_equity)?;
         let VaultFee {
             management_fee_payment,
@@ -917,6 +1452,7 @@ impl Vault {
             let vault_shares_after = self
                 .total_shares
                 .safe_sub(self.user_shares)?
+                .safe_sub(self.referrer_shares)?
                 .safe_sub(vp.protocol_profit_and_fee_shares)?;
             // get_protocol_shares logic but doesn't need Option<RefMut<VaultProtocol>>
             let protocol_shares_after = vp.protocol_profit_and_fee_shares;
@@ -1031,6 +1567,7 @@ impl Vault {
             let vault_shares_after = self
                 .total_shares
                 .safe_sub(self.user_shares)?
+                .safe_sub(self.referrer_shares)?
                 .safe_sub(vp.protocol_profit_and_fee_shares)?;
             // get_protocol_shares but doesn't need Option<RefMut<VaultProtocol>>
             let protocol_shares_after = vp.protocol_profit_and_fee_shares;
@@ -1111,6 +1648,76 @@ impl Vault {
         }
     }
 
+    pub fn validate_manager_position(
+        &self,
+        mp: &Option<AccountLoader<ManagerPosition>>,
+    ) -> Result<()> {
+        match mp {
+            None => {
+                if self.has_companion(VaultCompanion::ManagerPosition) {
+                    // Vault has ManagerPosition but no rem acct provided.
+                    let ec = ErrorCode::ManagerPositionMissing;
+                    msg!("Error {} thrown at {}:{}", ec, file!(), line!());
+                    msg!("ManagerPosition missing in remaining accounts");
+                    Err(anchor_lang::error::Error::from(ec))
+                } else {
+                    Ok(())
+                }
+            }
+            Some(mp) => {
+                if self.has_companion(VaultCompanion::ManagerPosition) {
+                    let (expected, _) = Pubkey::find_program_address(
+                        &[b"manager_position", self.pubkey.as_ref()],
+                        &crate::id(),
+                    );
+                    let actual = mp.to_account_info().key();
+                    if actual != expected {
+                        Err(
+                            anchor_lang::error::Error::from(error::ErrorCode::ConstraintSeeds)
+                                .with_account_name("manager_position")
+                                .with_pubkeys((actual, expected)),
+                        )
+                    } else {
+                        Ok(())
+                    }
+                } else {
+                    // Vault does not have ManagerPosition, but rem accts provided one
+                    let ec = ErrorCode::ManagerPositionMissing;
+                    msg!("Error {} thrown at {}:{}", ec, file!(), line!());
+                    msg!("Vault does not have ManagerPosition");
+                    Err(anchor_lang::error::Error::from(ec))
+                }
+            }
+        }
+    }
+
+    pub fn validate_withdraw_gate(&self, gate: &Option<AccountLoader<WithdrawGate>>) -> Result<()> {
+        match gate {
+            None => {
+                validate!(
+                    !self.has_companion(VaultCompanion::WithdrawGate),
+                    ErrorCode::InvalidWithdrawGate,
+                    "WithdrawGate missing in remaining accounts"
+                )?;
+            }
+            Some(gate) => {
+                let (expected, _) = Pubkey::find_program_address(
+                    &[b"withdraw_gate", self.pubkey.as_ref()],
+                    &crate::id(),
+                );
+                validate!(
+                    self.has_companion(VaultCompanion::WithdrawGate) && gate.key() == expected,
+                    ErrorCode::InvalidWithdrawGate,
+                    "WithdrawGate {} does not match vault withdraw gate {}",
+                    gate.key(),
+                    expected
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
     fn emit_vault_depositor_record(
         &self,
         params: VaultDepositorRecordParams,
@@ -1135,6 +1742,7 @@ impl Vault {
                     profit_share: params.manager_profit_share,
                     management_fee: params.management_fee,
                     management_fee_shares: params.management_fee_shares,
+                    entry_exit_fee: 0,
                 });
             }
             Some(protocol_params) => {
@@ -1161,6 +1769,7 @@ impl Vault {
                     protocol_fee_shares: protocol_params.protocol_fee_shares,
                     protocol_shares_before: protocol_params.protocol_shares_before,
                     protocol_shares_after: protocol_params.protocol_shares_after,
+                    entry_exit_fee: 0,
                 });
             }
         };
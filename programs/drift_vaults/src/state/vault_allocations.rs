@@ -0,0 +1,438 @@
+use std::cell::RefCell;
+
+use crate::error::ErrorCode;
+use crate::state::{
+    ShareClass, ShareClassProvider, SubAccountProvider, Vault, VaultCompanion, VaultDepositor,
+    VaultProtocol,
+};
+use crate::{validate, Size};
+use static_assertions::const_assert_eq;
+
+use anchor_lang::prelude::*;
+use bytemuck::Zeroable;
+use drift::math::casting::Cast;
+use drift::math::insurance::if_shares_to_vault_amount as depositor_shares_to_vault_amount;
+use drift::math::safe_math::SafeMath;
+use drift::state::user::User;
+use drift_macros::assert_no_slop;
+
+/// Max number of child vaults a vault can allocate to
+pub const MAX_VAULT_ALLOCATIONS: usize = 4;
+
+#[assert_no_slop]
+#[derive(
+    Default, AnchorSerialize, AnchorDeserialize, Copy, Clone, Eq, PartialEq, Debug, Zeroable,
+)]
+pub struct VaultAllocation {
+    /// The child vault. Default if the slot is unused
+    pub vault: Pubkey,
+    /// The parent vault's [`VaultDepositor`] in the child vault
+    pub vault_depositor: Pubkey,
+}
+
+impl VaultAllocation {
+    pub fn is_available(&self) -> bool {
+        self.vault == Pubkey::default()
+    }
+}
+
+/// The child [`Vault`]s a fund-of-funds vault holds positions in. The parent vault is the authority of a
+/// [`VaultDepositor`] in each child and the position's value is part of the parent's equity.
+#[assert_no_slop]
+#[account(zero_copy(unsafe))]
+#[derive(Default, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub struct VaultAllocations {
+    /// The parent vault the allocations belong to
+    pub vault: Pubkey,
+    /// The vault allocations account's pubkey. It is a pda of vault
+    pub pubkey: Pubkey,
+    pub allocations: [VaultAllocation; MAX_VAULT_ALLOCATIONS],
+    /// The bump for the vault allocations pda
+    pub bump: u8,
+    pub padding1: [u8; 7],
+    pub padding: [u64; 4],
+}
+
+impl Size for VaultAllocations {
+    const SIZE: usize = 360 + 8;
+}
+
+const_assert_eq!(
+    VaultAllocations::SIZE,
+    std::mem::size_of::<VaultAllocations>() + 8
+);
+
+impl VaultAllocations {
+    pub fn new(vault: Pubkey, pubkey: Pubkey, bump: u8) -> Self {
+        Self {
+            vault,
+            pubkey,
+            bump,
+            ..Self::default()
+        }
+    }
+
+    pub fn get(&self, child_vault: &Pubkey) -> Option<&VaultAllocation> {
+        self.allocations
+            .iter()
+            .find(|allocation| !allocation.is_available() && allocation.vault == *child_vault)
+    }
+
+    /// Checks `vault_depositor` is the parent's position in `child_vault`
+    pub fn validate_allocation(
+        &self,
+        child_vault: &Pubkey,
+        vault_depositor: &Pubkey,
+    ) -> Result<()> {
+        validate!(
+            self.get(child_vault)
+                .map_or(false, |allocation| allocation.vault_depositor
+                    == *vault_depositor),
+            ErrorCode::InvalidVaultAllocation,
+            "vault {} with depositor {} is not allocated to",
+            child_vault,
+            vault_depositor
+        )?;
+
+        Ok(())
+    }
+
+    pub fn add(&mut self, child_vault: Pubkey, vault_depositor: Pubkey) -> Result<()> {
+        validate!(
+            self.get(&child_vault).is_none(),
+            ErrorCode::InvalidVaultAllocation,
+            "vault {} is already allocated to",
+            child_vault
+        )?;
+
+        let slot = self
+            .allocations
+            .iter_mut()
+            .find(|allocation| allocation.is_available());
+
+        match slot {
+            Some(slot) => {
+                *slot = VaultAllocation {
+                    vault: child_vault,
+                    vault_depositor,
+                };
+                Ok(())
+            }
+            None => {
+                msg!(
+                    "vault can allocate to at most {} vaults",
+                    MAX_VAULT_ALLOCATIONS
+                );
+                Err(ErrorCode::InvalidVaultAllocation.into())
+            }
+        }
+    }
+
+    pub fn remove(&mut self, child_vault: &Pubkey) -> Result<()> {
+        let slot = self
+            .allocations
+            .iter_mut()
+            .find(|allocation| !allocation.is_available() && allocation.vault == *child_vault);
+
+        match slot {
+            Some(slot) => {
+                *slot = VaultAllocation::default();
+                Ok(())
+            }
+            None => {
+                msg!("vault {} is not allocated to", child_vault);
+                Err(ErrorCode::InvalidVaultAllocation.into())
+            }
+        }
+    }
+}
+
+/// The accounts needed to value a parent vault's position in a child vault
+pub struct ChildVaultPosition<'a> {
+    pub vault: AccountLoader<'a, Vault>,
+    pub vault_depositor: AccountLoader<'a, VaultDepositor>,
+    pub user: AccountLoader<'a, User>,
+    pub sub_accounts: Vec<AccountLoader<'a, User>>,
+    pub vault_protocol: Option<AccountLoader<'a, VaultProtocol>>,
+    pub share_class: Option<AccountLoader<'a, ShareClass>>,
+}
+
+impl ChildVaultPosition<'_> {
+    /// What withdrawing the parent's shares from the child vault would pay, in the child's (and parent's) spot
+    /// market token. That is net of the child's unsettled management and protocol fees, the parent's unsettled
+    /// share class fee and profit share, the value cap of a pending withdraw request and the exit fee.
+    pub fn calculate_value(
+        child_vault: &Vault,
+        child_vault_protocol: Option<&VaultProtocol>,
+        vault_depositor: &VaultDepositor,
+        share_class: Option<&ShareClass>,
+        child_vault_equity: u64,
+        now: i64,
+    ) -> Result<u64> {
+        // fees are settled on copies, the child's accounts are only read
+        let mut child_vault = *child_vault;
+        let mut vault_depositor = *vault_depositor;
+        let mut share_class = share_class.copied();
+        let child_vault_protocol = child_vault_protocol.map(|vp| RefCell::new(*vp));
+        let mut vp = child_vault_protocol.as_ref().map(|vp| vp.borrow_mut());
+
+        vault_depositor.settle_fees(
+            share_class.as_mut(),
+            None,
+            child_vault_equity,
+            &mut child_vault,
+            &mut vp,
+            now,
+        )?;
+        vault_depositor.apply_rebase(&mut child_vault, &mut vp, child_vault_equity)?;
+        child_vault.apply_fee(&mut vp, child_vault_equity, now)?;
+
+        let shares = vault_depositor.checked_vault_shares(&child_vault)?;
+        let requested_shares = vault_depositor.last_withdraw_request.shares.min(shares);
+
+        // requested shares are paid at most their value when the request was made
+        let requested_value = depositor_shares_to_vault_amount(
+            requested_shares,
+            child_vault.total_shares,
+            child_vault_equity,
+        )?
+        .min(vault_depositor.last_withdraw_request.value);
+        let unrequested_value = depositor_shares_to_vault_amount(
+            shares.safe_sub(requested_shares)?,
+            child_vault.total_shares,
+            child_vault_equity,
+        )?;
+        let value = requested_value.safe_add(unrequested_value)?;
+
+        // profit share is taken when a withdraw is requested, on the profit since the last time it was taken
+        let (manager_profit_share, protocol_profit_share) =
+            vault_depositor.calculate_profit_share_and_update(value, &child_vault, &mut vp)?;
+        let value = value.saturating_sub(
+            manager_profit_share
+                .safe_add(protocol_profit_share)?
+                .cast()?,
+        );
+
+        let exit_fee = child_vault.calculate_exit_fee(value, vault_depositor.deposit_ts, now)?;
+
+        Ok(value.safe_sub(exit_fee)?)
+    }
+}
+
+pub trait VaultAllocationsProvider<'a> {
+    /// The parent vault's positions in its child vaults. If the vault has [`VaultAllocations`], the account, and each
+    /// child's vault, vault depositor, drift user, drift user stats, sub-accounts, and [`VaultProtocol`] and
+    /// [`ShareClass`] if it has them, must be in the remaining accounts, after the market accounts and before any
+    /// [`VaultProtocol`] of the parent.
+    fn vault_allocations(&self, vault: &Vault) -> Result<Vec<ChildVaultPosition<'a>>>;
+}
+
+impl<'a: 'info, 'info, T: anchor_lang::Bumps> VaultAllocationsProvider<'a>
+    for Context<'_, '_, 'a, 'info, T>
+{
+    fn vault_allocations(&self, vault: &Vault) -> Result<Vec<ChildVaultPosition<'a>>> {
+        if !vault.has_companion(VaultCompanion::VaultAllocations) {
+            return Ok(vec![]);
+        }
+
+        let allocations = self
+            .remaining_accounts
+            .iter()
+            .filter_map(|account_info| {
+                AccountLoader::<'a, VaultAllocations>::try_from(account_info).ok()
+            })
+            .find(|allocations| {
+                allocations
+                    .load()
+                    .map(|allocations| allocations.vault == vault.pubkey)
+                    .unwrap_or(false)
+            });
+
+        let allocations = match allocations {
+            Some(allocations) => *allocations.load()?,
+            None => {
+                msg!("vault allocations not found in remaining accounts");
+                return Err(ErrorCode::VaultAllocationsMissing.into());
+            }
+        };
+
+        let find_account = |key: &Pubkey| {
+            let account_info = self.remaining_accounts.iter().find(|a| a.key() == *key);
+            if account_info.is_none() {
+                msg!(
+                    "vault allocation account {} not found in remaining accounts",
+                    key
+                );
+            }
+            account_info.ok_or(ErrorCode::VaultAllocationsMissing)
+        };
+
+        let mut positions = vec![];
+        for allocation in allocations
+            .allocations
+            .iter()
+            .filter(|allocation| !allocation.is_available())
+        {
+            let child_vault =
+                AccountLoader::<'a, Vault>::try_from(find_account(&allocation.vault)?)?;
+            let vault_depositor = AccountLoader::<'a, VaultDepositor>::try_from(find_account(
+                &allocation.vault_depositor,
+            )?)?;
+
+            let (user, sub_accounts, vault_protocol, share_class) = {
+                let child = child_vault.load()?;
+
+                validate!(
+                    !child.has_companion(VaultCompanion::VaultAllocations),
+                    ErrorCode::InvalidVaultAllocation,
+                    "child vault {} allocates to other vaults",
+                    child.pubkey
+                )?;
+
+                let user = AccountLoader::<'a, User>::try_from(find_account(&child.user)?)?;
+                let sub_accounts = self.sub_accounts_in_remaining_accounts(&child)?;
+
+                let vault_protocol = if child.vault_protocol {
+                    let (vault_protocol, _) = Pubkey::find_program_address(
+                        &[b"vault_protocol", child.pubkey.as_ref()],
+                        &crate::id(),
+                    );
+                    Some(AccountLoader::<'a, VaultProtocol>::try_from(find_account(
+                        &vault_protocol,
+                    )?)?)
+                } else {
+                    None
+                };
+                child.validate_vault_protocol(&vault_protocol)?;
+
+                let vd = vault_depositor.load()?;
+                let share_class = self.share_class(&vd);
+                vd.validate_share_class(&share_class)?;
+                drop(vd);
+
+                (user, sub_accounts, vault_protocol, share_class)
+            };
+
+            positions.push(ChildVaultPosition {
+                vault: child_vault,
+                vault_depositor,
+                user,
+                sub_accounts,
+                vault_protocol,
+                share_class,
+            });
+        }
+
+        Ok(positions)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{ChildVaultPosition, Vault, VaultAllocations, VaultDepositor, VaultDepositorBase};
+    use anchor_lang::prelude::Pubkey;
+
+    #[test]
+    fn test_vault_allocations_add_remove() {
+        let mut allocations = VaultAllocations::new(Pubkey::default(), Pubkey::default(), 0);
+        let children: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+
+        for child in children.iter().take(4) {
+            allocations.add(*child, Pubkey::new_unique()).unwrap();
+        }
+        assert!(allocations.get(&children[3]).is_some());
+
+        // duplicate and full
+        assert!(allocations.add(children[0], Pubkey::new_unique()).is_err());
+        assert!(allocations.add(children[4], Pubkey::new_unique()).is_err());
+
+        allocations.remove(&children[1]).unwrap();
+        assert!(allocations.get(&children[1]).is_none());
+        assert!(allocations.remove(&children[1]).is_err());
+        allocations.add(children[4], Pubkey::new_unique()).unwrap();
+        assert_eq!(allocations.allocations[1].vault, children[4]);
+    }
+
+    #[test]
+    fn test_child_vault_position_value() {
+        let mut child = Vault {
+            total_shares: 2_000_000,
+            ..Vault::default()
+        };
+        let mut vault_depositor = VaultDepositor::default();
+        vault_depositor.set_vault_shares(500_000);
+
+        // a quarter of the child's shares
+        assert_eq!(
+            ChildVaultPosition::calculate_value(&child, None, &vault_depositor, None, 4_000_000, 0)
+                .unwrap(),
+            1_000_000
+        );
+
+        // the child rebased 10x and the parent's depositor hasn't caught up
+        child.total_shares = 200_000;
+        child.shares_base = 1;
+        assert_eq!(
+            ChildVaultPosition::calculate_value(&child, None, &vault_depositor, None, 4_000_000, 0)
+                .unwrap(),
+            1_000_000
+        );
+
+        vault_depositor.set_vault_shares(50_000);
+        vault_depositor.set_vault_shares_base(1);
+        assert_eq!(
+            ChildVaultPosition::calculate_value(&child, None, &vault_depositor, None, 4_000_000, 0)
+                .unwrap(),
+            1_000_000
+        );
+    }
+    #[test]
+    fn test_child_vault_position_value_net_of_fees() {
+        let now = 1_000;
+        let child = Vault {
+            total_shares: 2_000_000,
+            user_shares: 2_000_000,
+            profit_share: 100_000, // 10%
+            exit_fee_bps: 100,     // 1%
+            last_fee_update_ts: now,
+            ..Vault::default()
+        };
+        let mut vault_depositor = VaultDepositor {
+            net_deposits: 500_000,
+            ..VaultDepositor::default()
+        };
+        vault_depositor.set_vault_shares(500_000);
+
+        // 500k of profit pays 50k profit share, then 1% of the remaining 950k exits
+        assert_eq!(
+            ChildVaultPosition::calculate_value(
+                &child,
+                None,
+                &vault_depositor,
+                None,
+                4_000_000,
+                now
+            )
+            .unwrap(),
+            940_500
+        );
+
+        // a pending request is paid at most its value at request time, and its profit share was already taken
+        vault_depositor.cumulative_profit_share_amount = 500_000;
+        vault_depositor.last_withdraw_request.shares = 250_000;
+        vault_depositor.last_withdraw_request.value = 400_000;
+        assert_eq!(
+            ChildVaultPosition::calculate_value(
+                &child,
+                None,
+                &vault_depositor,
+                None,
+                4_000_000,
+                now
+            )
+            .unwrap(),
+            891_000
+        );
+    }
+}
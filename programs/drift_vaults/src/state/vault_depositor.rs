@@ -4,7 +4,7 @@ use anchor_lang::prelude::*;
 use drift::controller::spot_balance::update_spot_balances;
 use drift::error::ErrorCode as DriftErrorCode;
 use drift::math::casting::Cast;
-use drift::math::constants::PERCENTAGE_PRECISION;
+use drift::math::constants::{ONE_YEAR, PERCENTAGE_PRECISION};
 use drift::math::insurance::{
     if_shares_to_vault_amount as depositor_shares_to_vault_amount,
     vault_amount_to_if_shares as vault_amount_to_depositor_shares,
@@ -19,12 +19,15 @@ use drift::state::user::User;
 use drift_macros::assert_no_slop;
 use static_assertions::const_assert_eq;
 
+use crate::constants::VAULT_DEAD_SHARES;
 use crate::error::ErrorCode;
 use crate::events::VaultDepositorAction;
-use crate::state::events::{VaultDepositorRecord, VaultDepositorV1Record};
+use crate::state::events::{
+    VaultDepositorFeeOverrideRecord, VaultDepositorRecord, VaultDepositorV1Record,
+};
 use crate::state::withdraw_request::WithdrawRequest;
 use crate::state::withdraw_unit::WithdrawUnit;
-use crate::state::{Vault, VaultDepositorBase, VaultFee, VaultProtocol};
+use crate::state::{ShareClass, Vault, VaultDepositorBase, VaultFee, VaultProtocol, VaultReferrer};
 use crate::validate;
 use crate::Size;
 
@@ -56,8 +59,25 @@ pub struct VaultDepositor {
     pub profit_share_fee_paid: u64,
     /// the exponent for vault_shares decimal places
     pub vault_shares_base: u32,
-    pub padding1: u32,
-    pub padding: [u64; 8],
+    /// The [`ShareClass`] id whose fee terms this depositor pays, 0 for the vault's default terms
+    pub share_class: u8,
+    /// Whether profit_share_override and management_fee_override apply to this depositor
+    pub fee_override: bool,
+    pub padding1: [u8; 2],
+    /// The [`VaultReferrer`] that onboarded this depositor, Pubkey::default() if none
+    pub referrer: Pubkey,
+    /// Last time fees charged per depositor (share class management fee, referrer's cut) were settled
+    pub last_fee_ts: i64,
+    /// Share-weighted average time the depositor's shares were deposited, used to decay the early exit fee
+    pub deposit_ts: i64,
+    /// The share class management fee as of the last settle: PERCENTAGE_PRECISION
+    pub class_management_fee: u32,
+    /// The share class profit share as of the last settle: PERCENTAGE_PRECISION
+    pub class_profit_share: u32,
+    /// Manager granted profit share, never above the vault or share class default: PERCENTAGE_PRECISION
+    pub profit_share_override: u32,
+    /// Manager granted annual management fee, never above the vault or share class default: PERCENTAGE_PRECISION
+    pub management_fee_override: u32,
 }
 
 impl Size for VaultDepositor {
@@ -111,6 +131,27 @@ impl VaultDepositorBase for VaultDepositor {
     fn set_profit_share_fee_paid(&mut self, amount: u64) {
         self.profit_share_fee_paid = amount;
     }
+
+    fn get_deposit_ts(&self) -> i64 {
+        self.deposit_ts
+    }
+    fn set_deposit_ts(&mut self, ts: i64) {
+        self.deposit_ts = ts;
+    }
+
+    fn get_manager_profit_share(&self, vault: &Vault) -> u32 {
+        let profit_share = if self.share_class != 0 {
+            self.class_profit_share
+        } else {
+            vault.profit_share
+        };
+
+        if self.fee_override {
+            profit_share.min(self.profit_share_override)
+        } else {
+            profit_share
+        }
+    }
 }
 
 impl VaultDepositor {
@@ -127,12 +168,38 @@ impl VaultDepositor {
             total_deposits: 0,
             total_withdraws: 0,
             cumulative_profit_share_amount: 0,
-            padding1: 0,
+            share_class: 0,
+            fee_override: false,
+            padding1: [0; 2],
             profit_share_fee_paid: 0,
-            padding: [0u64; 8],
+            referrer: Pubkey::default(),
+            last_fee_ts: now,
+            deposit_ts: now,
+            class_management_fee: 0,
+            class_profit_share: 0,
+            profit_share_override: 0,
+            management_fee_override: 0,
         }
     }
 
+    /// The depositor `payer` creates for `authority` in `deposit_for`. Anyone can create one for someone else in a
+    /// permissionless vault, but only the manager can let a depositor into a permissioned vault.
+    pub fn new_for(
+        vault: &Vault,
+        pubkey: Pubkey,
+        authority: Pubkey,
+        payer: &Pubkey,
+        now: i64,
+    ) -> Result<Self> {
+        validate!(
+            !vault.permissioned || vault.manager == *payer,
+            ErrorCode::PermissionedVault,
+            "Vault depositor can only be created by vault manager"
+        )?;
+
+        Ok(VaultDepositor::new(vault.pubkey, pubkey, authority, now))
+    }
+
     pub fn validate_base(&self, vault: &Vault) -> Result<()> {
         validate!(
             self.vault_shares_base == vault.shares_base,
@@ -203,7 +270,7 @@ impl VaultDepositor {
             let profit_u128 = profit.cast::<u128>()?;
 
             let manager_profit_share_amount = profit_u128
-                .safe_mul(vault.profit_share.cast()?)?
+                .safe_mul(self.get_manager_profit_share(vault).cast()?)?
                 .safe_div(PERCENTAGE_PRECISION)?;
             let protocol_profit_share_amount = match vault_protocol {
                 None => 0,
@@ -233,6 +300,12 @@ impl VaultDepositor {
         vault_protocol: &mut Option<RefMut<VaultProtocol>>,
         now: i64,
     ) -> Result<()> {
+        validate!(
+            !vault.shutdown,
+            ErrorCode::VaultShutdown,
+            "vault is shut down"
+        )?;
+
         validate!(
             vault.max_tokens == 0 || vault.max_tokens > vault_equity.safe_add(amount)?,
             ErrorCode::VaultIsAtCapacity,
@@ -277,7 +350,13 @@ impl VaultDepositor {
         let (manager_profit_share, protocol_profit_share) =
             self.apply_profit_share(vault_equity, vault, vault_protocol)?;
 
-        let n_shares = vault_amount_to_depositor_shares(amount, vault.total_shares, vault_equity)?;
+        let entry_fee = vault.calculate_entry_fee(amount)?;
+        let entry_fee_shares = vault.calculate_load_fee_shares(entry_fee, vault_equity)?;
+        let n_shares = vault_amount_to_depositor_shares(
+            amount.safe_sub(entry_fee)?,
+            vault.total_shares,
+            vault_equity,
+        )?;
 
         self.total_deposits = self.total_deposits.saturating_add(amount);
         self.net_deposits = self.net_deposits.safe_add(amount.cast()?)?;
@@ -285,11 +364,19 @@ impl VaultDepositor {
         vault.total_deposits = vault.total_deposits.saturating_add(amount);
         vault.net_deposits = vault.net_deposits.safe_add(amount.cast()?)?;
 
+        self.update_deposit_ts(n_shares, now)?;
         self.increase_vault_shares(n_shares, vault)?;
 
-        vault.total_shares = vault.total_shares.safe_add(n_shares)?;
+        vault.total_shares = vault
+            .total_shares
+            .safe_add(n_shares)?
+            .safe_add(entry_fee_shares)?;
         vault.user_shares = vault.user_shares.safe_add(n_shares)?;
 
+        if entry_fee_shares > 0 {
+            vault.manager_total_fee = vault.manager_total_fee.safe_add(entry_fee.cast()?)?;
+        }
+
         let vault_shares_after = self.checked_vault_shares(vault)?;
         let protocol_shares_after = vault.get_protocol_shares(vault_protocol);
 
@@ -312,6 +399,7 @@ impl VaultDepositor {
                     profit_share: manager_profit_share,
                     management_fee: management_fee_payment,
                     management_fee_shares,
+                    entry_exit_fee: entry_fee,
                 });
             }
             Some(_) => {
@@ -337,6 +425,7 @@ impl VaultDepositor {
                     management_fee_shares,
                     protocol_shares_before,
                     protocol_shares_after,
+                    entry_exit_fee: entry_fee,
                 });
             }
         }
@@ -413,6 +502,7 @@ impl VaultDepositor {
                     profit_share: manager_profit_share,
                     management_fee: management_fee_payment,
                     management_fee_shares,
+                    entry_exit_fee: 0,
                 });
             }
             Some(_) => {
@@ -438,6 +528,7 @@ impl VaultDepositor {
                     management_fee_shares,
                     protocol_shares_before,
                     protocol_shares_after,
+                    entry_exit_fee: 0,
                 });
             }
         }
@@ -502,6 +593,7 @@ impl VaultDepositor {
                     profit_share: 0,
                     management_fee: management_fee_payment,
                     management_fee_shares,
+                    entry_exit_fee: 0,
                 });
             }
             Some(_) => {
@@ -527,6 +619,7 @@ impl VaultDepositor {
                     management_fee_shares,
                     protocol_shares_before,
                     protocol_shares_after,
+                    entry_exit_fee: 0,
                 });
             }
         }
@@ -594,6 +687,13 @@ impl VaultDepositor {
             self.last_withdraw_request.shares
         );
 
+        let exit_fee = vault.calculate_exit_fee(withdraw_amount, self.deposit_ts, now)?;
+        let exit_fee_shares = vault
+            .calculate_load_fee_shares(exit_fee, vault_equity)?
+            .min(n_shares);
+        let withdraw_amount = withdraw_amount.safe_sub(exit_fee)?;
+        msg!("exit_fee={}, exit_fee_shares={}", exit_fee, exit_fee_shares);
+
         self.decrease_vault_shares(n_shares, vault)?;
 
         self.total_withdraws = self.total_withdraws.saturating_add(withdraw_amount);
@@ -601,8 +701,15 @@ impl VaultDepositor {
 
         vault.total_withdraws = vault.total_withdraws.saturating_add(withdraw_amount);
         vault.net_deposits = vault.net_deposits.safe_sub(withdraw_amount.cast()?)?;
-        vault.total_shares = vault.total_shares.safe_sub(n_shares)?;
+        // shares backing the exit fee stay outstanding as manager shares
+        vault.total_shares = vault
+            .total_shares
+            .safe_sub(n_shares.safe_sub(exit_fee_shares)?)?;
         vault.user_shares = vault.user_shares.safe_sub(n_shares)?;
+
+        if exit_fee_shares > 0 {
+            vault.manager_total_fee = vault.manager_total_fee.safe_add(exit_fee.cast()?)?;
+        }
         vault.total_withdraw_requested = vault
             .total_withdraw_requested
             .safe_sub(self.last_withdraw_request.value)?;
@@ -631,6 +738,7 @@ impl VaultDepositor {
                     profit_share: 0,
                     management_fee: management_fee_payment,
                     management_fee_shares,
+                    entry_exit_fee: exit_fee,
                 });
             }
             Some(_) => {
@@ -656,6 +764,7 @@ impl VaultDepositor {
                     management_fee_shares,
                     protocol_shares_before,
                     protocol_shares_after,
+                    entry_exit_fee: exit_fee,
                 });
             }
         }
@@ -679,6 +788,332 @@ impl VaultDepositor {
         VaultDepositorBase::apply_profit_share(self, vault_equity, vault, vault_protocol)
     }
 
+    pub fn has_referrer(&self) -> bool {
+        self.referrer != Pubkey::default()
+    }
+
+    pub fn validate_vault_referrer(
+        &self,
+        vault_referrer: &Option<AccountLoader<VaultReferrer>>,
+    ) -> Result<()> {
+        match vault_referrer {
+            None => {
+                validate!(
+                    !self.has_referrer(),
+                    ErrorCode::VaultReferrerMissing,
+                    "VaultReferrer {} missing in remaining accounts",
+                    self.referrer
+                )?;
+            }
+            Some(vault_referrer) => {
+                validate!(
+                    vault_referrer.key() == self.referrer,
+                    ErrorCode::VaultReferrerMissing,
+                    "VaultReferrer {} does not match depositor referrer {}",
+                    vault_referrer.key(),
+                    self.referrer
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Locks VAULT_DEAD_SHARES of the first deposit into an empty vault. They stay in `vault.user_shares` with no
+    /// depositor owning them.
+    pub fn lock_dead_shares(&mut self, vault: &Vault) -> Result<()> {
+        validate!(
+            self.checked_vault_shares(vault)? > VAULT_DEAD_SHARES,
+            ErrorCode::InvalidVaultDeposit,
+            "first deposit must mint more than {} shares",
+            VAULT_DEAD_SHARES
+        )?;
+
+        self.decrease_vault_shares(VAULT_DEAD_SHARES, vault)
+    }
+
+    pub fn check_can_close(&self) -> Result<()> {
+        validate!(
+            self.vault_shares == 0 && self.last_withdraw_request.shares == 0,
+            ErrorCode::CannotCloseAccount,
+            "vault depositor has {} shares and {} requested shares",
+            self.vault_shares,
+            self.last_withdraw_request.shares
+        )?;
+
+        Ok(())
+    }
+
+    /// This depositor's position moved to `authority` at `pubkey`. The shares, pending withdraw request, deposit
+    /// history, profit share high-water mark and share class carry over unchanged. The referrer and fee override were
+    /// granted to the old authority, so they don't.
+    pub fn with_authority(&self, pubkey: Pubkey, authority: Pubkey, now: i64) -> VaultDepositor {
+        VaultDepositor {
+            vault: self.vault,
+            pubkey,
+            authority,
+            vault_shares: self.vault_shares,
+            last_withdraw_request: self.last_withdraw_request,
+            last_valid_ts: now,
+            net_deposits: self.net_deposits,
+            total_deposits: self.total_deposits,
+            total_withdraws: self.total_withdraws,
+            cumulative_profit_share_amount: self.cumulative_profit_share_amount,
+            profit_share_fee_paid: self.profit_share_fee_paid,
+            vault_shares_base: self.vault_shares_base,
+            share_class: self.share_class,
+            fee_override: false,
+            padding1: [0; 2],
+            referrer: Pubkey::default(),
+            last_fee_ts: self.last_fee_ts,
+            deposit_ts: self.deposit_ts,
+            class_management_fee: self.class_management_fee,
+            class_profit_share: self.class_profit_share,
+            profit_share_override: 0,
+            management_fee_override: 0,
+        }
+    }
+
+    pub fn validate_share_class(
+        &self,
+        share_class: &Option<AccountLoader<ShareClass>>,
+    ) -> Result<()> {
+        match share_class {
+            None => {
+                validate!(
+                    self.share_class == 0,
+                    ErrorCode::InvalidShareClass,
+                    "ShareClass {} missing in remaining accounts",
+                    self.share_class
+                )?;
+            }
+            Some(share_class) => {
+                let share_class = share_class.load()?;
+                validate!(
+                    share_class.vault == self.vault && share_class.class_id == self.share_class,
+                    ErrorCode::InvalidShareClass,
+                    "ShareClass {} does not match depositor share class {}",
+                    share_class.class_id,
+                    self.share_class
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Settles the fees charged per depositor rather than vault wide since the last settle: the [`ShareClass`]
+    /// management fee, and the referrer's cut of the manager profit share and management fee this depositor paid.
+    /// Fees applied here are not charged again within the same timestamp.
+    #[allow(clippy::too_many_arguments)]
+    pub fn settle_fees(
+        &mut self,
+        share_class: Option<&mut ShareClass>,
+        vault_referrer: Option<&mut VaultReferrer>,
+        vault_equity: u64,
+        vault: &mut Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+        now: i64,
+    ) -> Result<()> {
+        if share_class.is_none() && vault_referrer.is_none() && !self.fee_override {
+            return Ok(());
+        }
+
+        self.apply_rebase(vault, vault_protocol, vault_equity)?;
+        vault.apply_fee(vault_protocol, vault_equity, now)?;
+
+        let vault_shares_before = self.vault_shares;
+        // the rate the vault wide fee was just minted at on every depositor's shares, class members included
+        let vault_management_fee = vault.get_management_fee(depositor_shares_to_vault_amount(
+            vault.user_shares,
+            vault.total_shares,
+            vault_equity,
+        )?)?;
+        let default_management_fee = match &share_class {
+            None => vault_management_fee,
+            Some(share_class) => {
+                self.class_management_fee = share_class.management_fee;
+                self.class_profit_share = share_class.profit_share;
+                self.class_management_fee.cast()?
+            }
+        };
+        let management_fee_rate = if self.fee_override {
+            default_management_fee.min(self.management_fee_override.cast()?)
+        } else {
+            default_management_fee
+        };
+
+        // profit share was already taken when the pending withdraw request was made
+        let manager_profit_share = if self.last_withdraw_request.pending() {
+            0
+        } else {
+            self.apply_profit_share(vault_equity, vault, vault_protocol)?
+                .0
+        };
+
+        // shares in a pending withdraw request stop paying fees, their value is capped at request time
+        let fee_paying_shares = if self.last_withdraw_request.pending() {
+            self.vault_shares
+                .saturating_sub(self.last_withdraw_request.shares)
+        } else {
+            self.vault_shares
+        };
+
+        let since_last = now.safe_sub(self.last_fee_ts)?.max(0);
+        let management_fee = self.calculate_management_fee(
+            management_fee_rate,
+            fee_paying_shares,
+            since_last,
+            vault,
+            vault_equity,
+        )?;
+        let vault_fee = self.calculate_management_fee(
+            vault_management_fee,
+            fee_paying_shares,
+            since_last,
+            vault,
+            vault_equity,
+        )?;
+        self.last_fee_ts = now;
+
+        // the vault wide fee counts towards the depositor's own fee: any shortfall moves the depositor's shares to
+        // the manager and any excess is rebated from the manager's shares, so classes never pay each other's fees
+        if management_fee > vault_fee {
+            let fee = management_fee.safe_sub(vault_fee)?;
+            let fee_shares =
+                vault_amount_to_depositor_shares(fee, vault.total_shares, vault_equity)?
+                    .min(fee_paying_shares);
+            self.decrease_vault_shares(fee_shares, vault)?;
+            vault.user_shares = vault.user_shares.safe_sub(fee_shares)?;
+            vault.manager_total_fee = vault.manager_total_fee.safe_add(fee.cast()?)?;
+        } else if management_fee < vault_fee {
+            let rebate = vault_fee.safe_sub(management_fee)?;
+            let rebate_shares =
+                vault_amount_to_depositor_shares(rebate, vault.total_shares, vault_equity)?
+                    .min(vault.get_manager_shares(vault_protocol)?);
+            self.increase_vault_shares(rebate_shares, vault)?;
+            vault.user_shares = vault.user_shares.safe_add(rebate_shares)?;
+            vault.manager_total_fee = vault.manager_total_fee.safe_sub(rebate.cast()?)?;
+        }
+
+        if let Some(share_class) = share_class {
+            share_class.apply_rebase(vault)?;
+            share_class.total_management_fee = share_class
+                .total_management_fee
+                .saturating_add(management_fee);
+            share_class.total_profit_share = share_class
+                .total_profit_share
+                .saturating_add(manager_profit_share);
+            share_class.update_user_shares(vault_shares_before, self.vault_shares)?;
+        }
+
+        if let Some(vault_referrer) = vault_referrer {
+            vault_referrer.accrue(
+                manager_profit_share,
+                management_fee,
+                self.authority,
+                vault_equity,
+                vault,
+                vault_protocol,
+                now,
+            )?;
+        }
+
+        Ok(())
+    }
+
+    /// The management fee at `management_fee_rate` on the equity of `fee_paying_shares` over `since_last` seconds.
+    fn calculate_management_fee(
+        &self,
+        management_fee_rate: i64,
+        fee_paying_shares: u128,
+        since_last: i64,
+        vault: &Vault,
+        vault_equity: u64,
+    ) -> Result<u64> {
+        if management_fee_rate <= 0 {
+            return Ok(0);
+        }
+
+        let depositor_equity =
+            depositor_shares_to_vault_amount(fee_paying_shares, vault.total_shares, vault_equity)?;
+
+        Ok(depositor_equity
+            .cast::<u128>()?
+            .safe_mul(management_fee_rate.cast()?)?
+            .safe_div(PERCENTAGE_PRECISION)?
+            .safe_mul(since_last.cast()?)?
+            .safe_div(ONE_YEAR)?
+            .cast()?)
+    }
+
+    /// Sets the fee terms the manager granted this depositor. Overrides can only lower fees below the vault or
+    /// share class defaults. Expects fees to be settled under the previous terms.
+    pub fn set_fee_override(
+        &mut self,
+        fee_override: bool,
+        profit_share_override: u32,
+        management_fee_override: u32,
+        vault: &Vault,
+        now: i64,
+    ) -> Result<()> {
+        let (default_profit_share, default_management_fee) = if self.share_class != 0 {
+            (
+                self.class_profit_share,
+                self.class_management_fee.cast::<i64>()?,
+            )
+        } else {
+            (vault.profit_share, vault.management_fee)
+        };
+
+        if fee_override {
+            validate!(
+                profit_share_override <= default_profit_share,
+                ErrorCode::InvalidFeeOverride,
+                "profit share override {} above default {}",
+                profit_share_override,
+                default_profit_share
+            )?;
+            validate!(
+                management_fee_override.cast::<i64>()? <= default_management_fee.max(0),
+                ErrorCode::InvalidFeeOverride,
+                "management fee override {} above default {}",
+                management_fee_override,
+                default_management_fee
+            )?;
+        }
+
+        let profit_share_override_before = self.profit_share_override;
+        let management_fee_override_before = self.management_fee_override;
+
+        self.fee_override = fee_override;
+        self.profit_share_override = if fee_override {
+            profit_share_override
+        } else {
+            0
+        };
+        self.management_fee_override = if fee_override {
+            management_fee_override
+        } else {
+            0
+        };
+        // fees up to now were settled under the previous terms
+        self.last_fee_ts = now;
+
+        emit!(VaultDepositorFeeOverrideRecord {
+            ts: now,
+            vault: vault.pubkey,
+            depositor_authority: self.authority,
+            fee_override,
+            profit_share_override_before,
+            profit_share_override_after: self.profit_share_override,
+            management_fee_override_before,
+            management_fee_override_after: self.management_fee_override,
+        });
+
+        Ok(())
+    }
+
     pub fn realize_profits(
         &mut self,
         vault_equity: u64,
@@ -722,6 +1157,7 @@ impl VaultDepositor {
                     profit_share: manager_profit_share,
                     management_fee: management_fee_payment,
                     management_fee_shares,
+                    entry_exit_fee: 0,
                 });
             }
             Some(_) => {
@@ -747,6 +1183,7 @@ impl VaultDepositor {
                     management_fee_shares,
                     protocol_shares_before,
                     protocol_shares_after,
+                    entry_exit_fee: 0,
                 });
             }
         }
@@ -0,0 +1,139 @@
+use crate::error::ErrorCode;
+use crate::{validate, Size};
+use static_assertions::const_assert_eq;
+
+use anchor_lang::prelude::*;
+use drift_macros::assert_no_slop;
+
+/// A pending transfer of a [`VaultDepositor`](crate::VaultDepositor) to a new authority. The new authority accepts it,
+/// which moves the depositor's position to a vault depositor it owns and closes the old one.
+#[assert_no_slop]
+#[account(zero_copy(unsafe))]
+#[derive(Default, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub struct VaultDepositorTransfer {
+    /// The vault depositor being transferred
+    pub vault_depositor: Pubkey,
+    /// The vault depositor transfer account's pubkey. It is a pda of vault_depositor
+    pub pubkey: Pubkey,
+    /// The authority that can accept the transfer
+    pub new_authority: Pubkey,
+    /// The bump for the vault depositor transfer pda
+    pub bump: u8,
+    pub padding1: [u8; 7],
+    pub padding: [u64; 4],
+}
+
+impl Size for VaultDepositorTransfer {
+    const SIZE: usize = 136 + 8;
+}
+
+const_assert_eq!(
+    VaultDepositorTransfer::SIZE,
+    std::mem::size_of::<VaultDepositorTransfer>() + 8
+);
+
+impl VaultDepositorTransfer {
+    pub fn new(
+        vault_depositor: Pubkey,
+        pubkey: Pubkey,
+        authority: Pubkey,
+        new_authority: Pubkey,
+        bump: u8,
+    ) -> Result<Self> {
+        validate!(
+            new_authority != Pubkey::default() && new_authority != authority,
+            ErrorCode::InvalidVaultDepositorTransfer,
+            "new authority must be set and differ from the current authority"
+        )?;
+
+        Ok(Self {
+            vault_depositor,
+            pubkey,
+            new_authority,
+            bump,
+            ..Self::default()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{VaultDepositor, VaultDepositorBase, VaultDepositorTransfer};
+    use anchor_lang::prelude::Pubkey;
+
+    #[test]
+    fn test_vault_depositor_transfer() {
+        let authority = Pubkey::new_unique();
+        let new_authority = Pubkey::new_unique();
+
+        assert!(VaultDepositorTransfer::new(
+            Pubkey::default(),
+            Pubkey::default(),
+            authority,
+            authority,
+            0
+        )
+        .is_err());
+        assert!(VaultDepositorTransfer::new(
+            Pubkey::default(),
+            Pubkey::default(),
+            authority,
+            Pubkey::default(),
+            0
+        )
+        .is_err());
+
+        let mut vault_depositor = VaultDepositor::default();
+        vault_depositor.vault = Pubkey::new_unique();
+        vault_depositor.authority = authority;
+        vault_depositor.net_deposits = 100;
+        vault_depositor.total_deposits = 150;
+        vault_depositor.total_withdraws = 50;
+        vault_depositor.cumulative_profit_share_amount = 20;
+        vault_depositor.profit_share_fee_paid = 4;
+        vault_depositor.set_vault_shares(1_000);
+        vault_depositor.share_class = 1;
+        vault_depositor.class_management_fee = 10_000;
+        vault_depositor.deposit_ts = 10;
+        vault_depositor.last_fee_ts = 30;
+        vault_depositor.referrer = Pubkey::new_unique();
+        vault_depositor.fee_override = true;
+        vault_depositor.profit_share_override = 50_000;
+        vault_depositor.management_fee_override = 5_000;
+
+        vault_depositor.last_withdraw_request.shares = 500;
+        vault_depositor.last_withdraw_request.value = 400;
+        vault_depositor.last_withdraw_request.ts = 35;
+
+        let new_pubkey = Pubkey::new_unique();
+        let transferred = vault_depositor.with_authority(new_pubkey, new_authority, 40);
+        assert_eq!(transferred.vault, vault_depositor.vault);
+        assert_eq!(transferred.pubkey, new_pubkey);
+        assert_eq!(transferred.authority, new_authority);
+        assert_eq!(transferred.last_valid_ts, 40);
+
+        // the position carries over
+        assert_eq!(transferred.get_vault_shares(), 1_000);
+        assert_eq!(transferred.net_deposits, 100);
+        assert_eq!(transferred.total_deposits, 150);
+        assert_eq!(transferred.total_withdraws, 50);
+        assert_eq!(transferred.cumulative_profit_share_amount, 20);
+        assert_eq!(transferred.profit_share_fee_paid, 4);
+        assert_eq!(transferred.share_class, 1);
+        assert_eq!(transferred.class_management_fee, 10_000);
+        assert_eq!(transferred.deposit_ts, 10);
+        assert_eq!(transferred.last_fee_ts, 30);
+        // a pending withdraw request moves with the shares
+        assert_eq!(
+            transferred.last_withdraw_request,
+            vault_depositor.last_withdraw_request
+        );
+
+        // what the old authority was granted doesn't
+        assert_eq!(transferred.referrer, Pubkey::default());
+        assert!(!transferred.fee_override);
+        assert_eq!(transferred.profit_share_override, 0);
+        assert_eq!(transferred.management_fee_override, 0);
+    }
+}
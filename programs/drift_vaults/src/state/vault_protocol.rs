@@ -38,7 +38,10 @@ pub struct VaultProtocol {
     pub protocol_profit_share: u32,
     pub bump: u8,
     pub version: u8,
-    pub padding: [u8; 2],
+    /// Whether protocol fee shares are paid out through [`crate::FeeRecipients`] instead of withdrawn by the protocol
+    pub fee_recipients: bool,
+    /// Bitmask of the [`crate::VaultPauseFlag`]s the protocol set. Only the protocol can clear them
+    pub protocol_paused: u8,
 }
 
 impl Size for VaultProtocol {
@@ -0,0 +1,437 @@
+use std::cell::RefMut;
+
+use crate::error::ErrorCode;
+use crate::events::{VaultDepositorAction, VaultReferrerRecord};
+use crate::state::vault::Vault;
+use crate::state::withdraw_request::WithdrawRequest;
+use crate::{validate, Size, VaultDepositor, VaultProtocol, WithdrawUnit};
+use static_assertions::const_assert_eq;
+
+use anchor_lang::prelude::*;
+use drift::math::casting::Cast;
+use drift::math::constants::PERCENTAGE_PRECISION;
+use drift::math::insurance::{
+    if_shares_to_vault_amount as depositor_shares_to_vault_amount,
+    vault_amount_to_if_shares as vault_amount_to_depositor_shares,
+};
+use drift::math::safe_math::SafeMath;
+use drift_macros::assert_no_slop;
+
+/// A partner that onboarded depositors to a [`Vault`]. The referrer earns `vault.referral_fee_share` of the
+/// manager profit share and management fee paid by the depositors it referred, held as shares the same way
+/// `VaultProtocol.protocol_profit_and_fee_shares` are.
+#[assert_no_slop]
+#[account(zero_copy(unsafe))]
+#[derive(Default, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub struct VaultReferrer {
+    /// The vault the referrer earns fees from
+    pub vault: Pubkey,
+    /// The vault referrer account's pubkey. It is a pda of vault and authority
+    pub pubkey: Pubkey,
+    /// The authority is the address w permission to withdraw the referrer's shares
+    pub authority: Pubkey,
+    /// The shares from profit share and management fee unclaimed by the referrer
+    pub referrer_profit_and_fee_shares: u128,
+    pub last_withdraw_request: WithdrawRequest,
+    /// Total profit share earned by the referrer
+    pub total_profit_share: u64,
+    /// Total management fee earned by the referrer
+    pub total_fee: u64,
+    /// Total withdraws for the referrer
+    pub total_withdraws: u64,
+    /// The exponent for referrer_profit_and_fee_shares decimal places
+    pub vault_shares_base: u32,
+    /// The bump for the vault referrer pda
+    pub bump: u8,
+    pub padding1: [u8; 3],
+    pub padding: [u64; 4],
+}
+
+impl Size for VaultReferrer {
+    const SIZE: usize = 208 + 8;
+}
+
+const_assert_eq!(
+    VaultReferrer::SIZE,
+    std::mem::size_of::<VaultReferrer>() + 8
+);
+
+impl VaultReferrer {
+    pub fn new(
+        vault: Pubkey,
+        pubkey: Pubkey,
+        authority: Pubkey,
+        shares_base: u32,
+        bump: u8,
+    ) -> Self {
+        Self {
+            vault,
+            pubkey,
+            authority,
+            referrer_profit_and_fee_shares: 0,
+            last_withdraw_request: WithdrawRequest::default(),
+            total_profit_share: 0,
+            total_fee: 0,
+            total_withdraws: 0,
+            vault_shares_base: shares_base,
+            bump,
+            padding1: [0; 3],
+            padding: [0; 4],
+        }
+    }
+
+    pub fn apply_rebase(&mut self, vault: &Vault) -> Result<()> {
+        if vault.shares_base != self.vault_shares_base {
+            validate!(
+                vault.shares_base > self.vault_shares_base,
+                ErrorCode::InvalidVaultRebase,
+                "Rebase expo out of bounds"
+            )?;
+
+            let expo_diff = (vault.shares_base - self.vault_shares_base).cast::<u32>()?;
+            let rebase_divisor = 10_u128.pow(expo_diff);
+
+            msg!(
+                "rebasing vault referrer: base: {} -> {} ",
+                self.vault_shares_base,
+                vault.shares_base,
+            );
+
+            self.referrer_profit_and_fee_shares = self
+                .referrer_profit_and_fee_shares
+                .safe_div(rebase_divisor)?;
+            self.last_withdraw_request.rebase(rebase_divisor)?;
+            self.vault_shares_base = vault.shares_base;
+        }
+
+        Ok(())
+    }
+
+    /// Moves the referrer's cut of `manager_profit_share` and `management_fee` from the manager's shares to the
+    /// referrer. Expects the vault to be rebased and fees applied.
+    #[allow(clippy::too_many_arguments)]
+    pub fn accrue(
+        &mut self,
+        manager_profit_share: u64,
+        management_fee: u64,
+        depositor_authority: Pubkey,
+        vault_equity: u64,
+        vault: &mut Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+        now: i64,
+    ) -> Result<u128> {
+        self.apply_rebase(vault)?;
+
+        let referral_profit_share: u64 = manager_profit_share
+            .cast::<u128>()?
+            .safe_mul(vault.referral_fee_share.cast()?)?
+            .safe_div(PERCENTAGE_PRECISION)?
+            .cast()?;
+        let referral_fee: u64 = management_fee
+            .cast::<u128>()?
+            .safe_mul(vault.referral_fee_share.cast()?)?
+            .safe_div(PERCENTAGE_PRECISION)?
+            .cast()?;
+
+        let amount = referral_profit_share.safe_add(referral_fee)?;
+        if amount == 0 {
+            return Ok(0);
+        }
+
+        // the referrer can never take more than the manager has accrued
+        let n_shares = vault_amount_to_depositor_shares(amount, vault.total_shares, vault_equity)?
+            .min(vault.get_manager_shares(vault_protocol)?);
+
+        let referrer_shares_before = self.referrer_profit_and_fee_shares;
+
+        vault.referrer_shares = vault.referrer_shares.safe_add(n_shares)?;
+        self.referrer_profit_and_fee_shares =
+            self.referrer_profit_and_fee_shares.safe_add(n_shares)?;
+        self.total_profit_share = self
+            .total_profit_share
+            .saturating_add(referral_profit_share);
+        self.total_fee = self.total_fee.saturating_add(referral_fee);
+
+        emit!(VaultReferrerRecord {
+            ts: now,
+            vault: vault.pubkey,
+            vault_referrer: self.pubkey,
+            depositor_authority,
+            action: VaultDepositorAction::FeePayment,
+            amount,
+            vault_equity_before: vault_equity,
+            referrer_shares_before,
+            referrer_shares_after: self.referrer_profit_and_fee_shares,
+            total_vault_shares_after: vault.total_shares,
+            profit_share: referral_profit_share,
+            management_fee: referral_fee,
+        });
+
+        Ok(n_shares)
+    }
+
+    pub fn request_withdraw(
+        &mut self,
+        withdraw_amount: u64,
+        withdraw_unit: WithdrawUnit,
+        vault_equity: u64,
+        vault: &mut Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+        now: i64,
+    ) -> Result<()> {
+        let rebase_divisor = vault.apply_rebase(vault_protocol, vault_equity)?;
+        vault.apply_fee(vault_protocol, vault_equity, now)?;
+        self.apply_rebase(vault)?;
+
+        let referrer_shares_before = self.referrer_profit_and_fee_shares;
+
+        let (withdraw_value, n_shares) = withdraw_unit.get_withdraw_value_and_shares(
+            withdraw_amount,
+            vault_equity,
+            referrer_shares_before,
+            vault.total_shares,
+            rebase_divisor,
+        )?;
+
+        validate!(
+            n_shares > 0,
+            ErrorCode::InvalidVaultWithdrawSize,
+            "Requested n_shares = 0"
+        )?;
+
+        self.last_withdraw_request.set(
+            referrer_shares_before,
+            n_shares,
+            withdraw_value,
+            vault_equity,
+            now,
+        )?;
+        vault.total_withdraw_requested = vault.total_withdraw_requested.safe_add(withdraw_value)?;
+
+        emit!(VaultReferrerRecord {
+            ts: now,
+            vault: vault.pubkey,
+            vault_referrer: self.pubkey,
+            depositor_authority: self.authority,
+            action: VaultDepositorAction::WithdrawRequest,
+            amount: self.last_withdraw_request.value,
+            vault_equity_before: vault_equity,
+            referrer_shares_before,
+            referrer_shares_after: self.referrer_profit_and_fee_shares,
+            total_vault_shares_after: vault.total_shares,
+            profit_share: 0,
+            management_fee: 0,
+        });
+
+        Ok(())
+    }
+
+    pub fn cancel_withdraw_request(
+        &mut self,
+        vault_equity: u64,
+        vault: &mut Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+        now: i64,
+    ) -> Result<()> {
+        vault.apply_rebase(vault_protocol, vault_equity)?;
+        vault.apply_fee(vault_protocol, vault_equity, now)?;
+        self.apply_rebase(vault)?;
+
+        let referrer_shares_before = self.referrer_profit_and_fee_shares;
+
+        let vault_shares_lost = self
+            .last_withdraw_request
+            .calculate_shares_lost(vault, vault_equity)?;
+
+        self.referrer_profit_and_fee_shares = self
+            .referrer_profit_and_fee_shares
+            .safe_sub(vault_shares_lost)?;
+        vault.referrer_shares = vault.referrer_shares.safe_sub(vault_shares_lost)?;
+        vault.total_shares = vault.total_shares.safe_sub(vault_shares_lost)?;
+
+        vault.total_withdraw_requested = vault
+            .total_withdraw_requested
+            .safe_sub(self.last_withdraw_request.value)?;
+        self.last_withdraw_request.reset(now)?;
+
+        emit!(VaultReferrerRecord {
+            ts: now,
+            vault: vault.pubkey,
+            vault_referrer: self.pubkey,
+            depositor_authority: self.authority,
+            action: VaultDepositorAction::CancelWithdrawRequest,
+            amount: 0,
+            vault_equity_before: vault_equity,
+            referrer_shares_before,
+            referrer_shares_after: self.referrer_profit_and_fee_shares,
+            total_vault_shares_after: vault.total_shares,
+            profit_share: 0,
+            management_fee: 0,
+        });
+
+        Ok(())
+    }
+
+    pub fn withdraw(
+        &mut self,
+        vault_equity: u64,
+        vault: &mut Vault,
+        vault_protocol: &mut Option<RefMut<VaultProtocol>>,
+        now: i64,
+    ) -> Result<u64> {
+        self.last_withdraw_request
+            .check_redeem_period_finished(vault, now)?;
+
+        vault.apply_rebase(vault_protocol, vault_equity)?;
+        vault.apply_fee(vault_protocol, vault_equity, now)?;
+        self.apply_rebase(vault)?;
+
+        let referrer_shares_before = self.referrer_profit_and_fee_shares;
+        let n_shares = self.last_withdraw_request.shares;
+
+        validate!(
+            n_shares > 0,
+            ErrorCode::InvalidVaultWithdraw,
+            "Must submit withdraw request and wait the redeem_period ({} seconds)",
+            vault.redeem_period
+        )?;
+
+        validate!(
+            referrer_shares_before >= n_shares,
+            ErrorCode::InsufficientVaultShares,
+            "referrer_shares_before={} < n_shares={}",
+            referrer_shares_before,
+            n_shares
+        )?;
+
+        let amount: u64 =
+            depositor_shares_to_vault_amount(n_shares, vault.total_shares, vault_equity)?;
+        let n_tokens = amount.min(self.last_withdraw_request.value);
+
+        self.referrer_profit_and_fee_shares =
+            self.referrer_profit_and_fee_shares.safe_sub(n_shares)?;
+        self.total_withdraws = self.total_withdraws.saturating_add(n_tokens);
+
+        vault.referrer_shares = vault.referrer_shares.safe_sub(n_shares)?;
+        vault.total_shares = vault.total_shares.safe_sub(n_shares)?;
+        vault.total_withdraws = vault.total_withdraws.saturating_add(n_tokens);
+        vault.net_deposits = vault.net_deposits.safe_sub(n_tokens.cast()?)?;
+        vault.total_withdraw_requested = vault
+            .total_withdraw_requested
+            .safe_sub(self.last_withdraw_request.value)?;
+
+        self.last_withdraw_request.reset(now)?;
+
+        emit!(VaultReferrerRecord {
+            ts: now,
+            vault: vault.pubkey,
+            vault_referrer: self.pubkey,
+            depositor_authority: self.authority,
+            action: VaultDepositorAction::Withdraw,
+            amount: n_tokens,
+            vault_equity_before: vault_equity,
+            referrer_shares_before,
+            referrer_shares_after: self.referrer_profit_and_fee_shares,
+            total_vault_shares_after: vault.total_shares,
+            profit_share: 0,
+            management_fee: 0,
+        });
+
+        Ok(n_tokens)
+    }
+}
+
+pub trait VaultReferrerProvider<'a> {
+    /// The depositor's [`VaultReferrer`], if it was referred and the referrer is anywhere in the remaining accounts
+    fn vault_referrer(
+        &self,
+        vault_depositor: &VaultDepositor,
+    ) -> Option<AccountLoader<'a, VaultReferrer>>;
+}
+
+impl<'a: 'info, 'info, T: anchor_lang::Bumps> VaultReferrerProvider<'a>
+    for Context<'_, '_, 'a, 'info, T>
+{
+    fn vault_referrer(
+        &self,
+        vault_depositor: &VaultDepositor,
+    ) -> Option<AccountLoader<'a, VaultReferrer>> {
+        if !vault_depositor.has_referrer() {
+            return None;
+        }
+
+        let account_info = self
+            .remaining_accounts
+            .iter()
+            .find(|account_info| account_info.key() == vault_depositor.referrer)?;
+        AccountLoader::<'a, VaultReferrer>::try_from(account_info).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Vault, VaultDepositor, VaultDepositorBase, VaultReferrer, WithdrawUnit};
+    use anchor_lang::prelude::Pubkey;
+    use drift::math::constants::QUOTE_PRECISION_U64;
+
+    #[test]
+    fn test_referrer_earns_manager_profit_share() {
+        let now = 0;
+        let mut vault = Vault::default();
+        let mut vp = None;
+        vault.profit_share = 100_000; // 10%
+        vault.referral_fee_share = 500_000; // 50% of the manager's cut
+
+        let mut vr = VaultReferrer::new(
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            0,
+            0,
+        );
+        let vd =
+            &mut VaultDepositor::new(Pubkey::default(), Pubkey::default(), Pubkey::default(), now);
+        vd.referrer = vr.pubkey;
+
+        let amount = 100 * QUOTE_PRECISION_U64;
+        vd.deposit(amount, 0, &mut vault, &mut vp, now).unwrap();
+        assert_eq!(vault.user_shares, amount as u128);
+        assert_eq!(vault.total_shares, amount as u128);
+
+        // vault doubles, depositor owes $10 profit share to the manager
+        let vault_equity = 2 * amount;
+        vd.settle_fees(None, Some(&mut vr), vault_equity, &mut vault, &mut vp, now)
+            .unwrap();
+
+        assert_eq!(vd.get_profit_share_fee_paid(), 10 * QUOTE_PRECISION_U64);
+        assert_eq!(vr.total_profit_share, 5 * QUOTE_PRECISION_U64);
+        assert_eq!(vr.referrer_profit_and_fee_shares, 2_500_000);
+        assert_eq!(vault.referrer_shares, 2_500_000);
+        assert_eq!(vault.get_manager_shares(&mut vp).unwrap(), 2_500_000);
+
+        // accruing again at the same price is a noop
+        vd.settle_fees(None, Some(&mut vr), vault_equity, &mut vault, &mut vp, now)
+            .unwrap();
+        assert_eq!(vault.referrer_shares, 2_500_000);
+
+        vr.request_withdraw(
+            2_500_000,
+            WithdrawUnit::Shares,
+            vault_equity,
+            &mut vault,
+            &mut vp,
+            now,
+        )
+        .unwrap();
+        assert_eq!(vault.total_withdraw_requested, 5 * QUOTE_PRECISION_U64);
+
+        let withdraw_amount = vr.withdraw(vault_equity, &mut vault, &mut vp, now).unwrap();
+        assert_eq!(withdraw_amount, 5 * QUOTE_PRECISION_U64);
+        assert_eq!(vr.referrer_profit_and_fee_shares, 0);
+        assert_eq!(vault.referrer_shares, 0);
+        assert_eq!(vault.total_withdraw_requested, 0);
+        assert_eq!(vault.total_shares, 97_500_000);
+        assert_eq!(vault.get_manager_shares(&mut vp).unwrap(), 2_500_000);
+    }
+}
@@ -0,0 +1,78 @@
+use crate::state::Vault;
+use crate::Size;
+use static_assertions::const_assert_eq;
+
+use anchor_lang::prelude::*;
+use drift_macros::assert_no_slop;
+
+/// Keys a [`Vault`] manager grants narrow powers to so the manager key can stay cold. The trader role is the vault's
+/// existing `delegate`. Unset roles are the default pubkey. The manager keeps every power it grants.
+#[assert_no_slop]
+#[account(zero_copy(unsafe))]
+#[derive(Default, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub struct VaultRoles {
+    /// The vault the roles belong to
+    pub vault: Pubkey,
+    /// The vault roles account's pubkey. It is a pda of vault
+    pub pubkey: Pubkey,
+    /// Can pause the vault and toggle the drift user's reduce-only flag
+    pub risk_officer: Pubkey,
+    /// Can run the profit share crank
+    pub keeper: Pubkey,
+    /// Can force withdraw depositors
+    pub operator: Pubkey,
+    /// The bump for the vault roles pda
+    pub bump: u8,
+    pub padding1: [u8; 7],
+    pub padding: [u64; 4],
+}
+
+impl Size for VaultRoles {
+    const SIZE: usize = 200 + 8;
+}
+
+const_assert_eq!(VaultRoles::SIZE, std::mem::size_of::<VaultRoles>() + 8);
+
+impl VaultRoles {
+    pub fn new(
+        vault: Pubkey,
+        pubkey: Pubkey,
+        risk_officer: Pubkey,
+        keeper: Pubkey,
+        operator: Pubkey,
+        bump: u8,
+    ) -> Self {
+        Self {
+            vault,
+            pubkey,
+            risk_officer,
+            keeper,
+            operator,
+            bump,
+            padding1: [0; 7],
+            padding: [0; 4],
+        }
+    }
+}
+
+pub trait VaultRolesProvider<'a> {
+    /// The vault's [`VaultRoles`], if it is anywhere in the remaining accounts
+    fn vault_roles(&self, vault: &Vault) -> Option<AccountLoader<'a, VaultRoles>>;
+}
+
+impl<'a: 'info, 'info, T: anchor_lang::Bumps> VaultRolesProvider<'a>
+    for Context<'_, '_, 'a, 'info, T>
+{
+    fn vault_roles(&self, vault: &Vault) -> Option<AccountLoader<'a, VaultRoles>> {
+        self.remaining_accounts
+            .iter()
+            .filter_map(|account_info| AccountLoader::<'a, VaultRoles>::try_from(account_info).ok())
+            .find(|account| {
+                account
+                    .load()
+                    .map(|account| account.vault == vault.pubkey)
+                    .unwrap_or(false)
+            })
+    }
+}
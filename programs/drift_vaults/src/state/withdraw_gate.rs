@@ -0,0 +1,337 @@
+use crate::constants::BPS_PRECISION;
+use crate::error::ErrorCode;
+use crate::events::WithdrawGateRecord;
+use crate::state::withdraw_request::WithdrawRequest;
+use crate::state::Vault;
+use crate::{validate, Size};
+use static_assertions::const_assert_eq;
+
+use anchor_lang::prelude::*;
+use drift::math::casting::Cast;
+use drift::math::safe_math::SafeMath;
+use drift_macros::assert_no_slop;
+
+/// Caps the depositor withdraws a [`Vault`] settles per epoch to `gate_bps` of the vault equity at the start of the
+/// epoch. When the requests outstanding at the start of the epoch exceed the gate, each is filled pro rata and the
+/// remainder stays pending into the next epoch.
+#[assert_no_slop]
+#[account(zero_copy(unsafe))]
+#[derive(Default, Eq, PartialEq, Debug)]
+#[repr(C)]
+pub struct WithdrawGate {
+    /// The vault the withdraw gate belongs to
+    pub vault: Pubkey,
+    /// The withdraw gate account's pubkey. It is a pda of vault
+    pub pubkey: Pubkey,
+    /// The length of an epoch in seconds. Must be at least the vault redeem_period
+    pub epoch_duration: i64,
+    /// When the current epoch started. Zero until the first gated withdraw
+    pub epoch_start_ts: i64,
+    /// The most that can be withdrawn in the current epoch
+    pub epoch_capacity: u64,
+    /// The amount withdrawn in the current epoch
+    pub epoch_withdrawn: u64,
+    /// The vault total_withdraw_requested at the start of the current epoch
+    pub epoch_requested: u64,
+    /// The share of vault equity that can be withdrawn each epoch, in bps
+    pub gate_bps: u16,
+    /// The bump for the withdraw gate pda
+    pub bump: u8,
+    pub padding1: [u8; 5],
+    pub padding: [u64; 4],
+}
+
+impl Size for WithdrawGate {
+    const SIZE: usize = 144 + 8;
+}
+
+const_assert_eq!(WithdrawGate::SIZE, std::mem::size_of::<WithdrawGate>() + 8);
+
+impl WithdrawGate {
+    pub fn new(
+        vault: Pubkey,
+        pubkey: Pubkey,
+        gate_bps: u16,
+        epoch_duration: i64,
+        bump: u8,
+    ) -> Self {
+        Self {
+            vault,
+            pubkey,
+            epoch_duration,
+            epoch_start_ts: 0,
+            epoch_capacity: 0,
+            epoch_withdrawn: 0,
+            epoch_requested: 0,
+            gate_bps,
+            bump,
+            padding1: [0; 5],
+            padding: [0; 4],
+        }
+    }
+
+    pub fn validate_params(gate_bps: u16, epoch_duration: i64, vault: &Vault) -> Result<()> {
+        validate!(
+            gate_bps > 0 && gate_bps.cast::<u64>()? <= BPS_PRECISION,
+            ErrorCode::InvalidWithdrawGate,
+            "gate must be between 1 and {} bps",
+            BPS_PRECISION
+        )?;
+
+        // a partially filled request is restamped with the epoch start, so it must be redeemable by the next epoch
+        validate!(
+            epoch_duration > 0 && epoch_duration >= vault.redeem_period,
+            ErrorCode::InvalidWithdrawGate,
+            "epoch duration must be at least the redeem period ({} seconds)",
+            vault.redeem_period
+        )?;
+
+        Ok(())
+    }
+
+    /// Starts a new epoch if the current one has ended. Epochs stay aligned to the first epoch start.
+    pub fn update_epoch(&mut self, vault: &Vault, vault_equity: u64, now: i64) -> Result<()> {
+        if self.epoch_start_ts != 0 && now < self.epoch_start_ts.safe_add(self.epoch_duration)? {
+            return Ok(());
+        }
+
+        self.epoch_start_ts = if self.epoch_start_ts == 0 {
+            now
+        } else {
+            let epochs_elapsed = now
+                .safe_sub(self.epoch_start_ts)?
+                .safe_div(self.epoch_duration)?;
+            self.epoch_start_ts
+                .safe_add(epochs_elapsed.safe_mul(self.epoch_duration)?)?
+        };
+        self.epoch_capacity = vault_equity
+            .cast::<u128>()?
+            .safe_mul(self.gate_bps.cast()?)?
+            .safe_div(BPS_PRECISION.cast()?)?
+            .cast()?;
+        self.epoch_withdrawn = 0;
+        self.epoch_requested = vault.total_withdraw_requested;
+
+        Ok(())
+    }
+
+    /// Shrinks `request` to the part that can be filled this epoch and returns the remainder, if any.
+    pub fn split_request(
+        &mut self,
+        request: &mut WithdrawRequest,
+        vault: &Vault,
+        vault_equity: u64,
+        now: i64,
+    ) -> Result<Option<WithdrawRequest>> {
+        self.update_epoch(vault, vault_equity, now)?;
+
+        if request.value == 0 {
+            return Ok(None);
+        }
+
+        // a request partially filled this epoch was restamped with the epoch start and waits for the next one
+        let mut fill_value = if request.ts == self.epoch_start_ts {
+            0
+        } else {
+            request.value
+        };
+
+        if self.epoch_requested > self.epoch_capacity {
+            fill_value = fill_value
+                .cast::<u128>()?
+                .safe_mul(self.epoch_capacity.cast()?)?
+                .safe_div(self.epoch_requested.cast()?)?
+                .cast()?;
+        }
+
+        fill_value = fill_value.min(self.epoch_capacity.saturating_sub(self.epoch_withdrawn));
+
+        let fill_shares = request
+            .shares
+            .safe_mul(fill_value.cast()?)?
+            .safe_div(request.value.cast()?)?;
+
+        validate!(
+            fill_shares > 0,
+            ErrorCode::WithdrawGated,
+            "withdraw gate reached for epoch starting {}, request stays pending until {}",
+            self.epoch_start_ts,
+            self.epoch_start_ts.safe_add(self.epoch_duration)?
+        )?;
+
+        if fill_value == request.value {
+            return Ok(None);
+        }
+
+        let remaining = WithdrawRequest {
+            shares: request.shares.safe_sub(fill_shares)?,
+            value: request.value.safe_sub(fill_value)?,
+            ts: self.epoch_start_ts,
+        };
+
+        request.shares = fill_shares;
+        request.value = fill_value;
+
+        Ok(Some(remaining))
+    }
+
+    /// Records a gated withdraw and puts the unfilled part of the request back as pending. The withdraw only took
+    /// the filled value out of `vault.total_withdraw_requested`, so the remainder is still counted there.
+    pub fn record_withdraw(
+        &mut self,
+        authority: Pubkey,
+        withdraw_amount: u64,
+        remaining: Option<WithdrawRequest>,
+        request: &mut WithdrawRequest,
+        now: i64,
+    ) -> Result<()> {
+        self.epoch_withdrawn = self.epoch_withdrawn.safe_add(withdraw_amount)?;
+
+        if let Some(remaining) = remaining {
+            *request = remaining;
+
+            emit!(WithdrawGateRecord {
+                ts: now,
+                vault: self.vault,
+                depositor_authority: authority,
+                epoch_start_ts: self.epoch_start_ts,
+                epoch_capacity: self.epoch_capacity,
+                epoch_withdrawn: self.epoch_withdrawn,
+                amount: withdraw_amount,
+                remaining_shares: remaining.shares,
+                remaining_value: remaining.value,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+pub trait WithdrawGateProvider<'a> {
+    /// The vault's [`WithdrawGate`], if it is anywhere in the remaining accounts
+    fn withdraw_gate(&self, vault: &Vault) -> Option<AccountLoader<'a, WithdrawGate>>;
+}
+
+impl<'a: 'info, 'info, T: anchor_lang::Bumps> WithdrawGateProvider<'a>
+    for Context<'_, '_, 'a, 'info, T>
+{
+    fn withdraw_gate(&self, vault: &Vault) -> Option<AccountLoader<'a, WithdrawGate>> {
+        self.remaining_accounts
+            .iter()
+            .filter_map(|account_info| {
+                AccountLoader::<'a, WithdrawGate>::try_from(account_info).ok()
+            })
+            .find(|gate| {
+                gate.load()
+                    .map(|gate| gate.vault == vault.pubkey)
+                    .unwrap_or(false)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Vault, VaultDepositor, WithdrawGate, WithdrawUnit};
+    use anchor_lang::prelude::Pubkey;
+    use drift::math::constants::QUOTE_PRECISION_U64;
+
+    #[test]
+    fn test_withdraw_gate_fills_requests_pro_rata() {
+        let mut now = 1000;
+        let mut vault = Vault::default();
+        let mut vp = None;
+        let mut gate = WithdrawGate::new(Pubkey::default(), Pubkey::default(), 1_000, 100, 0);
+
+        let vd_a =
+            &mut VaultDepositor::new(Pubkey::default(), Pubkey::default(), Pubkey::default(), now);
+        let vd_b =
+            &mut VaultDepositor::new(Pubkey::default(), Pubkey::default(), Pubkey::default(), now);
+
+        let amount = 100 * QUOTE_PRECISION_U64;
+        vd_a.deposit(amount, 0, &mut vault, &mut vp, now).unwrap();
+        vd_b.deposit(amount, amount, &mut vault, &mut vp, now)
+            .unwrap();
+        let mut vault_equity = 2 * amount;
+
+        vd_a.request_withdraw(
+            40 * QUOTE_PRECISION_U64,
+            WithdrawUnit::Token,
+            vault_equity,
+            &mut vault,
+            &mut vp,
+            now,
+        )
+        .unwrap();
+        vd_b.request_withdraw(
+            10 * QUOTE_PRECISION_U64,
+            WithdrawUnit::Token,
+            vault_equity,
+            &mut vault,
+            &mut vp,
+            now,
+        )
+        .unwrap();
+        assert_eq!(vault.total_withdraw_requested, 50 * QUOTE_PRECISION_U64);
+
+        // $50 requested against a $20 gate fills 40% of each request
+        now += 10;
+        let remaining = gate
+            .split_request(&mut vd_a.last_withdraw_request, &vault, vault_equity, now)
+            .unwrap();
+        assert_eq!(gate.epoch_capacity, 20 * QUOTE_PRECISION_U64);
+        assert_eq!(vd_a.last_withdraw_request.value, 16 * QUOTE_PRECISION_U64);
+        let (withdraw_amount, _) = vd_a
+            .withdraw(vault_equity, &mut vault, &mut vp, now)
+            .unwrap();
+        assert_eq!(withdraw_amount, 16 * QUOTE_PRECISION_U64);
+        gate.record_withdraw(
+            Pubkey::default(),
+            withdraw_amount,
+            remaining,
+            &mut vd_a.last_withdraw_request,
+            now,
+        )
+        .unwrap();
+        vault_equity -= withdraw_amount;
+        assert_eq!(vd_a.last_withdraw_request.value, 24 * QUOTE_PRECISION_U64);
+        assert_eq!(vd_a.last_withdraw_request.ts, gate.epoch_start_ts);
+        assert_eq!(vault.total_withdraw_requested, 34 * QUOTE_PRECISION_U64);
+
+        // the remainder waits for the next epoch
+        assert!(gate
+            .split_request(&mut vd_a.last_withdraw_request, &vault, vault_equity, now)
+            .is_err());
+
+        let remaining = gate
+            .split_request(&mut vd_b.last_withdraw_request, &vault, vault_equity, now)
+            .unwrap();
+        assert_eq!(vd_b.last_withdraw_request.value, 4 * QUOTE_PRECISION_U64);
+        let (withdraw_amount, _) = vd_b
+            .withdraw(vault_equity, &mut vault, &mut vp, now)
+            .unwrap();
+        gate.record_withdraw(
+            Pubkey::default(),
+            withdraw_amount,
+            remaining,
+            &mut vd_b.last_withdraw_request,
+            now,
+        )
+        .unwrap();
+        vault_equity -= withdraw_amount;
+        assert_eq!(gate.epoch_withdrawn, gate.epoch_capacity);
+        assert_eq!(vault.total_withdraw_requested, 30 * QUOTE_PRECISION_U64);
+
+        // next epoch: $30 pending against an $18 gate fills 60% of each remainder
+        now += 100;
+        let remaining = gate
+            .split_request(&mut vd_a.last_withdraw_request, &vault, vault_equity, now)
+            .unwrap();
+        assert_eq!(gate.epoch_start_ts, 1110);
+        assert_eq!(gate.epoch_capacity, 18 * QUOTE_PRECISION_U64);
+        assert_eq!(
+            vd_a.last_withdraw_request.value,
+            14_400_000 // $24 * 60%
+        );
+        assert_eq!(remaining.unwrap().value, 9_600_000);
+    }
+}
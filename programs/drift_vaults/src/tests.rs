@@ -1,11 +1,16 @@
 #[cfg(test)]
 mod vault_fcn {
+    use crate::constants::MAX_WITHDRAW_PAUSE;
     use crate::state::traits::VaultDepositorBase;
     use crate::withdraw_request::WithdrawRequest;
-    use crate::{Vault, VaultDepositor, WithdrawUnit};
+    use crate::{
+        TokenizedVaultDepositor, TokenizedWithdrawRequest, Vault, VaultDepositor, VaultPauseFlag,
+        WithdrawUnit,
+    };
     use anchor_lang::prelude::Pubkey;
     use drift::math::constants::{ONE_YEAR, QUOTE_PRECISION_U64};
     use drift::math::insurance::if_shares_to_vault_amount as depositor_shares_to_vault_amount;
+    use drift::state::user::User;
 
     #[test]
     fn test_manager_withdraw() {
@@ -747,6 +752,657 @@ mod vault_fcn {
         );
         assert!(!finishing_liquidation);
     }
+
+    #[test]
+    fn test_vd_entry_exit_fees_to_depositors() {
+        let now = 123456789;
+        let vault = &mut Vault::default();
+        vault.exit_fee_bps = 50; // 0.5%
+
+        let mut vault_equity: u64 = 0;
+        let amount: u64 = 100 * QUOTE_PRECISION_U64;
+
+        let vd1 = &mut VaultDepositor::new(
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            now,
+        );
+        vd1.deposit(amount, vault_equity, vault, &mut None, now)
+            .unwrap();
+        vault_equity += amount;
+
+        vault.entry_fee_bps = 100; // 1%
+
+        let vd2 = &mut VaultDepositor::new(
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            now,
+        );
+        vd2.deposit(amount, vault_equity, vault, &mut None, now)
+            .unwrap();
+        vault_equity += amount;
+
+        // entry fee is not minted, so it accrues to vd1
+        assert_eq!(vd2.get_vault_shares(), 99_000_000);
+        assert_eq!(vault.total_shares, 199_000_000);
+        assert_eq!(vault.user_shares, 199_000_000);
+
+        vd2.request_withdraw(
+            vd2.get_vault_shares() as u64,
+            WithdrawUnit::Shares,
+            vault_equity,
+            vault,
+            &mut None,
+            now,
+        )
+        .unwrap();
+        assert_eq!(vd2.last_withdraw_request.value, 99_497_487);
+
+        let (withdraw_amount, _) = vd2.withdraw(vault_equity, vault, &mut None, now).unwrap();
+        assert_eq!(withdraw_amount, 99_000_000); // 497_487 exit fee stays in the vault
+        vault_equity -= withdraw_amount;
+
+        assert_eq!(vault.total_shares, 100_000_000);
+        assert_eq!(vault.user_shares, 100_000_000);
+        assert_eq!(vault_equity, 101_000_000);
+        assert_eq!(vault.manager_total_fee, 0);
+    }
+
+    #[test]
+    fn test_vd_early_exit_fee_to_manager() {
+        let mut now = 123456789;
+        let vault = &mut Vault::default();
+        vault.early_exit_fee_bps = 200; // 2%
+        vault.early_exit_fee_period = 100;
+        vault.load_fees_to_manager = true;
+
+        let amount: u64 = 100 * QUOTE_PRECISION_U64;
+        assert_eq!(vault.calculate_exit_fee(amount, now, now + 100).unwrap(), 0);
+        assert_eq!(
+            vault.calculate_exit_fee(amount, now, now + 50).unwrap(),
+            1_000_000
+        );
+
+        let mut vault_equity: u64 = 0;
+
+        let vd = &mut VaultDepositor::new(
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            now,
+        );
+        vd.deposit(amount, vault_equity, vault, &mut None, now)
+            .unwrap();
+        vault_equity += amount;
+        let first_deposit_ts = now;
+
+        now += 50;
+        vd.deposit(amount, vault_equity, vault, &mut None, now)
+            .unwrap();
+        vault_equity += amount;
+
+        // equal shares deposited at both times
+        assert_eq!(vd.deposit_ts, first_deposit_ts + 25);
+
+        vd.request_withdraw(
+            vd.get_vault_shares() as u64,
+            WithdrawUnit::Shares,
+            vault_equity,
+            vault,
+            &mut None,
+            now,
+        )
+        .unwrap();
+
+        // held for 25 of 100 seconds: 2% * 75% = 1.5% exit fee
+        let (withdraw_amount, _) = vd.withdraw(vault_equity, vault, &mut None, now).unwrap();
+        assert_eq!(withdraw_amount, 197_000_000);
+
+        assert_eq!(vault.user_shares, 0);
+        assert_eq!(vault.total_shares, 3_000_000);
+        assert_eq!(vault.manager_total_fee, 3_000_000);
+    }
+
+    #[test]
+    fn test_early_exit_fee_survives_tokenize() {
+        let mut now = 123456789;
+        let vault = &mut Vault::default();
+        vault.early_exit_fee_bps = 200; // 2%
+        vault.early_exit_fee_period = 100;
+        vault.load_fees_to_manager = true;
+        vault.redeem_period = 0;
+
+        let amount: u64 = 100 * QUOTE_PRECISION_U64;
+        let mut vault_equity: u64 = 0;
+
+        let vd = &mut VaultDepositor::new(
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            now,
+        );
+        vd.deposit(amount, vault_equity, vault, &mut None, now)
+            .unwrap();
+        vault_equity += amount;
+        let deposit_ts = now;
+
+        // tokenizing into a depositor created later carries the holding time
+        now += 10;
+        let tvd = &mut TokenizedVaultDepositor::new(
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            0,
+            0,
+            now,
+        );
+        vd.transfer_shares(
+            tvd,
+            vault,
+            &mut None,
+            vd.get_vault_shares() as u64,
+            WithdrawUnit::Shares,
+            vault_equity,
+            now,
+        )
+        .unwrap();
+        assert_eq!(tvd.deposit_ts, deposit_ts);
+
+        let withdraw_request = &mut TokenizedWithdrawRequest::new(
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            0,
+            0,
+        );
+        let total_supply = tvd.get_vault_shares() as u64;
+        tvd.request_withdraw_tokens(
+            withdraw_request,
+            vault,
+            &mut None,
+            total_supply,
+            vault_equity,
+            total_supply,
+            now,
+        )
+        .unwrap();
+        assert_eq!(withdraw_request.deposit_ts, deposit_ts);
+
+        // held for 50 of 100 seconds: 2% * 50% = 1% exit fee
+        now += 40;
+        let withdraw_amount = withdraw_request
+            .withdraw(vault_equity, vault, &mut None, now)
+            .unwrap();
+        assert_eq!(withdraw_amount, 99_000_000);
+        assert_eq!(vault.manager_total_fee, 1_000_000);
+
+        // redeeming into a fresh depositor also carries the holding time
+        let fresh_vd = &mut VaultDepositor::new(
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            now,
+        );
+        fresh_vd.update_deposit_ts(1_000, tvd.deposit_ts).unwrap();
+        assert_eq!(fresh_vd.deposit_ts, deposit_ts);
+    }
+
+    #[test]
+    fn test_vd_fee_override() {
+        let mut now = 123456789;
+        let vault = &mut Vault::default();
+        vault.profit_share = 200_000; // 20%
+        vault.management_fee = 20_000; // 2%
+        vault.last_fee_update_ts = now;
+
+        let amount: u64 = 100 * QUOTE_PRECISION_U64;
+        let vd = &mut VaultDepositor::new(
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            now,
+        );
+        vd.deposit(amount, 0, vault, &mut None, now).unwrap();
+
+        // overrides can never raise fees above the vault default
+        assert!(vd.set_fee_override(true, 300_000, 0, vault, now).is_err());
+        assert!(vd
+            .set_fee_override(true, 50_000, 30_000, vault, now)
+            .is_err());
+
+        vd.set_fee_override(true, 50_000, 0, vault, now).unwrap();
+
+        // a flat year: the vault wide management fee is rebated from the manager's shares
+        now += ONE_YEAR as i64;
+        let mut vault_equity = amount;
+        vd.settle_fees(None, None, vault_equity, vault, &mut None, now)
+            .unwrap();
+
+        let vd_amount = depositor_shares_to_vault_amount(
+            vd.get_vault_shares(),
+            vault.total_shares,
+            vault_equity,
+        )
+        .unwrap();
+        assert!(vd_amount > 99_900_000);
+        assert!(vault.total_shares - vault.user_shares < 100_000);
+
+        // vault doubles, the depositor pays 5% instead of 20% on profits
+        vault_equity *= 2;
+        let profit = depositor_shares_to_vault_amount(
+            vd.get_vault_shares(),
+            vault.total_shares,
+            vault_equity,
+        )
+        .unwrap()
+            - amount;
+        vd.settle_fees(None, None, vault_equity, vault, &mut None, now)
+            .unwrap();
+        assert_eq!(vd.get_profit_share_fee_paid(), profit / 20);
+
+        // removing the override restores the vault terms
+        vd.set_fee_override(false, 0, 0, vault, now).unwrap();
+        assert_eq!(vd.get_manager_profit_share(vault), 200_000);
+    }
+
+    #[test]
+    fn test_vd_withdraw_after_shutdown() {
+        let now = 123456789;
+        let vault = &mut Vault::default();
+        vault.redeem_period = 3600; // 1 hour
+        vault.last_fee_update_ts = now;
+
+        let amount: u64 = 100 * QUOTE_PRECISION_U64;
+        let vd = &mut VaultDepositor::new(
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            now,
+        );
+        vd.deposit(amount, 0, vault, &mut None, now).unwrap();
+        let vault_equity = amount;
+
+        vault.shutdown().unwrap();
+        assert!(vault.shutdown().is_err());
+        assert_eq!(vault.redeem_period, 0);
+
+        // deposits are blocked for good
+        assert!(vd
+            .deposit(amount, vault_equity, vault, &mut None, now)
+            .is_err());
+        assert!(vault
+            .manager_deposit(&mut None, amount, vault_equity, now)
+            .is_err());
+
+        assert!(vd.check_can_close().is_err());
+        assert!(vault.check_can_close().is_err());
+
+        // the redeem period is waived
+        vd.request_withdraw(
+            vd.get_vault_shares() as u64,
+            WithdrawUnit::Shares,
+            vault_equity,
+            vault,
+            &mut None,
+            now,
+        )
+        .unwrap();
+        let (withdraw_amount, _) = vd.withdraw(vault_equity, vault, &mut None, now).unwrap();
+        assert_eq!(withdraw_amount, amount);
+        assert_eq!(vd.get_vault_shares(), 0);
+        assert_eq!(vault.user_shares, 0);
+
+        // once every share is withdrawn the accounts can be closed
+        vd.check_can_close().unwrap();
+        vault.check_can_close().unwrap();
+    }
+
+    #[test]
+    fn test_vault_pause() {
+        let mut now = 123456789;
+        let vault = &mut Vault::default();
+        let paused = VaultPauseFlag::Deposit as u8 | VaultPauseFlag::Withdraw as u8;
+
+        assert!(vault
+            .update_paused(0b00100000, Pubkey::default(), now)
+            .is_err());
+
+        vault.update_paused(paused, Pubkey::default(), now).unwrap();
+        assert!(vault
+            .validate_not_paused(VaultPauseFlag::Deposit, now)
+            .is_err());
+        assert!(vault.is_paused(VaultPauseFlag::Withdraw, now));
+        assert!(!vault.is_paused(VaultPauseFlag::RequestWithdraw, now));
+
+        // withdraw pauses lapse on their own
+        now += MAX_WITHDRAW_PAUSE;
+        assert!(!vault.is_paused(VaultPauseFlag::Withdraw, now));
+        assert!(vault.is_paused(VaultPauseFlag::Deposit, now));
+
+        // and can't be renewed until the cooldown passes
+        assert!(vault.update_paused(paused, Pubkey::default(), now).is_err());
+        now += MAX_WITHDRAW_PAUSE;
+        vault.update_paused(paused, Pubkey::default(), now).unwrap();
+        assert!(vault.is_paused(VaultPauseFlag::Withdraw, now));
+
+        // unpausing early starts the cooldown
+        now += 1;
+        vault
+            .update_paused(VaultPauseFlag::Deposit as u8, Pubkey::default(), now)
+            .unwrap();
+        assert!(!vault.is_paused(VaultPauseFlag::Withdraw, now));
+        assert!(vault
+            .update_paused(paused, Pubkey::default(), now + 1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_vd_tiered_management_fee() {
+        let mut now = 123456789;
+        let vault = &mut Vault::default();
+        vault.management_fee = 20_000; // 2%
+                                       // 1.5% above $1M, 1% above $10M
+        vault.management_fee_tier_thresholds = [
+            1_000_000 * QUOTE_PRECISION_U64,
+            10_000_000 * QUOTE_PRECISION_U64,
+        ];
+        vault.management_fee_tier_bps = [150, 100];
+        vault.last_fee_update_ts = now;
+
+        assert_eq!(
+            vault
+                .get_management_fee(500_000 * QUOTE_PRECISION_U64)
+                .unwrap(),
+            20_000
+        );
+        assert_eq!(
+            vault
+                .get_management_fee(5_000_000 * QUOTE_PRECISION_U64)
+                .unwrap(),
+            16_000
+        );
+        assert_eq!(
+            vault
+                .get_management_fee(20_000_000 * QUOTE_PRECISION_U64)
+                .unwrap(),
+            12_750
+        );
+
+        let amount: u64 = 5_000_000 * QUOTE_PRECISION_U64;
+        let vd = &mut VaultDepositor::new(
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            now,
+        );
+        vd.deposit(amount, 0, vault, &mut None, now).unwrap();
+
+        // a flat year pays the blended 1.6% instead of 2%
+        now += ONE_YEAR as i64;
+        vault.apply_fee(&mut None, amount, now).unwrap();
+
+        let vd_amount =
+            depositor_shares_to_vault_amount(vd.get_vault_shares(), vault.total_shares, amount)
+                .unwrap();
+        assert!(vd_amount > 4_919_990 * QUOTE_PRECISION_U64);
+        assert!(vd_amount < 4_920_000 * QUOTE_PRECISION_U64);
+        assert_eq!(vault.manager_total_fee, 80_000 * QUOTE_PRECISION_U64 as i64);
+    }
+
+    #[test]
+    fn test_first_deposit_donation_attack_unprofitable() {
+        let now = 0;
+        let vault = &mut Vault::default();
+
+        // the first deposit must leave the depositor something after the dead shares are locked
+        let vd = &mut VaultDepositor::new(
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            now,
+        );
+        let empty_vault = &mut Vault::default();
+        assert!(empty_vault.is_first_deposit());
+        vd.deposit(1_000, 0, empty_vault, &mut None, now).unwrap();
+        assert!(vd.lock_dead_shares(empty_vault).is_err());
+
+        // attacker mints the first shares with a dust deposit
+        let attacker = &mut VaultDepositor::new(
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            now,
+        );
+        let attacker_deposit = 1_001;
+        attacker
+            .deposit(attacker_deposit, 0, vault, &mut None, now)
+            .unwrap();
+        attacker.lock_dead_shares(vault).unwrap();
+        assert_eq!(attacker.get_vault_shares(), 1);
+        assert_eq!(vault.user_shares, 1_001);
+        assert_eq!(vault.total_shares, 1_001);
+
+        // then donates to the drift user to inflate the share price
+        let donation = 1_000 * QUOTE_PRECISION_U64;
+        let mut vault_equity = attacker_deposit + donation;
+        assert!(!vault.is_first_deposit());
+
+        let victim = &mut VaultDepositor::new(
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            now,
+        );
+        let victim_deposit = 100 * QUOTE_PRECISION_U64;
+        victim
+            .deposit(victim_deposit, vault_equity, vault, &mut None, now)
+            .unwrap();
+        vault_equity += victim_deposit;
+        assert_eq!(victim.get_vault_shares(), 100);
+
+        // the dead shares hold most of the donation, so the attacker loses far more than the victim
+        let attacker_amount = depositor_shares_to_vault_amount(
+            attacker.get_vault_shares(),
+            vault.total_shares,
+            vault_equity,
+        )
+        .unwrap();
+        let victim_amount = depositor_shares_to_vault_amount(
+            victim.get_vault_shares(),
+            vault.total_shares,
+            vault_equity,
+        )
+        .unwrap();
+        assert_eq!(attacker_amount, 999_092);
+        assert_eq!(victim_amount, 99_909_264);
+        assert!(attacker_amount < attacker_deposit + donation);
+        assert!(victim_deposit - victim_amount < attacker_deposit + donation - attacker_amount);
+
+        // a donation made before any shares exist still locks the dead shares on the first deposit
+        let donated_vault = &mut Vault::default();
+        assert!(donated_vault.is_first_deposit());
+        let first = &mut VaultDepositor::new(
+            Pubkey::default(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            now,
+        );
+        first
+            .deposit(attacker_deposit, donation, donated_vault, &mut None, now)
+            .unwrap();
+        first.lock_dead_shares(donated_vault).unwrap();
+        assert_eq!(first.get_vault_shares(), 1);
+        assert_eq!(donated_vault.user_shares, 1_001);
+    }
+
+    #[test]
+    fn test_manager_first_deposit_locks_dead_shares() {
+        let now = 0;
+        let vault = &mut Vault::default();
+        let amount = 100 * QUOTE_PRECISION_U64;
+
+        assert!(vault.is_first_deposit());
+        vault.manager_deposit(&mut None, amount, 0, now).unwrap();
+        vault.lock_manager_dead_shares(&mut None).unwrap();
+
+        assert_eq!(vault.total_shares, amount as u128);
+        assert_eq!(vault.user_shares, 1_000);
+        assert_eq!(
+            vault.get_manager_shares(&mut None).unwrap(),
+            amount as u128 - 1_000
+        );
+
+        // the dead shares alone don't keep a shut down vault open
+        vault.shutdown().unwrap();
+        assert!(vault.check_can_close().is_err());
+        let vault_equity = amount;
+        vault
+            .manager_request_withdraw(
+                &mut None,
+                amount - 1_000,
+                WithdrawUnit::Shares,
+                vault_equity,
+                now,
+            )
+            .unwrap();
+        vault
+            .manager_withdraw(&mut None, vault_equity, now)
+            .unwrap();
+        vault.check_can_close().unwrap();
+
+        // a depositor's leftover shares are not dust
+        vault.user_shares += 5;
+        vault.total_shares += 5;
+        assert!(vault.check_can_close().is_err());
+    }
+
+    #[test]
+    fn test_close_vault_after_rebase() {
+        let now = 0;
+        let vault = &mut Vault::default();
+        let amount = 100 * QUOTE_PRECISION_U64;
+
+        vault.manager_deposit(&mut None, amount, 0, now).unwrap();
+        vault.lock_manager_dead_shares(&mut None).unwrap();
+        vault.shutdown().unwrap();
+
+        // the vault loses almost everything and its shares are rebased, dead shares included
+        let vault_equity = amount / 1_000;
+        vault.apply_rebase(&mut None, vault_equity).unwrap();
+        assert_eq!(vault.shares_base, 2);
+        assert_eq!(vault.user_shares, 10);
+        assert_eq!(vault.dead_shares(), 10);
+
+        let manager_shares = vault.get_manager_shares(&mut None).unwrap();
+        vault
+            .manager_request_withdraw(
+                &mut None,
+                manager_shares as u64,
+                WithdrawUnit::Shares,
+                vault_equity,
+                now,
+            )
+            .unwrap();
+        vault
+            .manager_withdraw(&mut None, vault_equity, now)
+            .unwrap();
+        vault.check_can_close().unwrap();
+    }
+
+    #[test]
+    fn test_deposit_for_creates_beneficiary_depositor() {
+        let mut now = 123456789;
+        let vault = &mut Vault {
+            pubkey: Pubkey::new_unique(),
+            manager: Pubkey::new_unique(),
+            ..Vault::default()
+        };
+        let mut vault_equity: u64 = 0;
+        let amount: u64 = 100 * QUOTE_PRECISION_U64;
+
+        // a third party creates the beneficiary's depositor in a permissionless vault and funds it
+        let authority = Pubkey::new_unique();
+        let payer = Pubkey::new_unique();
+        let vd = &mut VaultDepositor::new_for(vault, Pubkey::new_unique(), authority, &payer, now)
+            .unwrap();
+        assert_eq!(vd.vault, vault.pubkey);
+        assert_eq!(vd.authority, authority);
+        assert_eq!(vd.deposit_ts, now);
+
+        vd.deposit(amount, vault_equity, vault, &mut None, now)
+            .unwrap();
+        vault_equity += amount;
+        now += 100;
+
+        assert_eq!(vd.get_vault_shares(), amount as u128);
+        assert_eq!(vd.get_vault_shares(), vault.user_shares);
+        assert_eq!(vd.total_deposits, amount);
+        assert_eq!(vd.net_deposits, amount as i64);
+
+        // the beneficiary can take out what the third party put in
+        vd.request_withdraw(
+            amount,
+            WithdrawUnit::Token,
+            vault_equity,
+            vault,
+            &mut None,
+            now,
+        )
+        .unwrap();
+        let (withdraw_amount, _) = vd.withdraw(vault_equity, vault, &mut None, now).unwrap();
+        assert_eq!(withdraw_amount, amount);
+        assert_eq!(vd.get_vault_shares(), 0);
+    }
+
+    #[test]
+    fn test_deposit_for_permissioned_vault() {
+        let manager = Pubkey::new_unique();
+        let vault = Vault {
+            pubkey: Pubkey::new_unique(),
+            manager,
+            permissioned: true,
+            ..Vault::default()
+        };
+        let authority = Pubkey::new_unique();
+
+        // only the manager can let a depositor in, even one another payer funds
+        let payer = Pubkey::new_unique();
+        assert!(
+            VaultDepositor::new_for(&vault, Pubkey::new_unique(), authority, &payer, 0).is_err()
+        );
+        assert!(
+            VaultDepositor::new_for(&vault, Pubkey::new_unique(), authority, &authority, 0)
+                .is_err()
+        );
+
+        let vd =
+            VaultDepositor::new_for(&vault, Pubkey::new_unique(), authority, &manager, 0).unwrap();
+        assert_eq!(vd.vault, vault.pubkey);
+        assert_eq!(vd.authority, authority);
+    }
+
+    #[test]
+    fn test_check_sub_account_flat() {
+        let vault = Vault::default();
+        let mut sub_account = User {
+            sub_account_id: 1,
+            ..User::default()
+        };
+
+        vault.check_sub_account_flat(&sub_account).unwrap();
+
+        // the primary user may keep a balance in the vault's spot market, a sub-account may not
+        sub_account.spot_positions[0].market_index = vault.spot_market_index;
+        sub_account.spot_positions[0].scaled_balance = 1;
+        vault.check_positions_flat(&sub_account).unwrap();
+        assert!(vault.check_sub_account_flat(&sub_account).is_err());
+
+        sub_account.spot_positions[0].scaled_balance = 0;
+        vault.check_sub_account_flat(&sub_account).unwrap();
+    }
 }
 
 #[cfg(test)]
@@ -758,7 +1414,7 @@ mod vault_v1_fcn {
     use drift::math::insurance::if_shares_to_vault_amount as depositor_shares_to_vault_amount;
 
     use crate::state::{Vault, VaultProtocol};
-    use crate::{VaultDepositor, WithdrawUnit};
+    use crate::{VaultDepositor, VaultPauseFlag, VaultPauser, WithdrawUnit};
 
     const USER_SHARES_AFTER_1500_BPS_FEE: u64 = 99_850_025;
 
@@ -1682,4 +2338,118 @@ mod vault_v1_fcn {
             vault_equity - 1
         );
     }
+
+    #[test]
+    fn test_vault_pause_ownership() {
+        let mut now = 123456789;
+        let vault = &mut Vault::default();
+        let vp = RefCell::new(VaultProtocol::default());
+        let deposit = VaultPauseFlag::Deposit as u8;
+        let tokenize = VaultPauseFlag::Tokenize as u8;
+
+        // the protocol pauses deposits, the manager can't lift it
+        vault
+            .update_paused_by(
+                VaultPauser::Protocol,
+                deposit,
+                &mut Some(vp.borrow_mut()),
+                Pubkey::default(),
+                now,
+            )
+            .unwrap();
+        assert_eq!(vp.borrow().protocol_paused, deposit);
+        assert!(vault
+            .update_paused_by(
+                VaultPauser::Manager,
+                0,
+                &mut Some(vp.borrow_mut()),
+                Pubkey::default(),
+                now
+            )
+            .is_err());
+
+        // but can add and clear its own flags
+        vault
+            .update_paused_by(
+                VaultPauser::Manager,
+                deposit | tokenize,
+                &mut Some(vp.borrow_mut()),
+                Pubkey::default(),
+                now,
+            )
+            .unwrap();
+        vault
+            .update_paused_by(
+                VaultPauser::Manager,
+                deposit,
+                &mut Some(vp.borrow_mut()),
+                Pubkey::default(),
+                now,
+            )
+            .unwrap();
+        assert!(!vault.is_paused(VaultPauseFlag::Tokenize, now));
+
+        // the risk officer can only add flags
+        vault
+            .update_paused_by(
+                VaultPauser::RiskOfficer,
+                deposit | tokenize,
+                &mut Some(vp.borrow_mut()),
+                Pubkey::default(),
+                now,
+            )
+            .unwrap();
+        assert!(vault
+            .update_paused_by(
+                VaultPauser::RiskOfficer,
+                deposit,
+                &mut Some(vp.borrow_mut()),
+                Pubkey::default(),
+                now
+            )
+            .is_err());
+
+        // a tripped breaker's pause outlives the protocol's, and only resetting the breaker lifts it
+        vault.set_breaker_paused(Pubkey::default(), now).unwrap();
+        vault
+            .update_paused_by(
+                VaultPauser::Protocol,
+                deposit,
+                &mut Some(vp.borrow_mut()),
+                Pubkey::default(),
+                now,
+            )
+            .unwrap();
+        assert_eq!(vp.borrow().protocol_paused, deposit);
+        for pauser in [VaultPauser::Manager, VaultPauser::Protocol] {
+            assert!(vault
+                .update_paused_by(
+                    pauser,
+                    0,
+                    &mut Some(vp.borrow_mut()),
+                    Pubkey::default(),
+                    now
+                )
+                .is_err());
+        }
+
+        // resetting the breaker keeps the protocol's pause
+        now += 1;
+        vault
+            .clear_breaker_paused(&Some(vp.borrow_mut()), Pubkey::default(), now)
+            .unwrap();
+        assert!(vault.is_paused(VaultPauseFlag::Deposit, now));
+        vault
+            .update_paused_by(
+                VaultPauser::Protocol,
+                0,
+                &mut Some(vp.borrow_mut()),
+                Pubkey::default(),
+                now,
+            )
+            .unwrap();
+        assert!(!vault.is_paused(VaultPauseFlag::Deposit, now));
+        assert_eq!(vp.borrow().protocol_paused, 0);
+        assert_eq!(vault.breaker_paused, 0);
+    }
 }
@@ -0,0 +1,50 @@
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::Instruction;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::InstructionData;
+
+use crate::WithdrawUnit;
+
+pub trait VaultDepositCPI {
+    fn vault_deposit(&self, amount: u64, min_shares_out: Option<u128>) -> Result<()>;
+}
+
+pub trait VaultRequestWithdrawCPI {
+    fn vault_request_withdraw(
+        &self,
+        withdraw_amount: u64,
+        withdraw_unit: WithdrawUnit,
+    ) -> Result<()>;
+}
+
+pub trait VaultWithdrawCPI {
+    fn vault_withdraw(&self, min_amount_out: Option<u64>) -> Result<()>;
+}
+
+/// Invokes one of this program's instructions with a vault pda as signer. `crate::cpi` only exists with the cpi
+/// feature, so the instruction is built from the generated `crate::accounts` and `crate::instruction` structs.
+pub fn invoke_vault_instruction<'info>(
+    accounts: &impl ToAccountMetas,
+    data: &impl InstructionData,
+    mut account_infos: Vec<AccountInfo<'info>>,
+    remaining_accounts: &[AccountInfo<'info>],
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mut account_metas = accounts.to_account_metas(None);
+    account_metas.extend(
+        remaining_accounts
+            .iter()
+            .flat_map(|account_info| account_info.to_account_metas(None)),
+    );
+
+    let instruction = Instruction {
+        program_id: crate::ID,
+        accounts: account_metas,
+        data: data.data(),
+    };
+
+    account_infos.extend_from_slice(remaining_accounts);
+    invoke_signed(&instruction, &account_infos, signer_seeds)?;
+
+    Ok(())
+}
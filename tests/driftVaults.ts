@@ -58,6 +58,8 @@ import {
 	getTokenizedVaultMintAddressSync,
 	getVaultAddressSync,
 	getVaultDepositorAddressSync,
+	getVaultDepositorTransferAddressSync,
+	getDepositorOperatorAddressSync,
 	encodeName,
 	DriftVaults,
 	VaultProtocolParams,
@@ -424,6 +426,128 @@ describe('driftVaults', () => {
 		}
 	});
 
+	it('Transfer Vault Depositor', async () => {
+		const vaultAccount = await program.account.vault.fetch(vault);
+		const vaultDepositor = getVaultDepositorAddressSync(
+			program.programId,
+			vault,
+			vd2.publicKey
+		);
+		const newVaultDepositor = getVaultDepositorAddressSync(
+			program.programId,
+			vault,
+			_delegate.publicKey
+		);
+		const vaultDepositorTransfer = getVaultDepositorTransferAddressSync(
+			program.programId,
+			vaultDepositor
+		);
+		const depositorOperator = getDepositorOperatorAddressSync(
+			program.programId,
+			vaultDepositor
+		);
+		const remainingAccounts = vd2Client.driftClient.getRemainingAccounts({
+			userAccounts: [],
+			writableSpotMarketIndexes: [0],
+		});
+		const driftAccounts = {
+			driftUser: vaultAccount.user,
+			driftUserStats: vaultAccount.userStats,
+			driftState: await adminClient.getStatePublicKey(),
+		};
+
+		const depositAmount = new BN(100).mul(QUOTE_PRECISION);
+		await vd2Client.program.methods
+			.deposit(depositAmount)
+			.accounts({
+				userTokenAccount: vd2UserUSDCAccount,
+				vault,
+				vaultDepositor,
+				vaultTokenAccount: vaultAccount.tokenAccount,
+				driftSpotMarketVault: adminClient.getSpotMarketAccount(0).vault,
+				driftProgram: adminClient.program.programId,
+				...driftAccounts,
+			})
+			.remainingAccounts(remainingAccounts)
+			.rpc();
+
+		// vd2 names an operator, which the transfer closes
+		await vd2Client.program.methods
+			.initializeDepositorOperator({
+				operator: Keypair.generate().publicKey,
+				withdrawDestination: PublicKey.default,
+			})
+			.accounts({
+				vault,
+				vaultDepositor,
+				depositorOperator,
+				authority: vd2.publicKey,
+				payer: vd2.publicKey,
+			})
+			.rpc();
+
+		await vd2Client.program.methods
+			.requestVaultDepositorTransfer(_delegate.publicKey)
+			.accounts({
+				vaultDepositor,
+				vaultDepositorTransfer,
+				authority: vd2.publicKey,
+			})
+			.rpc();
+
+		// a pending withdraw request moves to the new authority
+		await vd2Client.program.methods
+			.requestWithdraw(depositAmount.divn(2), WithdrawUnit.TOKEN)
+			.accounts({ vault, vaultDepositor, ...driftAccounts })
+			.remainingAccounts(remainingAccounts)
+			.rpc();
+
+		const before = await program.account.vaultDepositor.fetch(vaultDepositor);
+		const txSig = await delegateClient.program.methods
+			.acceptVaultDepositorTransfer()
+			.accounts({
+				vault,
+				vaultDepositor,
+				vaultDepositorTransfer,
+				newVaultDepositor,
+				depositorOperator,
+				authority: vd2.publicKey,
+				newAuthority: _delegate.publicKey,
+				manager: null,
+			})
+			.rpc();
+		await printTxLogs(provider.connection, txSig);
+
+		const after = await program.account.vaultDepositor.fetch(newVaultDepositor);
+		assert(after.authority.equals(_delegate.publicKey));
+		assert(after.vaultShares.eq(before.vaultShares));
+		assert(after.netDeposits.eq(before.netDeposits));
+		assert(after.totalDeposits.eq(before.totalDeposits));
+		assert(
+			after.lastWithdrawRequest.shares.eq(before.lastWithdrawRequest.shares)
+		);
+		assert(after.lastWithdrawRequest.value.eq(before.lastWithdrawRequest.value));
+		assert(
+			after.cumulativeProfitShareAmount.eq(before.cumulativeProfitShareAmount)
+		);
+		assert(after.referrer.equals(PublicKey.default));
+
+		assert(
+			(await program.account.vaultDepositor.fetchNullable(vaultDepositor)) ===
+				null
+		);
+		assert(
+			(await program.account.vaultDepositorTransfer.fetchNullable(
+				vaultDepositorTransfer
+			)) === null
+		);
+		assert(
+			(await program.account.depositorOperator.fetchNullable(
+				depositorOperator
+			)) === null
+		);
+	});
+
 	it('Update Delegate', async () => {
 		const vaultAccount = await program.account.vault.fetch(vault);
 		const delegateKeyPair = Keypair.generate();
@@ -29,6 +29,32 @@ export function getVaultDepositorAddressSync(
 	)[0];
 }
 
+export function getVaultDepositorTransferAddressSync(
+	programId: PublicKey,
+	vaultDepositor: PublicKey
+): PublicKey {
+	return PublicKey.findProgramAddressSync(
+		[
+			Buffer.from(anchor.utils.bytes.utf8.encode('vault_depositor_transfer')),
+			vaultDepositor.toBuffer(),
+		],
+		programId
+	)[0];
+}
+
+export function getDepositorOperatorAddressSync(
+	programId: PublicKey,
+	vaultDepositor: PublicKey
+): PublicKey {
+	return PublicKey.findProgramAddressSync(
+		[
+			Buffer.from(anchor.utils.bytes.utf8.encode('depositor_operator')),
+			vaultDepositor.toBuffer(),
+		],
+		programId
+	)[0];
+}
+
 export function getTokenVaultAddressSync(
 	programId: PublicKey,
 	vault: PublicKey
@@ -70,6 +96,19 @@ export function getVaultProtocolAddressSync(
 	)[0];
 }
 
+export function getVaultRolesAddressSync(
+	programId: PublicKey,
+	vault: PublicKey
+): PublicKey {
+	return PublicKey.findProgramAddressSync(
+		[
+			Buffer.from(anchor.utils.bytes.utf8.encode('vault_roles')),
+			vault.toBuffer(),
+		],
+		programId
+	)[0];
+}
+
 export function getTokenizedVaultAddressSync(
 	programId: PublicKey,
 	vault: PublicKey,
@@ -1918,8 +1918,8 @@ export type DriftVaults = {
 					{
 						name: 'totalShares';
 						docs: [
-							'The sum of all shares: deposits from users, manager deposits, manager profit/fee, and protocol profit/fee.',
-							'The manager deposits are total_shares - user_shares - protocol_profit_and_fee_shares.'
+							'The sum of all shares: deposits from users, manager deposits, manager profit/fee, referrer profit/fee, and protocol profit/fee.',
+							'The manager deposits are total_shares - user_shares - referrer_shares - protocol_profit_and_fee_shares.'
 						];
 						type: 'u128';
 					},
@@ -2034,9 +2034,18 @@ export type DriftVaults = {
 					{
 						name: 'hurdleRate';
 						docs: [
-							'Vault manager only collect incentive fees during periods when returns are higher than this amount: PERCENTAGE_PRECISION'
+							'Vault manager only collect incentive fees during periods when returns are higher than this amount: PERCENTAGE_PRECISION.',
+							'Unused, it can only be set to zero'
 						];
-						type: 'u32';
+						type: 'u16';
+					},
+					{
+						name: 'minManagerShareBps';
+						docs: [
+							'The share of total vault shares the manager must keep as capital in its [`ManagerPosition`] while there are',
+							'depositors, in bps. Zero for no minimum'
+						];
+						type: 'u16';
 					},
 					{
 						name: 'spotMarketIndex';
@@ -2061,16 +2070,107 @@ export type DriftVaults = {
 						type: 'bool';
 					},
 					{
-						name: 'padding1';
+						name: 'companions';
+						docs: [
+							'Bitmask of [`VaultCompanion`]s, the optional accounts the vault has'
+						];
+						type: 'u8';
+					},
+					{
+						name: 'paused';
+						docs: [
+							'Bitmask of [`VaultPauseFlag`]s for the vault actions currently halted'
+						];
+						type: 'u8';
+					},
+					{
+						name: 'breakerPaused';
+						docs: [
+							'Bitmask of the [`VaultPauseFlag`]s a tripped circuit breaker set. They are cleared when the breaker is reset'
+						];
+						type: 'u8';
+					},
+					{
+						name: 'referralFeeShare';
+						docs: [
+							'Percentage of the manager profit share and management fee paid by referred depositors that goes to their',
+							'referrer: PERCENTAGE_PRECISION'
+						];
+						type: 'u32';
+					},
+					{
+						name: 'referrerShares';
+						docs: [
+							'The shares from profit share and management fee owed to all referrers'
+						];
+						type: 'u128';
+					},
+					{
+						name: 'earlyExitFeePeriod';
+						docs: [
+							'Seconds after a deposit over which the early exit fee decays linearly to zero'
+						];
+						type: 'u32';
+					},
+					{
+						name: 'entryFeeBps';
+						docs: ['Fee charged on deposits, in bps of the deposit amount'];
+						type: 'u16';
+					},
+					{
+						name: 'exitFeeBps';
+						docs: ['Fee charged on withdraws, in bps of the withdraw amount'];
+						type: 'u16';
+					},
+					{
+						name: 'earlyExitFeeBps';
+						docs: [
+							'Additional exit fee charged on withdraws within early_exit_fee_period of depositing, in bps'
+						];
+						type: 'u16';
+					},
+					{
+						name: 'loadFeesToManager';
+						docs: [
+							'Whether entry and exit fees are paid to the manager as shares. If false they are left in the vault for',
+							'the remaining depositors.'
+						];
+						type: 'bool';
+					},
+					{
+						name: 'shutdown';
+						docs: [
+							'Whether the vault is winding down. Deposits are blocked, the drift user is reduce only and there is no',
+							'redeem_period. Depositors can be withdrawn by anyone once positions are flat.'
+						];
+						type: 'bool';
+					},
+					{
+						name: 'managementFeeTierBps';
+						docs: [
+							'The annual management fee charged on depositor equity above each of management_fee_tier_thresholds, in bps.',
+							'Equity below the first threshold is charged management_fee.'
+						];
 						type: {
-							array: ['u8', 7];
+							array: ['u16', 2];
 						};
 					},
 					{
-						name: 'padding';
+						name: 'managementFeeTierThresholds';
+						docs: [
+							'Depositor equity at which each management fee tier starts. Zero thresholds are unused tiers.'
+						];
 						type: {
-							array: ['u64', 7];
+							array: ['u64', 2];
 						};
+					},
+					{
+						name: 'withdrawPauseEndTs';
+						docs: [
+							"When the current or last withdraw pause lapses. Withdraws can't be paused again until MAX_WITHDRAW_PAUSE",
+							'after this'
+						];
+						type: 'i64';
 					}
 				];
 			};
@@ -4681,8 +4781,8 @@ export const IDL: DriftVaults = {
 					{
 						name: 'totalShares',
 						docs: [
-							'The sum of all shares: deposits from users, manager deposits, manager profit/fee, and protocol profit/fee.',
-							'The manager deposits are total_shares - user_shares - protocol_profit_and_fee_shares.',
+							'The sum of all shares: deposits from users, manager deposits, manager profit/fee, referrer profit/fee, and protocol profit/fee.',
+							'The manager deposits are total_shares - user_shares - referrer_shares - protocol_profit_and_fee_shares.',
 						],
 						type: 'u128',
 					},
@@ -4797,9 +4897,18 @@ export const IDL: DriftVaults = {
 					{
 						name: 'hurdleRate',
 						docs: [
-							'Vault manager only collect incentive fees during periods when returns are higher than this amount: PERCENTAGE_PRECISION',
+							'Vault manager only collect incentive fees during periods when returns are higher than this amount: PERCENTAGE_PRECISION.',
+							'Unused, it can only be set to zero',
 						],
-						type: 'u32',
+						type: 'u16',
+					},
+					{
+						name: 'minManagerShareBps',
+						docs: [
+							'The share of total vault shares the manager must keep as capital in its [`ManagerPosition`] while there are',
+							'depositors, in bps. Zero for no minimum',
+						],
+						type: 'u16',
 					},
 					{
 						name: 'spotMarketIndex',
@@ -4824,17 +4933,108 @@ export const IDL: DriftVaults = {
 						type: 'bool',
 					},
 					{
-						name: 'padding1',
+						name: 'companions',
+						docs: [
+							'Bitmask of [`VaultCompanion`]s, the optional accounts the vault has',
+						],
+						type: 'u8',
+					},
+					{
+						name: 'paused',
+						docs: [
+							'Bitmask of [`VaultPauseFlag`]s for the vault actions currently halted',
+						],
+						type: 'u8',
+					},
+					{
+						name: 'breakerPaused',
+						docs: [
+							'Bitmask of the [`VaultPauseFlag`]s a tripped circuit breaker set. They are cleared when the breaker is reset',
+						],
+						type: 'u8',
+					},
+					{
+						name: 'referralFeeShare',
+						docs: [
+							'Percentage of the manager profit share and management fee paid by referred depositors that goes to their',
+							'referrer: PERCENTAGE_PRECISION',
+						],
+						type: 'u32',
+					},
+					{
+						name: 'referrerShares',
+						docs: [
+							'The shares from profit share and management fee owed to all referrers',
+						],
+						type: 'u128',
+					},
+					{
+						name: 'earlyExitFeePeriod',
+						docs: [
+							'Seconds after a deposit over which the early exit fee decays linearly to zero',
+						],
+						type: 'u32',
+					},
+					{
+						name: 'entryFeeBps',
+						docs: ['Fee charged on deposits, in bps of the deposit amount'],
+						type: 'u16',
+					},
+					{
+						name: 'exitFeeBps',
+						docs: ['Fee charged on withdraws, in bps of the withdraw amount'],
+						type: 'u16',
+					},
+					{
+						name: 'earlyExitFeeBps',
+						docs: [
+							'Additional exit fee charged on withdraws within early_exit_fee_period of depositing, in bps',
+						],
+						type: 'u16',
+					},
+					{
+						name: 'loadFeesToManager',
+						docs: [
+							'Whether entry and exit fees are paid to the manager as shares. If false they are left in the vault for',
+							'the remaining depositors.',
+						],
+						type: 'bool',
+					},
+					{
+						name: 'shutdown',
+						docs: [
+							'Whether the vault is winding down. Deposits are blocked, the drift user is reduce only and there is no',
+							'redeem_period. Depositors can be withdrawn by anyone once positions are flat.',
+						],
+						type: 'bool',
+					},
+					{
+						name: 'managementFeeTierBps',
+						docs: [
+							'The annual management fee charged on depositor equity above each of management_fee_tier_thresholds, in bps.',
+							'Equity below the first threshold is charged management_fee.',
+						],
 						type: {
-							array: ['u8', 7],
+							array: ['u16', 2],
 						},
 					},
 					{
-						name: 'padding',
+						name: 'managementFeeTierThresholds',
+						docs: [
+							'Depositor equity at which each management fee tier starts. Zero thresholds are unused tiers.',
+						],
 						type: {
-							array: ['u64', 7],
+							array: ['u64', 2],
 						},
 					},
+					{
+						name: 'withdrawPauseEndTs',
+						docs: [
+							"When the current or last withdraw pause lapses. Withdraws can't be paused again until MAX_WITHDRAW_PAUSE",
+							'after this',
+						],
+						type: 'i64',
+					},
 				],
 			},
 		},
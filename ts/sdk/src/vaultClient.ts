@@ -21,6 +21,7 @@ import {
 	getVaultAddressSync,
 	getVaultDepositorAddressSync,
 	getVaultProtocolAddressSync,
+	getVaultRolesAddressSync,
 } from './addresses';
 import {
 	AddressLookupTableAccount,
@@ -145,6 +146,10 @@ export class VaultClient {
 		return getVaultProtocolAddressSync(this.program.programId, vault);
 	}
 
+	public getVaultRolesAddress(vault: PublicKey): PublicKey {
+		return getVaultRolesAddressSync(this.program.programId, vault);
+	}
+
 	public async getVaultProtocol(
 		vaultProtocol: PublicKey
 	): Promise<VaultProtocol> {
@@ -1703,6 +1708,18 @@ export class VaultClient {
 			userAccounts: [user.getUserAccount()],
 			writableSpotMarketIndexes: [vaultAccount.spotMarketIndex],
 		});
+		// VaultRoles go after the markets and before any VaultProtocol
+		const vaultRoles = this.getVaultRolesAddress(vaultDepositorAccount.vault);
+		const vaultRolesExists = await this.driftClient.connection.getAccountInfo(
+			vaultRoles
+		);
+		if (vaultRolesExists) {
+			remainingAccounts.push({
+				pubkey: vaultRoles,
+				isSigner: false,
+				isWritable: false,
+			});
+		}
 		if (vaultAccount.vaultProtocol) {
 			const vaultProtocol = this.getVaultProtocolAddress(
 				vaultDepositorAccount.vault